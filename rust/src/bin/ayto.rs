@@ -5,199 +5,386 @@
 //! This module serves as a CLI to the simulation/calculation/evaluation/reporting and comparison
 //! code.
 
-use ayto::game::cache::{CacheModeArg, CacheModeFallback, CacheSpec};
+use ayto::game::cache::{
+    remove_superseded_caches, CachableSpec, CacheMode, CacheModeArg, CacheModeFallback, CacheSpec,
+};
 use ayto::game::cache_report::show_caches;
+use ayto::game::format::Format;
+use ayto::game::live::live;
 use ayto::game::parse::GameParse;
+use ayto::game::serve::serve;
 
+use anyhow::Result;
+use ayto::collector::{CollectorSpec, Registry};
 use ayto::dump_mode::DumpMode;
 use ayto::ignore_ops::IgnoreOps;
 use ayto::iterstate::IterState;
+use ayto::lint::{lint_file, print_diagnostics, LintFormat, Severity};
 use ayto::progressbar::ProgressBar;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use std::time::Instant;
+use ayto::watch::watch_file;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Specifies the complete CLI
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// The path to the file to read as game-config
-    yaml_path: PathBuf,
+    /// The path to the file to read as game-config (not needed by all subcommands). Besides yaml,
+    /// json and toml are supported (detected by the extension).
+    yaml_path: Option<PathBuf>,
 
     /// subcommands of the binary
     #[command(subcommand)]
     cmd: Commands,
 }
 
+/// The arguments of the `sim` subcommand
+#[derive(Args, Debug)]
+struct SimArgs {
+    /// allows to disable writing .dot files
+    #[arg(long = "no-tree-output", action)]
+    no_tree_output: bool,
+
+    // TODO:(later) make possible to specify multiple values if makes sense (multiple ignoreOps
+    // available)
+    /// specify which operations/events shall be ignored
+    #[arg(long = "ignore", value_enum, default_value_t = IgnoreOps::Nothing)]
+    ignore: IgnoreOps,
+
+    // #[arg(short = 'c', long = "color")]
+    // colored: bool,
+    /// option to transpose the generated tables (swaps set_a with set_b)
+    /// swapping via the config is not possible as it matters for the maps on which side
+    /// duplicates are possible to store
+    #[arg(long = "transpose")]
+    transpose_tabs: bool,
+
+    /// base-path where to write the outputs (.dot, .json)
+    #[arg(short = 'o', long = "output")]
+    stem: PathBuf,
+
+    /// whether and if so how to dump all the remaining possible solutions
+    #[arg(
+        long = "dump",
+        help = "dump all combinations ({winning,all}{nums,names} or their ranks) in the end of the simulation"
+    )]
+    dump: Option<DumpMode>,
+
+    /// whether to print everything regarding the ruleset_data
+    #[arg(
+        long = "full",
+        help = "print all probabilities instead of just the topX below the tables"
+    )]
+    full: bool,
+
+    /// feature-flag for enabling the caching functionality
+    #[arg(
+        long = "allow-cache",
+        help = "Allow caching to be used in principle. Whether it will be used depends on the config and/or the use-cache flag"
+    )]
+    allow_cache: bool,
+
+    /// whether a cache shall be generated
+    #[arg(
+        long = "gen-cache",
+        help = "Generate a cache of the current final stage. Overrides the setting in the config if set"
+    )]
+    gen_cache: bool,
+
+    /// whether to use a cache
+    /// overrides the game-config
+    #[arg(
+        value_enum,
+        long = "use-cache",
+        help = "Specifies which/whether cache shall be used. Overrides the setting of the config if set"
+    )]
+    use_cache: Option<CacheModeArg>,
+
+    /// which fallback to use if the specified cache does not exist
+    /// overrides the game-config
+    #[arg(
+        value_enum,
+        long = "cache-fallback",
+        help = "Specifies what shall be done if cache is requested but the specified one is not found. Overrides the setting of the config"
+    )]
+    cache_fallback: Option<CacheModeFallback>,
+
+    /// whether to use a cache and which cache (concrete path) shall be used
+    /// overrides the game-config
+    #[arg(
+        long,
+        conflicts_with = "cache_event",
+        help = "If specific cache is requested, this specifies which one"
+    )]
+    cache_path: Option<PathBuf>,
+
+    /// whether to use a cache and the cache of which event shall be used
+    /// overrides the game-config
+    #[arg(
+        long,
+        conflicts_with = "cache_path",
+        help = "if the cache of a specific event is requested, this specifies which one"
+    )]
+    cache_event: Option<String>,
+
+    /// re-run the simulation every time the game-config changes
+    #[arg(
+        long = "watch",
+        help = "Watch the game-config and re-run the simulation on every change. Implies --allow-cache, uses the most recent cache by default and generates a cache"
+    )]
+    watch: bool,
+
+    /// additional statistics to collect on the remaining possible solutions
+    #[arg(
+        long = "collect",
+        value_name = "NAME[=ARGS]",
+        help = "Enable a collector (e.g. coOccurrence, dupCount, lightsForecast or 'lightsForecast={A: a, B: b}') in addition to the ones of the config. Can be given multiple times"
+    )]
+    collect: Vec<CollectorSpec>,
+
+    /// how many MiB the remaining solutions may occupy in memory
+    #[arg(
+        long = "mem-budget",
+        value_name = "MIB",
+        help = "Limit the memory used for storing the remaining solutions (for the trees, dumps and summaries) to MIB MiB, further solutions are spilled to a temporary file. Unbounded by default"
+    )]
+    mem_budget: Option<usize>,
+
+    /// for how many solutions the remaining events are simulated
+    #[arg(
+        long = "remaining-events",
+        value_name = "SAMPLES",
+        help = "Estimate after each event the expected and worst-case amount of further match boxes and matching nights needed to solve the season and the probability to still win it within the matching nights left by simulating the strategies of the solver on up to SAMPLES of the solutions left. Only done once at most 20000 solutions are left"
+    )]
+    remaining_events: Option<usize>,
+
+    /// whether to rate the decisions made for the events
+    #[arg(
+        long = "rate-decisions",
+        help = "Rate the decision made for each event by its expected information compared to the strategies of the solver (boxes: the pair closest to 50%, nights: the best seating found and a random seating). Nights are only compared once at most 20000 solutions are left before them"
+    )]
+    rate_decisions: bool,
+}
+
 /// Specifies the subcommands available on the CLI
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Simulate/Calculate an existing game-config with all the constraints and show the stats
-    Sim {
-        /// allows to disable writing .dot files
-        #[arg(long = "no-tree-output", action)]
-        no_tree_output: bool,
-
-        // TODO:(later) make possible to specify multiple values if makes sense (multiple ignoreOps
-        // available)
-        /// specify which operations/events shall be ignored
-        #[arg(long = "ignore", value_enum, default_value_t = IgnoreOps::Nothing)]
-        ignore: IgnoreOps,
-
-        // #[arg(short = 'c', long = "color")]
-        // colored: bool,
-        /// option to transpose the generated tables (swaps set_a with set_b)
-        /// swapping via the config is not possible as it matters for the maps on which side
-        /// duplicates are possible to store
-        #[arg(long = "transpose")]
-        transpose_tabs: bool,
-
-        /// base-path where to write the outputs (.dot, .json)
-        #[arg(short = 'o', long = "output")]
-        stem: PathBuf,
-
-        /// whether and if so how to dump all the remaining possible solutions
-        #[arg(
-            long = "dump",
-            help = "dump all combinations ({winning,all}{nums,names} in the end of the simulation"
-        )]
-        dump: Option<DumpMode>,
-
-        /// whether to print everything regarding the ruleset_data
-        #[arg(
-            long = "full",
-            help = "print all probabilities instead of just the topX below the tables"
-        )]
-        full: bool,
-
-        /// feature-flag for enabling the caching functionality
-        #[arg(
-            long = "allow-cache",
-            help = "Allow caching to be used in principle. Whether it will be used depends on the config and/or the use-cache flag"
-        )]
-        allow_cache: bool,
-
-        /// whether a cache shall be generated
-        #[arg(
-            long = "gen-cache",
-            help = "Generate a cache of the current final stage. Overrides the setting in the config if set"
-        )]
-        gen_cache: bool,
-
-        /// whether to use a cache
-        /// overrides the game-config
-        #[arg(
-            value_enum,
-            long = "use-cache",
-            help = "Specifies which/whether cache shall be used. Overrides the setting of the config if set"
-        )]
-        use_cache: Option<CacheModeArg>,
-
-        /// which fallback to use if the specified cache does not exist
-        /// overrides the game-config
-        #[arg(
-            value_enum,
-            long = "cache-fallback",
-            help = "Specifies what shall be done if cache is requested but the specified one is not found. Overrides the setting of the config"
-        )]
-        cache_fallback: Option<CacheModeFallback>,
-
-        /// whether to use a cache and which cache (concrete path) shall be used
-        /// overrides the game-config
-        #[arg(
-            long,
-            conflicts_with = "cache_event",
-            help = "If specific cache is requested, this specifies which one"
-        )]
-        cache_path: Option<PathBuf>,
-
-        /// whether to use a cache and the cache of which event shall be used
-        /// overrides the game-config
-        #[arg(
-            long,
-            conflicts_with = "cache_path",
-            help = "if the cache of a specific event is requested, this specifies which one"
-        )]
-        cache_event: Option<String>,
-    },
+    Sim(SimArgs),
     /// Linter like checking of the game-config for errors
-    Check {},
+    Check {
+        /// how to print the findings
+        #[arg(long = "format", value_enum, default_value_t = LintFormat::Human)]
+        format: LintFormat,
+    },
     /// Report cache availability for a YAML file
     Cache {},
+    /// Print the size of the search space and an estimate of the runtime before simulating
+    Count {
+        /// for how many seconds to sample the simulation for estimating the runtime
+        #[arg(long = "sample", default_value_t = 1.0)]
+        sample: f64,
+    },
+    /// Check how robust the results are against the amount of lights being off by one
+    Sensitivity {},
+    /// Follow an episode live: enter the results in a REPL, the new events are written back to
+    /// the game-config in the end
+    Live {},
+    /// Print the JSON schema of the game-config (for validation/completion in editors)
+    Schema {},
+    /// Convert the game-config to another format (yaml/json/toml)
+    Convert {
+        /// where to write the converted config, prints to stdout if not set
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+
+        /// the format to convert to, detected by the extension of the output if not set
+        #[arg(long = "to", value_enum, required_unless_present = "output")]
+        to: Option<Format>,
+    },
+    /// Serve a local HTTP/JSON API to the simulation results of the seasons in the data directory
+    Serve {
+        /// the directory containing the seasons (one directory per season)
+        #[arg(long = "data", default_value = "./data")]
+        data: PathBuf,
+
+        /// the address to listen on
+        #[arg(long = "addr", default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
 }
 
-/// Run the command selected by the CLI arguments. Factored out for easier testing or reuse.
-fn main() {
-    let args = Cli::parse();
+/// Simulate the game-config at `yaml_path` and print/write the report
+///
+/// `written` are the caches generated by the earlier runs in watch mode.
+fn run_sim(yaml_path: &Path, args: &SimArgs, written: &mut Vec<PathBuf>) -> Result<()> {
+    let gp = GameParse::new_from_file(yaml_path)?;
+    let gp_cache = (
+        gp.gen_cache,
+        gp.use_cache.clone(),
+        gp.cache_fallback.clone(),
+    );
+    let mut g = gp.finalize_parsing(&args.stem, &args.ignore)?;
+    g.set_mem_budget(args.mem_budget);
+    g.set_remaining_events(args.remaining_events);
+    g.set_rate_decisions(args.rate_decisions);
+    let registry = Registry::default();
+    for spec in &args.collect {
+        let (lut_a, lut_b) = g.luts();
+        let c = registry.create(spec, lut_a, lut_b)?;
+        g.add_collector(c);
+    }
 
-    match args.cmd {
-        Commands::Sim {
-            no_tree_output,
-            ignore,
-            // colored: _,
-            transpose_tabs,
-            stem,
-            dump,
-            full,
-
-            allow_cache,
-            gen_cache,
-            use_cache,
-            cache_fallback,
-            cache_path,
-            cache_event,
-        } => {
-            let gp = GameParse::new_from_yaml(&args.yaml_path).expect("Parsing failed");
-            let gp_cache = (
-                gp.gen_cache,
-                gp.use_cache.clone(),
-                gp.cache_fallback.clone(),
-            );
-            let mut g = gp
-                .finalize_parsing(&stem, &ignore)
-                .expect("processing game failed");
+    // in watch mode only the events changed since the last run shall be simulated
+    if args.allow_cache || args.watch {
+        // construct the full cache-mode (postprocess the cli arguments)
+        let cache_mode = args
+            .use_cache
+            .as_ref()
+            .map(|x| x.finalize(&args.cache_path, &args.cache_event))
+            .transpose()?;
 
-            if allow_cache {
-                // construct the full cache-mode (postprocess the cli arguments)
-                let cache_mode = use_cache
-                    .map(|x| x.finalize(&cache_path, &cache_event))
-                    .transpose()
-                    .unwrap();
-
-                // cli arguments override the settings in the config
-                let cache_mode = cache_mode.or(gp_cache.1);
-                let cache_fallback = cache_fallback.or(gp_cache.2);
-
-                let cs: Vec<CacheSpec> = g.get_cache_candidates();
-                // try selecting a cache in case a cache mode was provided
-                if let Some(cache_mode) = cache_mode {
-                    g.select_cache(&cs, cache_mode, &cache_fallback, true)
-                        .unwrap();
+        // cli arguments override the settings in the config
+        let mut cache_mode = cache_mode.or(gp_cache.1);
+        let cache_fallback = args.cache_fallback.clone().or(gp_cache.2);
+        if args.watch {
+            cache_mode = cache_mode.or(Some(CacheMode::MostRecent));
+        }
+
+        let cs: Vec<CacheSpec> = g.get_cache_candidates();
+        // try selecting a cache in case a cache mode was provided
+        if let Some(cache_mode) = cache_mode {
+            let selected = g.select_cache(&cs, cache_mode, &cache_fallback, true);
+            if args.watch && selected.is_err() {
+                println!("No cache found, simulating from scratch");
+            } else {
+                selected?;
+            }
+        }
+        if gp_cache.0 || args.gen_cache || args.watch {
+            g.set_gen_cache(&cs, true)?;
+        }
+        if args.watch {
+            // the caches of earlier versions of the config would pile up with every change
+            remove_superseded_caches(written, &cs)?;
+            if let Some(path) = cs.last().map(|c| c.path()) {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
                 }
-                if gp_cache.0 || gen_cache {
-                    g.set_gen_cache(&cs, true).unwrap();
+                if !written.contains(path) {
+                    written.push(path.clone());
                 }
             }
+        }
+    }
 
-            let start = Instant::now();
-            let result: IterState<ProgressBar, _> = g.sim(dump.clone()).unwrap();
-            g.eval(transpose_tabs, dump, full, &result, no_tree_output)
+    let start = Instant::now();
+    let result: IterState<ProgressBar, _> = g.sim(args.dump.clone())?;
+    g.eval(
+        args.transpose_tabs,
+        args.dump.clone(),
+        args.full,
+        &result,
+        args.no_tree_output,
+    )?;
+    println!("\nRan in {:.2}s", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Run the command selected by the CLI arguments. Factored out for easier testing or reuse.
+fn main() {
+    let args = Cli::parse();
+    let yaml_path = || {
+        args.yaml_path.clone().unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "the path to the game-config is required for this subcommand",
+                )
+                .exit()
+        })
+    };
+
+    match args.cmd {
+        Commands::Sim(sim) => {
+            let yaml_path = yaml_path();
+            if sim.watch {
+                let mut written = vec![];
+                watch_file(&yaml_path, || {
+                    // clear the terminal so only the latest report is shown
+                    print!("\x1b[2J\x1b[H");
+                    if let Err(e) = run_sim(&yaml_path, &sim, &mut written) {
+                        println!("error: {:#}", e);
+                    }
+                    println!("\nWatching {} for changes...", yaml_path.display());
+                })
                 .unwrap();
-            println!("\nRan in {:.2}s", start.elapsed().as_secs_f64());
+            } else {
+                run_sim(&yaml_path, &sim, &mut vec![]).unwrap();
+            }
         }
         Commands::Cache {} => {
-            let gp = GameParse::new_from_yaml(&args.yaml_path).expect("Parsing failed");
-            let mut g = gp
+            let gp = GameParse::new_from_file(&yaml_path()).expect("Parsing failed");
+            let g = gp
                 .finalize_parsing(std::path::Path::new(".trash"), &IgnoreOps::Nothing)
                 .expect("processing game failed");
 
             let cs = g.get_cache_candidates();
             show_caches(cs).unwrap();
         }
-        Commands::Check {} => {
-            let gp = GameParse::new_from_yaml(&args.yaml_path).expect("Parsing failed");
-            gp.finalize_parsing(std::path::Path::new(".trash"), &IgnoreOps::Nothing)
+        Commands::Count { sample } => {
+            let gp = GameParse::new_from_file(&yaml_path()).expect("Parsing failed");
+            let g = gp
+                .finalize_parsing(std::path::Path::new(".trash"), &IgnoreOps::Nothing)
+                .expect("processing game failed");
+
+            let space = g
+                .count(Duration::from_secs_f64(sample))
+                .expect("estimating the search space failed");
+            print!("{}", space);
+        }
+        Commands::Sensitivity {} => {
+            let gp = GameParse::new_from_file(&yaml_path()).expect("Parsing failed");
+            let g = gp
+                .finalize_parsing(std::path::Path::new(".trash"), &IgnoreOps::Nothing)
                 .expect("processing game failed");
+
+            let start = Instant::now();
+            g.sensitivity::<ProgressBar>().unwrap();
+            println!("\nRan in {:.2}s", start.elapsed().as_secs_f64());
+        }
+        Commands::Check { format } => {
+            let yaml_path = yaml_path();
+            let diags = lint_file(&yaml_path).expect("Reading the file failed");
+            print_diagnostics(&yaml_path, &diags, format).unwrap();
+            if diags.iter().any(|d| d.severity == Severity::Error) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Convert { output, to } => {
+            let gp = GameParse::new_from_file(&yaml_path()).expect("Parsing failed");
+            let format = to
+                .map_or_else(|| Format::from_path(output.as_ref().unwrap()), Ok)
+                .expect("Unknown output format");
+            let converted = gp.to_string(format).expect("Conversion failed");
+            match output {
+                Some(output) => std::fs::write(output, converted).expect("Writing failed"),
+                None => print!("{}", converted),
+            }
+        }
+        Commands::Live {} => {
+            live(&yaml_path()).unwrap();
+        }
+        Commands::Serve { data, addr } => {
+            serve(&data, &addr).unwrap();
+        }
+        Commands::Schema {} => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&GameParse::json_schema()).unwrap()
+            );
         }
     }
 }
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module holds the state of a single document which is opened in the editor.
+
+use lsp_types::{Position, Range};
+use rust_decimal::Decimal;
+use serde_yaml::Value;
+
+/// A game-config opened in the editor
+#[derive(Debug, Default)]
+pub(crate) struct Document {
+    /// the current content
+    pub(crate) text: String,
+    /// the individuals of set_a (kept from the last version which could be parsed)
+    pub(crate) set_a: Vec<String>,
+    /// the individuals of set_b (kept from the last version which could be parsed)
+    pub(crate) set_b: Vec<String>,
+}
+
+impl Document {
+    /// Create a new document with content `text`
+    pub(crate) fn new(text: String) -> Self {
+        let mut d = Document::default();
+        d.update(text);
+        d
+    }
+
+    /// Replace the content of the document with `text`
+    pub(crate) fn update(&mut self, text: String) {
+        // while typing the document is often invalid => keep the old names in that case
+        if let Ok(v) = serde_yaml::from_str::<Value>(&text) {
+            let names = |key| -> Option<Vec<String>> {
+                v.get(key)?
+                    .as_sequence()?
+                    .iter()
+                    .map(|n| n.as_str().map(|n| n.to_string()))
+                    .collect()
+            };
+            if let Some(a) = names("setA") {
+                self.set_a = a;
+            }
+            if let Some(b) = names("setB") {
+                self.set_b = b;
+            }
+        }
+        self.text = text;
+    }
+
+    /// Get the line with index `line` (0-indexed)
+    pub(crate) fn line(&self, line: u32) -> Option<&str> {
+        self.text.lines().nth(line as usize)
+    }
+
+    /// The range of the token which starts at `pos` (position as reported by the linter). If no
+    /// token starts there, the range spans the rest of the line.
+    pub(crate) fn token_range(&self, pos: Option<ayto::lint::Position>) -> Range {
+        let Some(pos) = pos else {
+            return Range::default();
+        };
+        let start = Position::new(pos.line as u32 - 1, pos.column as u32 - 1);
+        let rest = self
+            .line(start.line)
+            .map(|l| l.chars().skip(start.character as usize).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut len = rest
+            .iter()
+            .take_while(|c| !c.is_whitespace() && !":,[]{}#".contains(**c))
+            .count();
+        if len == 0 {
+            len = rest.len();
+        }
+        Range::new(
+            start,
+            Position::new(start.line, start.character + len as u32),
+        )
+    }
+
+    /// Determine the next free number for events of the given `kind` ("Night"/"Box")
+    pub(crate) fn next_num(&self, kind: &str) -> Decimal {
+        let Ok(v) = serde_yaml::from_str::<Value>(&self.text) else {
+            return Decimal::ONE;
+        };
+        v.get("constraints")
+            .and_then(|c| c.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|c| match c.get("type")? {
+                Value::Tagged(t) if t.tag == kind => t.value.get("num"),
+                _ => None,
+            })
+            .filter_map(|n| serde_yaml::from_value::<Decimal>(n.clone()).ok())
+            .max()
+            .map(|n| n.floor() + Decimal::ONE)
+            .unwrap_or(Decimal::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rust_decimal::dec;
+
+    const SRC: &str = "setA: [A, B]
+setB: [a, b]
+constraints:
+  - type: !Box {num: 1, comment: \"\"}
+    map:
+      A: a
+  - type: !Night {num: 1, comment: \"\"}
+  - type: !Box {num: 2.1, comment: \"\"}
+";
+
+    #[test]
+    fn update_keeps_names() {
+        let mut d = Document::new(SRC.to_string());
+        assert_eq!(d.set_a, vec!["A", "B"]);
+        assert_eq!(d.set_b, vec!["a", "b"]);
+
+        d.update("setA: [A, B\n".to_string());
+        assert_eq!(d.set_a, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn token_range_simple() {
+        let d = Document::new(SRC.to_string());
+        assert_eq!(
+            d.token_range(Some(ayto::lint::Position { line: 6, column: 7 })),
+            Range::new(Position::new(5, 6), Position::new(5, 7))
+        );
+        assert_eq!(d.token_range(None), Range::default());
+    }
+
+    #[test]
+    fn next_num_simple() {
+        let d = Document::new(SRC.to_string());
+        assert_eq!(d.next_num("Box"), dec![3]);
+        assert_eq!(d.next_num("Night"), dec![2]);
+        assert_eq!(Document::default().next_num("Night"), dec![1]);
+    }
+}
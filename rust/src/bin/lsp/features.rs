@@ -0,0 +1,318 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements the language features (diagnostics, completion, hover, code actions).
+//! The functions here don't know anything about the protocol itself, they only map the state of
+//! a document to the types of the protocol.
+
+use std::collections::HashMap;
+
+use ayto::lint::spans::{PathSeg, SpanIndex};
+use ayto::lint::{lint_str, Severity};
+use lsp_types::{
+    CodeAction, CodeActionKind, CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity,
+    Hover, HoverContents, MarkupContent, MarkupKind, Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+
+use crate::document::Document;
+use crate::sim::SimResult;
+
+/// Lint the document and convert the findings to diagnostics
+pub(crate) fn diagnostics(doc: &Document) -> Vec<Diagnostic> {
+    lint_str(&doc.text)
+        .into_iter()
+        .map(|d| Diagnostic {
+            range: doc.token_range(d.position),
+            severity: Some(match d.severity {
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Error => DiagnosticSeverity::ERROR,
+            }),
+            source: Some("ayto".to_string()),
+            message: match d.suggestion {
+                Some(s) => format!("{} (did you mean '{}'?)", d.message, s),
+                None => d.message,
+            },
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Find the key of the block which encloses line `line` (the closest line above which is less
+/// indented). Returns the key along with its indentation.
+fn enclosing_key(doc: &Document, line: u32) -> Option<(String, usize)> {
+    let indent = |l: &str| l.len() - l.trim_start_matches([' ', '-']).len();
+    let current = indent(doc.line(line)?);
+    let lines = doc.text.lines().take(line as usize).collect::<Vec<_>>();
+    lines
+        .into_iter()
+        .rev()
+        .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .find(|l| indent(l) < current)
+        .and_then(|l| {
+            let key = l.trim_start_matches([' ', '-']).split(':').next()?;
+            Some((key.trim().to_string(), indent(l)))
+        })
+}
+
+/// Complete the names of the individuals at `pos`. Names of set_a are offered as keys of a
+/// `map`, names of set_b as values.
+pub(crate) fn completion(doc: &Document, pos: Position) -> Vec<CompletionItem> {
+    let Some(line) = doc.line(pos.line) else {
+        return vec![];
+    };
+    let before = line
+        .chars()
+        .take(pos.character as usize)
+        .collect::<String>();
+    if before.contains('#') {
+        return vec![];
+    }
+
+    // a flow list on the line itself (e.g. `setB: [a]`) takes precedence
+    let key = match before.split_once(':') {
+        Some((k, v)) if v.contains('[') => {
+            let indent = k.len() - k.trim_start().len();
+            Some((k.trim().to_string(), indent))
+        }
+        _ => enclosing_key(doc, pos.line),
+    };
+    let names = match key {
+        Some((k, _)) if k == "map" => {
+            if before.contains(':') {
+                &doc.set_b
+            } else {
+                &doc.set_a
+            }
+        }
+        // e.g. in queryPair (only if nested, the toplevel setA/setB define the names)
+        Some((k, indent)) if k == "setA" && indent > 0 => &doc.set_a,
+        Some((k, indent)) if k == "setB" && indent > 0 => &doc.set_b,
+        _ => return vec![],
+    };
+    names
+        .iter()
+        .map(|n| CompletionItem {
+            label: n.clone(),
+            kind: Some(CompletionItemKind::VALUE),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Show the stats of the event at `pos`. If hovering over a pair of a `map`, the probability of
+/// this pair being a match is shown as well.
+pub(crate) fn hover(doc: &Document, sim: Option<&SimResult>, pos: Position) -> Option<Hover> {
+    let (spans, _) = SpanIndex::new(&doc.text).ok()?;
+    let path = spans.path_at(pos.line as usize + 1)?;
+    let idx = match path {
+        [PathSeg::Key(k), PathSeg::Idx(idx), ..] if k == "constraints" => *idx,
+        _ => return None,
+    };
+
+    let text = match sim {
+        None => "_simulation not yet available_".to_string(),
+        Some(sim) if sim.text != doc.text => {
+            "_simulation is out of date, save the file to update it_".to_string()
+        }
+        Some(sim) => {
+            let (before, after) = (sim.rem_before(idx), sim.rems.get(idx)?);
+            let mut text = format!(
+                "**{}**: {} solutions left (before: {})",
+                sim.events.get(idx)?,
+                after.1,
+                before.1
+            );
+            if let [.., PathSeg::Key(m), PathSeg::Key(a)] = path {
+                if m == "map" {
+                    let line = doc.line(pos.line)?;
+                    let b = line.split_once(':')?.1.split('#').next()?.trim();
+                    text += &format!(
+                        "\n\nPr[{} - {}]: {:.1}% (before: {:.1}%)",
+                        a,
+                        b,
+                        sim.game.pair_prob(after, a, b)?,
+                        sim.game.pair_prob(before, a, b)?
+                    );
+                }
+            }
+            text
+        }
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: text,
+        }),
+        range: None,
+    })
+}
+
+/// Build the skeleton of a new event of `kind` ("Night"/"Box") indented by `indent`
+fn skeleton(doc: &Document, kind: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut s = format!(
+        "{}- type: !{} {{num: {}, comment: \"\"}}\n{}  check: !Lights [0]\n{}  map:\n",
+        pad,
+        kind,
+        doc.next_num(kind),
+        pad,
+        pad
+    );
+    if kind == "Night" {
+        for a in &doc.set_a {
+            s += &format!("{}    {}: \n", pad, a);
+        }
+    } else {
+        s += &format!("{}    \n", pad);
+    }
+    s
+}
+
+/// Offer to append a new night/box (with the next free number) to the constraints
+pub(crate) fn code_actions(doc: &Document, uri: &Url) -> Vec<CodeAction> {
+    let Ok((spans, _)) = SpanIndex::new(&doc.text) else {
+        return vec![];
+    };
+    let constraints = [PathSeg::Key("constraints".to_string())];
+    let Some(end) = spans.end(&constraints) else {
+        return vec![];
+    };
+    // the block sequence starts at the '-' of the first event
+    let indent = spans
+        .value(&constraints)
+        .map(|p| p.column.saturating_sub(1))
+        .unwrap_or(2);
+
+    // the end of the sequence might be at the very end of the file
+    let (pos, prefix) = if end.line <= doc.text.lines().count() {
+        (Position::new(end.line as u32 - 1, 0), "")
+    } else {
+        let last = doc.text.rsplit('\n').next().unwrap_or_default();
+        let line = doc.text.matches('\n').count() as u32;
+        let prefix = if last.is_empty() { "" } else { "\n" };
+        (Position::new(line, last.chars().count() as u32), prefix)
+    };
+
+    ["Night", "Box"]
+        .into_iter()
+        .map(|kind| {
+            let edit = TextEdit {
+                range: Range::new(pos, pos),
+                new_text: format!("{}{}", prefix, skeleton(doc, kind, indent)),
+            };
+            CodeAction {
+                title: format!("Add {} #{}", kind, doc.next_num(kind)),
+                kind: Some(CodeActionKind::REFACTOR),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    const SRC: &str = "rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+queryPair:
+  setB: [a]
+constraints:
+  - type: !Box {num: 1, comment: \"\"}
+    check: !Lights [0]
+    map:
+      A: a
+";
+
+    /// extract the labels of the completion items
+    fn labels(items: Vec<CompletionItem>) -> Vec<String> {
+        items.into_iter().map(|i| i.label).collect()
+    }
+
+    #[test]
+    fn diagnostics_simple() {
+        let doc = Document::new(SRC.replace("A: a", "A: x"));
+        let diags = diagnostics(&doc);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(10, 9), Position::new(10, 10))
+        );
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn completion_simple() {
+        let doc = Document::new(SRC.to_string());
+        assert_eq!(
+            labels(completion(&doc, Position::new(10, 6))),
+            vec!["A", "B", "C"]
+        );
+        assert_eq!(
+            labels(completion(&doc, Position::new(10, 9))),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            labels(completion(&doc, Position::new(5, 10))),
+            vec!["a", "b", "c"]
+        );
+        assert!(completion(&doc, Position::new(8, 4)).is_empty());
+    }
+
+    #[test]
+    fn hover_simple() {
+        let doc = Document::new(SRC.to_string());
+        assert!(hover(&doc, None, Position::new(0, 0)).is_none());
+
+        let sim = SimResult::new(SRC.to_string(), PathBuf::from(".trash/test")).unwrap();
+        let h = hover(&doc, Some(&sim), Position::new(10, 6)).unwrap();
+        let HoverContents::Markup(m) = h.contents else {
+            panic!("unexpected hover contents");
+        };
+        assert_eq!(
+            m.value,
+            "**MB#1**: 4 solutions left (before: 6)\n\nPr[A - a]: 0.0% (before: 33.3%)"
+        );
+
+        let doc = Document::new(SRC.replace("A: a", "A: b"));
+        let h = hover(&doc, Some(&sim), Position::new(10, 6)).unwrap();
+        let HoverContents::Markup(m) = h.contents else {
+            panic!("unexpected hover contents");
+        };
+        assert!(m.value.contains("out of date"));
+    }
+
+    #[test]
+    fn code_actions_simple() {
+        let uri = Url::parse("file:///tmp/x.yaml").unwrap();
+        let doc = Document::new(SRC.to_string());
+        let actions = code_actions(&doc, &uri);
+        assert_eq!(
+            actions.iter().map(|a| a.title.clone()).collect::<Vec<_>>(),
+            vec!["Add Night #1", "Add Box #2"]
+        );
+        let edit = &actions[1].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0];
+        assert_eq!(edit.range.start, Position::new(11, 0));
+        assert_eq!(
+            edit.new_text,
+            "  - type: !Box {num: 2, comment: \"\"}\n    check: !Lights [0]\n    map:\n      \n"
+        );
+
+        let doc = Document::new(SRC.trim_end().to_string());
+        let actions = code_actions(&doc, &uri);
+        let edit = &actions[0].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0];
+        assert_eq!(edit.range.start, Position::new(10, 10));
+        assert!(edit.new_text.starts_with("\n  - type: !Night {num: 1"));
+        assert!(edit.new_text.ends_with("      A: \n      B: \n      C: \n"));
+    }
+}
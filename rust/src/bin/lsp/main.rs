@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module is the binary module for the language server which assists when editing the
+//! game-configs. It communicates via stdio, so it can be started by the editor directly.
+
+mod document;
+mod features;
+mod sim;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Completion, HoverRequest, Request as _};
+use lsp_types::{
+    CodeActionOrCommand, CodeActionProviderCapability, CompletionOptions, HoverProviderCapability,
+    PublishDiagnosticsParams, SaveOptions, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+
+use crate::document::Document;
+use crate::sim::{forget, spawn_sim, SimResults};
+
+/// The state of the language server
+struct Server {
+    /// the connection to the editor
+    conn: Connection,
+    /// the documents which are currently open
+    docs: HashMap<Url, Document>,
+    /// the latest simulation results of the documents
+    sims: SimResults,
+}
+
+impl Server {
+    /// Lint the document `uri` and send the diagnostics to the editor
+    fn publish_diagnostics(&self, uri: &Url) -> Result<()> {
+        let Some(doc) = self.docs.get(uri) else {
+            return Ok(());
+        };
+        let params = PublishDiagnosticsParams {
+            uri: uri.clone(),
+            diagnostics: features::diagnostics(doc),
+            version: None,
+        };
+        self.conn
+            .sender
+            .send(Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.to_string(),
+                params,
+            )))?;
+        Ok(())
+    }
+
+    /// Start a new simulation of the document `uri`
+    fn simulate(&self, uri: &Url) {
+        if let Some(doc) = self.docs.get(uri) {
+            spawn_sim(uri.clone(), doc.text.clone(), Arc::clone(&self.sims));
+        }
+    }
+
+    /// Handle a notification of the editor
+    fn on_notification(&mut self, not: Notification) -> Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let p = not
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)?;
+                let uri = p.text_document.uri;
+                self.docs
+                    .insert(uri.clone(), Document::new(p.text_document.text));
+                self.publish_diagnostics(&uri)?;
+                self.simulate(&uri);
+            }
+            DidChangeTextDocument::METHOD => {
+                let p = not.extract::<lsp_types::DidChangeTextDocumentParams>(
+                    DidChangeTextDocument::METHOD,
+                )?;
+                let uri = p.text_document.uri;
+                // only full syncs are requested => the last change contains the whole document
+                if let (Some(doc), Some(change)) = (
+                    self.docs.get_mut(&uri),
+                    p.content_changes.into_iter().last(),
+                ) {
+                    doc.update(change.text);
+                }
+                self.publish_diagnostics(&uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let p = not
+                    .extract::<lsp_types::DidSaveTextDocumentParams>(DidSaveTextDocument::METHOD)?;
+                self.simulate(&p.text_document.uri);
+            }
+            DidCloseTextDocument::METHOD => {
+                let p = not.extract::<lsp_types::DidCloseTextDocumentParams>(
+                    DidCloseTextDocument::METHOD,
+                )?;
+                self.docs.remove(&p.text_document.uri);
+                forget(&p.text_document.uri, &self.sims);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle a request of the editor
+    fn on_request(&mut self, req: Request) -> Result<()> {
+        let resp = match req.method.as_str() {
+            Completion::METHOD => {
+                let (id, p) = req.extract::<lsp_types::CompletionParams>(Completion::METHOD)?;
+                let pos = p.text_document_position;
+                let items = self
+                    .docs
+                    .get(&pos.text_document.uri)
+                    .map(|d| features::completion(d, pos.position))
+                    .unwrap_or_default();
+                Response::new_ok(id, items)
+            }
+            HoverRequest::METHOD => {
+                let (id, p) = req.extract::<lsp_types::HoverParams>(HoverRequest::METHOD)?;
+                let pos = p.text_document_position_params;
+                let sims = self.sims.lock().unwrap();
+                let hover = self.docs.get(&pos.text_document.uri).and_then(|d| {
+                    let sim = sims.get(&pos.text_document.uri).and_then(|s| s.result());
+                    features::hover(d, sim, pos.position)
+                });
+                Response::new_ok(id, hover)
+            }
+            CodeActionRequest::METHOD => {
+                let (id, p) =
+                    req.extract::<lsp_types::CodeActionParams>(CodeActionRequest::METHOD)?;
+                let uri = p.text_document.uri;
+                let actions = self
+                    .docs
+                    .get(&uri)
+                    .map(|d| features::code_actions(d, &uri))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(CodeActionOrCommand::CodeAction)
+                    .collect::<Vec<_>>();
+                Response::new_ok(id, actions)
+            }
+            _ => Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("unsupported request {}", req.method),
+            ),
+        };
+        self.conn.sender.send(Message::Response(resp))?;
+        Ok(())
+    }
+
+    /// Process the messages of the editor until it requests the shutdown
+    fn run(&mut self) -> Result<()> {
+        while let Ok(msg) = self.conn.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.conn.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.on_request(req)?;
+                }
+                Message::Notification(not) => self.on_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The capabilities this server offers to the editor
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(false),
+                })),
+                ..Default::default()
+            },
+        )),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![" ".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+/// Start the language server on stdio
+fn main() -> Result<()> {
+    let (conn, io_threads) = Connection::stdio();
+    conn.initialize(serde_json::to_value(capabilities())?)?;
+
+    let mut server = Server {
+        conn,
+        docs: HashMap::new(),
+        sims: Arc::new(Mutex::new(HashMap::new())),
+    };
+    server.run()?;
+
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module runs the simulation of a document in the background so the results can be shown
+//! when hovering over an event.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::Result;
+use ayto::constraint::ConstraintGetters;
+use ayto::game::parse::GameParse;
+use ayto::game::Game;
+use ayto::ignore_ops::IgnoreOps;
+use ayto::iterstate::IterState;
+use ayto::progressbar::MockProgressBar;
+use ayto::Rem;
+use lsp_types::Url;
+
+/// The results of a simulation of a document
+pub(crate) struct SimResult {
+    /// the content of the document which was simulated
+    pub(crate) text: String,
+    /// the simulated game
+    pub(crate) game: Game,
+    /// the name of each event (same order as the constraints in the config)
+    pub(crate) events: Vec<String>,
+    /// the remaining amount of solutions before the first event
+    pub(crate) initial: Rem,
+    /// the remaining amount of solutions after each event
+    pub(crate) rems: Vec<Rem>,
+}
+
+impl SimResult {
+    /// Simulate the game-config `text`. `stem` is only used to build the game, no output is
+    /// written.
+    pub(crate) fn new(text: String, stem: PathBuf) -> Result<Self> {
+        let mut game =
+            GameParse::new_from_str(&text)?.finalize_parsing(&stem, &IgnoreOps::Nothing)?;
+        let is: IterState<MockProgressBar, _> = game.sim(None)?;
+        Ok(SimResult {
+            events: is.constraints.iter().map(|c| c.type_str()).collect(),
+            initial: (is.each.clone(), is.total),
+            rems: game.rem_trail(&is)?,
+            text,
+            game,
+        })
+    }
+
+    /// The remaining amount of solutions before the event `idx`
+    pub(crate) fn rem_before(&self, idx: usize) -> &Rem {
+        idx.checked_sub(1)
+            .and_then(|i| self.rems.get(i))
+            .unwrap_or(&self.initial)
+    }
+}
+
+/// The simulations of a document
+#[derive(Default)]
+pub(crate) struct DocSim {
+    /// the result of the latest simulation which finished (results of outdated simulations are
+    /// dropped)
+    result: Option<SimResult>,
+    /// incremented with each simulation requested, used to detect outdated simulations
+    generation: u64,
+    /// the text to simulate once the running simulation is finished (only the latest one)
+    pending: Option<String>,
+    /// whether a simulation of the document is running
+    running: bool,
+}
+
+impl DocSim {
+    /// the result of the latest simulation (if there is one)
+    pub(crate) fn result(&self) -> Option<&SimResult> {
+        self.result.as_ref()
+    }
+}
+
+/// The simulations of all documents
+pub(crate) type SimResults = Arc<Mutex<HashMap<Url, DocSim>>>;
+
+/// Simulate `text` (the content of the document `uri`) in a background thread and store the result
+/// in `results` once it is finished. If the simulation fails, the old result is kept.
+///
+/// At most one simulation per document runs at a time. If a simulation is running already, `text`
+/// is simulated once it is finished (replacing earlier texts which are still waiting) and the
+/// result of the running simulation is dropped as it is outdated.
+pub(crate) fn spawn_sim(uri: Url, text: String, results: SimResults) {
+    let generation = {
+        let mut sims = results.lock().unwrap();
+        let doc = sims.entry(uri.clone()).or_default();
+        doc.generation += 1;
+        if doc.running {
+            doc.pending = Some(text);
+            return;
+        }
+        doc.running = true;
+        doc.generation
+    };
+    thread::spawn(move || run_sims(uri, text, generation, results));
+}
+
+/// Simulate `text` (the content of the document `uri` with the `generation`) and afterwards the
+/// texts which were requested in the meantime until none is pending anymore
+fn run_sims(uri: Url, mut text: String, mut generation: u64, results: SimResults) {
+    let stem = uri
+        .to_file_path()
+        .unwrap_or_else(|_| PathBuf::from(".trash/lsp"));
+    loop {
+        let r = SimResult::new(text, stem.clone());
+
+        let mut sims = results.lock().unwrap();
+        let Some(doc) = sims.get_mut(&uri) else {
+            return;
+        };
+        if let Ok(r) = r {
+            if doc.generation == generation {
+                doc.result = Some(r);
+            }
+        }
+        match doc.pending.take() {
+            Some(t) => {
+                text = t;
+                generation = doc.generation;
+            }
+            None => {
+                doc.running = false;
+                if doc.result.is_none() {
+                    sims.remove(&uri);
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Forget the simulations of the document `uri` (e.g. once it is closed). A running simulation
+/// is finished but its result is dropped.
+pub(crate) fn forget(uri: &Url, results: &SimResults) {
+    let mut sims = results.lock().unwrap();
+    let Some(doc) = sims.get_mut(uri) else {
+        return;
+    };
+    if doc.running {
+        doc.generation += 1;
+        doc.pending = None;
+        doc.result = None;
+    } else {
+        sims.remove(uri);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use std::time::Duration;
+
+    const SRC: &str = "rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+constraints:
+  - type: !Box {num: 1, comment: \"\"}
+    check: !Lights [0]
+    map:
+      A: a
+";
+
+    /// wait until no simulation of `uri` is running anymore
+    fn wait(uri: &Url, results: &SimResults) {
+        for _ in 0..1000 {
+            if results.lock().unwrap().get(uri).is_none_or(|d| !d.running) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("simulation didn't finish");
+    }
+
+    #[test]
+    fn spawn_sim_latest_wins() {
+        let results = SimResults::default();
+        let uri = Url::parse("file:///tmp/lsp/test.yaml").unwrap();
+        let texts = (1..=5)
+            .map(|i| SRC.replace("num: 1", &format!("num: {i}")))
+            .collect::<Vec<_>>();
+        for t in &texts {
+            spawn_sim(uri.clone(), t.clone(), Arc::clone(&results));
+        }
+        // only the latest text waits for the running simulation
+        {
+            let sims = results.lock().unwrap();
+            let doc = &sims[&uri];
+            assert_eq!(doc.generation, 5);
+            assert!(doc.pending.as_ref().is_none_or(|p| p == &texts[4]));
+        }
+        wait(&uri, &results);
+        let sims = results.lock().unwrap();
+        assert_eq!(sims[&uri].result().unwrap().text, texts[4]);
+    }
+
+    #[test]
+    fn forget_drops_running() {
+        let results = SimResults::default();
+        let uri = Url::parse("file:///tmp/lsp/forget.yaml").unwrap();
+        spawn_sim(uri.clone(), SRC.to_string(), Arc::clone(&results));
+        forget(&uri, &results);
+        wait(&uri, &results);
+        assert!(results.lock().unwrap().get(&uri).is_none());
+
+        // the failing simulation keeps the old result
+        spawn_sim(uri.clone(), SRC.to_string(), Arc::clone(&results));
+        wait(&uri, &results);
+        spawn_sim(uri.clone(), "setA: [".to_string(), Arc::clone(&results));
+        wait(&uri, &results);
+        assert_eq!(results.lock().unwrap()[&uri].result().unwrap().text, SRC);
+    }
+}
@@ -128,7 +128,7 @@ impl<S: StrategyBundle> Simulation<S> {
             rem = c.apply_to_rem(rem).context("Apply to rem failed")?;
         }
 
-        Ok((iter_state.constraints, iter_state.left_poss, rem))
+        Ok((iter_state.constraints, iter_state.left_poss.to_vec()?, rem))
     }
 
     /// Initializes the simulation state and computes the initial possibility space.
@@ -20,12 +20,12 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use ayto::constraint::ConstraintGetters;
+use ayto::solver::{calc_entropy, mb, mn};
 use clap::{Parser, Subcommand};
 
 use crate::runner::run_many_and_write;
 use crate::step::CfgParse;
-use crate::strategies::{mb, mn, Strategy};
-use crate::utils::calc_entropy;
+use crate::strategies::Strategy;
 
 /// The amount of players currently expected -> variable so it can be changed more easily later
 const NUM_PLAYERS_SET_A: usize = 10;
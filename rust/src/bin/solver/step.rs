@@ -3,54 +3,53 @@
 //! configuration file.
 
 use std::sync::Arc;
-use std::{fs::File, path::Path};
+use std::{fs::read_to_string, path::Path};
 
 use anyhow::{bail, ensure, Result};
 use ayto::constraint::parse_utils::convert_map_s_to_ids;
 use ayto::constraint::ConstraintGetters;
-use ayto::game::parse_utils::{build_luts, process_constraints};
-use ayto::ignore_ops::IgnoreOps;
+use ayto::game::builder::GameBuilder;
+use ayto::game::parse::GameParse;
 use ayto::matching_repr::MaskedMatching;
 use ayto::ruleset::RuleSet;
 use ayto::MapS;
-use ayto::{constraint::parse::ConstraintParse, ruleset::parse::RuleSetParse};
 use serde::Deserialize;
 
 use crate::engine::Simulation;
 use crate::strategies::StrategyBundle;
 use crate::NUM_PLAYERS_SET_A;
 
-/// this struct is only used for parsing the yaml file
+/// the keys of the config which are only used by the solver
 #[derive(Deserialize, Debug)]
-pub struct CfgParse {
-    /// the ruleset which is to be applied to this game
-    rule_set: RuleSetParse,
-    /// the constraints in this game
-    #[serde(rename = "constraints")]
-    constraints_orig: Vec<ConstraintParse>,
-
-    /// the set of individuals in set_a (also maps idx_a to name_a)
-    #[serde(rename = "setA")]
-    map_a: Vec<String>,
-    /// the set of individuals in set_b (also maps idx_b to name_b)
-    #[serde(rename = "setB")]
-    map_b: Vec<String>,
-
+struct SolverParse {
     /// The solution to this game
     solution: MapS,
 
     /// If specified the entropy for this matching will be calculated based on the permutations
-    /// left after applying the `constraints_orig` given in the config.
+    /// left after applying the constraints given in the config.
     #[serde(default, rename = "try_entropy")]
     try_entropy: Option<MapS>,
 }
 
+/// The parsed config of the solver. This is a regular game-config (see [`GameParse`]) with some
+/// additional keys.
+#[derive(Debug)]
+pub struct CfgParse {
+    /// the game (ruleset, individuals and constraints)
+    game: GameParse,
+    /// the keys only used by the solver
+    solver: SolverParse,
+}
+
 impl CfgParse {
     /// create a [`CfgParse`] from a yaml config. This struct can then be finalized to a
     /// [`Simulation`]
     pub fn new_from_yaml(yaml_path: &Path) -> Result<CfgParse> {
-        let gp: CfgParse = serde_yaml::from_reader(File::open(yaml_path)?)?;
-        Ok(gp)
+        let src = read_to_string(yaml_path)?;
+        Ok(CfgParse {
+            game: GameParse::new_from_str(&src)?,
+            solver: serde_yaml::from_str(&src)?,
+        })
     }
 
     /// Create a [`Simulation`] from the parsed config.
@@ -65,32 +64,24 @@ impl CfgParse {
         seed: u64,
         strategy: Arc<S>,
     ) -> Result<(MaskedMatching, Option<MaskedMatching>, Simulation<S>)> {
-        ensure!(self.map_a.len() == NUM_PLAYERS_SET_A);
-        ensure!(self.map_b.len() == NUM_PLAYERS_SET_A);
-
-        // build up the look up tables (LUT)
-        let (lut_a, lut_b) = build_luts(&self.map_a, &self.map_b)?;
+        // build and validate the game like a game-config read by the main binary
+        let mut game = GameBuilder::from_parse(self.game).build()?;
+        let (lut_a, lut_b) = game.luts();
+        let (lut_a, lut_b) = (lut_a.clone(), lut_b.clone());
         ensure!(lut_a.len() == NUM_PLAYERS_SET_A);
         ensure!(lut_b.len() == NUM_PLAYERS_SET_A);
 
-        let rule_set = self.rule_set.finalize_parsing();
+        let rule_set = game.rule_set.clone();
         ensure!(matches!(rule_set, RuleSet::Eq));
 
-        let (constraints, lights) = process_constraints(
-            self.constraints_orig,
-            &IgnoreOps::Nothing,
-            &lut_a,
-            &lut_b,
-            &rule_set,
-            &Default::default(),
-            &Default::default(),
-            &self.map_b,
-        )?;
+        let constraints = std::mem::take(&mut game.constraints_orig);
+        let lights = constraints.iter().map(|c| c.added_known_lights()).sum();
 
-        let (solution, _) = convert_map_s_to_ids(&self.solution, &lut_a, &lut_b)?;
+        let (solution, _) = convert_map_s_to_ids(&self.solver.solution, &lut_a, &lut_b)?;
         let solution = solution.try_into()?;
 
         let try_entropy = self
+            .solver
             .try_entropy
             .map(|t| -> Result<MaskedMatching> {
                 let (map, _) = convert_map_s_to_ids(&t, &lut_a, &lut_b)?;
@@ -2,10 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-//! This module-tree implements different strategies to play the game.
-
-pub(super) mod mb;
-pub(super) mod mn;
+//! This module combines the strategies to play the game (see [`ayto::solver`]).
 
 use anyhow::Result;
 use ayto::{
@@ -14,14 +11,12 @@ use ayto::{
         ConstraintType,
     },
     matching_repr::{bitset::Bitset, MaskedMatching},
+    solver::{mb::MbOptimizer, mn::MnOptimizer},
 };
 use rand::Rng;
 use rust_decimal::{dec, Decimal};
 
-use crate::{
-    strategies::{mb::MbOptimizer, mn::MnOptimizer},
-    trail::{constraint_type_order, CT},
-};
+use crate::trail::{constraint_type_order, CT};
 
 /// A single trait that groups both MB and MN strategy behaviour
 /// and provides an initial value for a set of perms.
@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module (and its submodules) implement user-defined statistics which are collected on the
+//! solutions left after the simulation.
+//!
+//! In contrast to [`crate::ruleset_data`] the collectors don't depend on the ruleset but are
+//! enabled explicitly, either in the game-config (`collectors`) or via the CLI (`--collect`). Each
+//! collector is registered under a name in a [`Registry`], new collectors can be added by
+//! implementing [`Collector`] and registering a [`CollectorFactory`] (pass the registry to
+//! [`crate::game::parse::GameParse::finalize_parsing_with`] to enable them in the game-config).
+//! The output of the collectors is appended to the report and to the json data used for the
+//! comparison, some collectors also contribute a plot to the season page.
+
+pub mod co_occurrence;
+pub mod dup_count;
+pub mod lights_forecast;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::lint::did_you_mean;
+use crate::matching_repr::MaskedMatching;
+use crate::solution_store::SolutionStore;
+use crate::Lut;
+
+/// Small helper trait to allow cloning boxed trait objects.
+///
+/// Implementors of [`Collector`] should derive/impl [`Clone`] and [`CollectorClone`]
+/// will provide a boxed clone via [`CollectorClone::clone_box`].
+pub trait CollectorClone {
+    /// clone the boxed Collector
+    fn clone_box(&self) -> Box<dyn Collector>;
+}
+
+impl<T> CollectorClone for T
+where
+    T: 'static + Collector + Clone,
+{
+    fn clone_box(&self) -> Box<dyn Collector> {
+        Box::new(self.clone())
+    }
+}
+
+/// Collects statistics on the solutions which are left after the simulation
+pub trait Collector: std::fmt::Debug + CollectorClone + Send {
+    /// the name the collector is registered with
+    fn name(&self) -> &str;
+
+    /// Called for each solution which is still possible after all constraints
+    fn push(&mut self, m: &MaskedMatching) -> Result<()>;
+
+    /// Whether the collector needs all the solutions left once they were pushed (see
+    /// [`Collector::finish`]). If so, the simulation keeps them (within its memory budget).
+    fn needs_solutions(&self) -> bool {
+        false
+    }
+
+    /// Called once after all solutions were pushed (before printing the statistics). `solutions`
+    /// holds the solutions left if any collector [needs them](Collector::needs_solutions),
+    /// otherwise it might be empty.
+    fn finish(&mut self, _solutions: &SolutionStore) -> Result<()> {
+        Ok(())
+    }
+
+    /// Print the collected statistics to `f`.
+    ///
+    /// `full` indicates whether to emit the full report or a short "top-k" summary, `total` is
+    /// the amount of solutions left.
+    fn print(
+        &self,
+        f: &mut dyn Write,
+        full: bool,
+        map_a: &[String],
+        map_b: &[String],
+        total: u128,
+    ) -> Result<()>;
+
+    /// The collected statistics as json (e.g. to be used in the comparison)
+    fn to_json(&self, map_a: &[String], map_b: &[String]) -> Result<serde_json::Value>;
+
+    /// An interactive plot of the collected statistics to be shown on the season page. Most
+    /// collectors don't provide one.
+    fn page_plot(&self, _map_a: &[String], _map_b: &[String]) -> Result<Option<PagePlot>> {
+        Ok(None)
+    }
+}
+
+/// the id of the theme used for the light plots on the season page (same default as in the
+/// comparison)
+pub(crate) const THEME_LIGHT: u8 = 1;
+/// the id of the theme used for the dark plots on the season page (same default as in the
+/// comparison)
+pub(crate) const THEME_DARK: u8 = 3;
+
+/// A plot a collector contributes to the season page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagePlot {
+    /// the title of the plot (shown before expanding the details block)
+    pub title: String,
+    /// the plot as inline html in the light theme
+    pub light: String,
+    /// the plot as inline html in the dark theme
+    pub dark: String,
+}
+
+impl Clone for Box<dyn Collector> {
+    fn clone(&self) -> Box<dyn Collector> {
+        self.clone_box()
+    }
+}
+
+/// Creates a collector from the `args` given in the game-config/CLI for a game with the lookup
+/// tables `lut_a` and `lut_b`
+pub type CollectorFactory =
+    fn(args: &serde_yaml::Value, lut_a: &Lut, lut_b: &Lut) -> Result<Box<dyn Collector>>;
+
+/// Specifies a collector to enable, as given in the game-config or the CLI
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct CollectorSpec {
+    /// the name of the collector
+    pub name: String,
+    /// the arguments for the collector (depends on the collector)
+    #[serde(default, skip_serializing_if = "serde_yaml::Value::is_null")]
+    #[schemars(with = "serde_json::Value")]
+    pub args: serde_yaml::Value,
+}
+
+impl FromStr for CollectorSpec {
+    type Err = anyhow::Error;
+
+    /// Parse `name` or `name=args` where `args` is yaml (e.g. `lightsForecast={A: a, B: b}`)
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, args) = match s.split_once('=') {
+            Some((name, args)) => (
+                name,
+                serde_yaml::from_str(args)
+                    .with_context(|| format!("invalid arguments for collector '{}'", name))?,
+            ),
+            None => (s, serde_yaml::Value::Null),
+        };
+        if name.trim().is_empty() {
+            bail!("no collector given in '{}'", s);
+        }
+        Ok(CollectorSpec {
+            name: name.trim().to_string(),
+            args,
+        })
+    }
+}
+
+/// The collectors which are available by name
+pub struct Registry {
+    /// maps the name of a collector to the function creating it
+    factories: BTreeMap<String, CollectorFactory>,
+}
+
+impl Default for Registry {
+    /// A registry containing all the collectors implemented in this crate
+    fn default() -> Self {
+        let mut r = Registry {
+            factories: BTreeMap::new(),
+        };
+        r.register(co_occurrence::NAME, co_occurrence::CoOccurrence::create);
+        r.register(dup_count::NAME, dup_count::DupCount::create);
+        r.register(
+            lights_forecast::NAME,
+            lights_forecast::LightsForecast::create,
+        );
+        r
+    }
+}
+
+impl Registry {
+    /// Make the collector created by `factory` available as `name`. An existing collector with
+    /// the same name is replaced.
+    pub fn register(&mut self, name: &str, factory: CollectorFactory) {
+        self.factories.insert(name.to_string(), factory);
+    }
+
+    /// the names of the collectors available
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(|k| k.as_str())
+    }
+
+    /// Create the collector specified by `spec` for a game with the lookup tables `lut_a` and
+    /// `lut_b`
+    pub fn create(
+        &self,
+        spec: &CollectorSpec,
+        lut_a: &Lut,
+        lut_b: &Lut,
+    ) -> Result<Box<dyn Collector>> {
+        let Some(factory) = self.factories.get(&spec.name) else {
+            match did_you_mean(&spec.name, self.factories.keys()) {
+                Some(s) => bail!("unknown collector '{}' (did you mean '{}'?)", spec.name, s),
+                None => bail!(
+                    "unknown collector '{}' (available: {})",
+                    spec.name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                ),
+            }
+        };
+        factory(&spec.args, lut_a, lut_b)
+            .with_context(|| format!("invalid arguments for collector '{}'", spec.name))
+    }
+}
+
+/// The statistics of a collector as stored in the json data used for the comparison
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CollectedStats {
+    /// the name of the collector
+    pub name: String,
+    /// the statistics collected (depends on the collector)
+    pub data: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn spec_from_str() -> Result<()> {
+        assert_eq!(
+            "dupCount".parse::<CollectorSpec>()?,
+            CollectorSpec {
+                name: "dupCount".to_string(),
+                args: serde_yaml::Value::Null,
+            }
+        );
+        let spec = "lightsForecast={A: a}".parse::<CollectorSpec>()?;
+        assert_eq!(spec.name, "lightsForecast");
+        assert_eq!(spec.args["A"], serde_yaml::Value::from("a"));
+        assert!("=x".parse::<CollectorSpec>().is_err());
+        assert!("x={a".parse::<CollectorSpec>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn registry_create() {
+        let r = Registry::default();
+        let lut = Lut::from([("A".to_string(), 0), ("B".to_string(), 1)]);
+        assert_eq!(
+            r.names().collect::<Vec<_>>(),
+            vec!["coOccurrence", "dupCount", "lightsForecast"]
+        );
+
+        let c = r.create(&"dupCount".parse().unwrap(), &lut, &lut).unwrap();
+        assert_eq!(c.name(), "dupCount");
+
+        let err = r
+            .create(&"dupCont".parse().unwrap(), &lut, &lut)
+            .unwrap_err();
+        assert!(err.to_string().contains("did you mean 'dupCount'"));
+    }
+}
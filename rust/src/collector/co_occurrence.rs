@@ -0,0 +1,317 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a collector counting how often two 1:1 matches occur together in the
+//! solutions left (pair-pair co-occurrence).
+//!
+//! Based on these counts the correlation of two pairs is derived (phi coefficient of the
+//! indicators "pair is part of the solution"). A correlation of +1 means the pairs imply each
+//! other, -1 means they are mutually exclusive. The strongest correlations are reported and the
+//! full correlation matrix is shown as heatmap on the season page.
+
+use std::io::Write;
+
+use anyhow::{ensure, Result};
+use serde_json::json;
+
+use crate::collector::{Collector, PagePlot, THEME_DARK, THEME_LIGHT};
+use crate::comparison::plotly::heatmap::build_matrix_heatmap;
+use crate::matching_repr::MaskedMatching;
+use crate::ruleset_data::utils::print_stats;
+use crate::Lut;
+
+/// the name this collector is registered with
+pub const NAME: &str = "coOccurrence";
+
+/// Counts how often two pairs (a1, b1) and (a2, b2) are part of the same solution
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoOccurrence {
+    /// the amount of individuals in set_a
+    len_a: usize,
+    /// the amount of individuals in set_b
+    len_b: usize,
+    /// the counts indexed by `i * pairs + j` with `i <= j` where a pair (a, b) has the index
+    /// `a * len_b + b` and `pairs = len_a * len_b`
+    cnt: Vec<u64>,
+    /// the amount of solutions pushed
+    total: u64,
+    /// buffer for the pairs of the matching which is currently pushed (avoids allocations)
+    buf: Vec<usize>,
+}
+
+impl CoOccurrence {
+    /// Create the collector, takes no arguments
+    pub fn create(
+        args: &serde_yaml::Value,
+        lut_a: &Lut,
+        lut_b: &Lut,
+    ) -> Result<Box<dyn Collector>> {
+        ensure!(args.is_null(), "{} takes no arguments", NAME);
+        let pairs = lut_a.len() * lut_b.len();
+        Ok(Box::new(CoOccurrence {
+            len_a: lut_a.len(),
+            len_b: lut_b.len(),
+            cnt: vec![0; pairs * pairs],
+            total: 0,
+            buf: vec![],
+        }))
+    }
+
+    /// the amount of different pairs
+    fn pairs(&self) -> usize {
+        self.len_a * self.len_b
+    }
+
+    /// how often the pairs with the index `i` and `j` occured together
+    fn get(&self, i: usize, j: usize) -> u64 {
+        let (i, j) = (i.min(j), i.max(j));
+        self.cnt[i * self.pairs() + j]
+    }
+
+    /// The correlation (phi coefficient) of the pairs with the index `i` and `j`. `None` if one of
+    /// the pairs is part of all or none of the solutions.
+    fn correlation(&self, i: usize, j: usize) -> Option<f64> {
+        let n = self.total as f64;
+        let (c_i, c_j) = (self.get(i, i) as f64, self.get(j, j) as f64);
+        let var = c_i * (n - c_i) * c_j * (n - c_j);
+        if var == 0.0 {
+            return None;
+        }
+        Some((n * self.get(i, j) as f64 - c_i * c_j) / var.sqrt())
+    }
+
+    /// whether the pairs with the index `i` and `j` share an individual. The correlation of such
+    /// pairs is mostly determined by the rules of the game.
+    fn share_individual(&self, i: usize, j: usize) -> bool {
+        i / self.len_b == j / self.len_b || i % self.len_b == j % self.len_b
+    }
+
+    /// the name of the pair with the index `i`
+    fn pair_name(&self, i: usize, map_a: &[String], map_b: &[String]) -> String {
+        format!("{}-{}", map_a[i / self.len_b], map_b[i % self.len_b])
+    }
+}
+
+impl Collector for CoOccurrence {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn push(&mut self, m: &MaskedMatching) -> Result<()> {
+        let len_b = self.len_b;
+        self.buf.clear();
+        self.buf.extend(
+            m.iter_pairs()
+                .filter(|&(a, b)| (a as usize) < self.len_a && (b as usize) < len_b)
+                .map(|(a, b)| a as usize * len_b + b as usize),
+        );
+        self.buf.sort_unstable();
+        self.total += 1;
+
+        let pairs = self.pairs();
+        for (k, &i) in self.buf.iter().enumerate() {
+            for &j in &self.buf[k..] {
+                self.cnt[i * pairs + j] += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn print(
+        &self,
+        f: &mut dyn Write,
+        full: bool,
+        map_a: &[String],
+        map_b: &[String],
+        total: u128,
+    ) -> Result<()> {
+        let pairs = self.pairs();
+        let fmt_key = |(i, j): (usize, usize)| {
+            format!(
+                "{} & {}",
+                self.pair_name(i, map_a, map_b),
+                self.pair_name(j, map_a, map_b)
+            )
+        };
+        let entries = (0..pairs)
+            .flat_map(|i| (i + 1..pairs).map(move |j| (i, j)))
+            .map(|(i, j)| ((i, j), self.get(i, j) as usize))
+            .filter(|(_, c)| *c > 0);
+        print_stats(
+            &mut *f,
+            "Pr[pairs together]",
+            "",
+            total,
+            entries,
+            |&k| fmt_key(k),
+            full,
+            5,
+        )?;
+
+        let mut corr = (0..pairs)
+            .flat_map(|i| (i + 1..pairs).map(move |j| (i, j)))
+            .filter(|&(i, j)| !self.share_individual(i, j))
+            .filter_map(|(i, j)| Some(((i, j), self.correlation(i, j)?)))
+            .collect::<Vec<_>>();
+        corr.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let pos = corr.iter().filter(|(_, c)| *c > 0.0);
+        print_corr(f, "corr+ [pairs]", pos, fmt_key, full, 5)?;
+        let neg = corr.iter().rev().filter(|(_, c)| *c < 0.0);
+        print_corr(f, "corr- [pairs]", neg, fmt_key, full, 5)?;
+        Ok(())
+    }
+
+    fn to_json(&self, map_a: &[String], map_b: &[String]) -> Result<serde_json::Value> {
+        let pairs = self.pairs();
+        Ok(json!({
+            "pairs": (0..pairs).map(|i| self.pair_name(i, map_a, map_b)).collect::<Vec<_>>(),
+            "counts": (0..pairs)
+                .map(|i| (0..pairs).map(|j| self.get(i, j)).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            "total": self.total,
+            "correlation": (0..pairs)
+                .map(|i| (0..pairs).map(|j| self.correlation(i, j)).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+        }))
+    }
+
+    fn page_plot(&self, map_a: &[String], map_b: &[String]) -> Result<Option<PagePlot>> {
+        // pairs which are part of all/none of the solutions don't correlate with anything
+        let pairs = (0..self.pairs())
+            .filter(|&i| self.correlation(i, i).is_some())
+            .collect::<Vec<_>>();
+        if pairs.len() < 2 {
+            return Ok(None);
+        }
+        let labels = pairs
+            .iter()
+            .map(|&i| self.pair_name(i, map_a, map_b))
+            .collect::<Vec<_>>();
+        let z = pairs
+            .iter()
+            .map(|&i| pairs.iter().map(|&j| self.correlation(i, j)).collect())
+            .collect::<Vec<_>>();
+        let title = "Correlation of the pairs";
+        Ok(Some(PagePlot {
+            title: title.to_string(),
+            light: build_matrix_heatmap(labels.clone(), z.clone(), THEME_LIGHT, title, "corr"),
+            dark: build_matrix_heatmap(labels, z, THEME_DARK, title, "corr"),
+        }))
+    }
+}
+
+/// Print the correlations of `entries` (optionally truncated to `top_n` if `full=false`) in the
+/// same style as [`print_stats`]. Nothing is printed if there are no entries.
+fn print_corr<'a, T: Copy + 'a>(
+    f: &mut dyn Write,
+    title: &str,
+    entries: impl Iterator<Item = &'a (T, f64)>,
+    fmt_key: impl Fn(T) -> String,
+    full: bool,
+    top_n: usize,
+) -> Result<()> {
+    let entries = entries
+        .take(if full { usize::MAX } else { top_n })
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if full {
+        write!(f, "{title}: ")?;
+    } else {
+        write!(f, "top{top_n} {title}: ")?;
+    }
+    for (idx, (key, corr)) in entries.into_iter().enumerate() {
+        let sep = match (full, idx) {
+            (true, _) => "\n  ",
+            (false, 0) => "",
+            (false, _) => " | ",
+        };
+        write!(f, "{sep}{corr:+.2}: {}", fmt_key(*key))?;
+    }
+    writeln!(f)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn push_simple() -> Result<()> {
+        let lut = Lut::from([("A".to_string(), 0), ("B".to_string(), 1)]);
+        let mut c = CoOccurrence::create(&serde_yaml::Value::Null, &lut, &lut)?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![0], vec![1]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![0], vec![1]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![1], vec![0]]))?;
+
+        let names = |n: &[&str]| n.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let (map_a, map_b) = (names(&["A", "B"]), names(&["a", "b"]));
+        assert_eq!(
+            c.to_json(&map_a, &map_b)?,
+            json!({
+                "pairs": ["A-a", "A-b", "B-a", "B-b"],
+                "counts": [[2, 0, 0, 2], [0, 1, 1, 0], [0, 1, 1, 0], [2, 0, 0, 2]],
+                "total": 3,
+                "correlation": [
+                    [1.0, -1.0, -1.0, 1.0],
+                    [-1.0, 1.0, 1.0, -1.0],
+                    [-1.0, 1.0, 1.0, -1.0],
+                    [1.0, -1.0, -1.0, 1.0],
+                ],
+            })
+        );
+
+        let mut out = vec![];
+        c.print(&mut out, false, &map_a, &map_b, 3)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "top5 Pr[pairs together]: 66.7%/2: A-a & B-b | 33.3%/1: A-b & B-a\n\
+             top5 corr+ [pairs]: +1.00: A-a & B-b | +1.00: A-b & B-a\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn correlation_simple() -> Result<()> {
+        let names = ["A", "B", "C"];
+        let mut c = CoOccurrence {
+            len_a: 3,
+            len_b: 3,
+            cnt: vec![0; 81],
+            total: 0,
+            buf: vec![],
+        };
+        // A-a is part of every solution, B-b and C-c always occur together
+        for m in [[0, 1, 2], [0, 1, 2], [0, 2, 1], [0, 2, 1]] {
+            c.push(&MaskedMatching::from(&m[..]))?;
+        }
+        // A-a has no variance
+        assert_eq!(c.correlation(0, 4), None);
+        // B-b & C-c
+        assert_eq!(c.correlation(4, 8), Some(1.0));
+        // B-b & C-b (share b, mutually exclusive)
+        assert_eq!(c.correlation(4, 7), Some(-1.0));
+        assert!(c.share_individual(4, 7));
+        assert!(!c.share_individual(4, 8));
+
+        let names = names.map(|x| x.to_string()).to_vec();
+        let mut out = vec![];
+        c.print(&mut out, true, &names, &names, 4)?;
+        let out = String::from_utf8(out)?;
+        assert!(
+            out.contains("corr+ [pairs]: \n  +1.00: B-B & C-C\n  +1.00: B-C & C-B\n"),
+            "{out}"
+        );
+        assert!(!out.contains("corr-"));
+
+        // the pairs with variance are B-b, B-c, C-b, C-c
+        let plot = c.page_plot(&names, &names)?.unwrap();
+        assert_eq!(plot.title, "Correlation of the pairs");
+        assert!(plot.light.contains("B-C") && plot.dark.contains("C-B"));
+        assert!(!plot.light.contains("A-A"));
+        Ok(())
+    }
+}
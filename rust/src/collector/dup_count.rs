@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a collector counting how often each individual is part of a
+//! multi-match (dup/trip). In contrast to [`crate::ruleset_data::dup::DupData`] this works with
+//! any amount of multi-matches and is available for every ruleset.
+
+use std::io::Write;
+
+use anyhow::{ensure, Result};
+use serde_json::json;
+
+use crate::collector::Collector;
+use crate::matching_repr::MaskedMatching;
+use crate::ruleset_data::utils::print_stats;
+use crate::Lut;
+
+/// the name this collector is registered with
+pub const NAME: &str = "dupCount";
+
+/// Counts per individual how often they are part of a multi-match
+#[derive(Debug, Clone, PartialEq)]
+pub struct DupCount {
+    /// how often the individual of set_a has multiple matches
+    cnt_a: Vec<usize>,
+    /// how often the individual of set_b is part of a multi-match
+    cnt_b: Vec<usize>,
+}
+
+impl DupCount {
+    /// Create the collector, takes no arguments
+    pub fn create(
+        args: &serde_yaml::Value,
+        lut_a: &Lut,
+        lut_b: &Lut,
+    ) -> Result<Box<dyn Collector>> {
+        ensure!(args.is_null(), "{} takes no arguments", NAME);
+        Ok(Box::new(DupCount {
+            cnt_a: vec![0; lut_a.len()],
+            cnt_b: vec![0; lut_b.len()],
+        }))
+    }
+}
+
+impl Collector for DupCount {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn push(&mut self, m: &MaskedMatching) -> Result<()> {
+        for (a, bs) in m.iter().enumerate().filter(|(_, bs)| bs.count() > 1) {
+            if let Some(c) = self.cnt_a.get_mut(a) {
+                *c += 1;
+            }
+            for b in bs.iter() {
+                if let Some(c) = self.cnt_b.get_mut(b as usize) {
+                    *c += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn print(
+        &self,
+        f: &mut dyn Write,
+        full: bool,
+        map_a: &[String],
+        map_b: &[String],
+        total: u128,
+    ) -> Result<()> {
+        for (cnt, map) in [(&self.cnt_a, map_a), (&self.cnt_b, map_b)] {
+            print_stats(
+                &mut *f,
+                "Pr[multi-match]",
+                "",
+                total,
+                cnt.iter().copied().enumerate().filter(|(_, c)| *c > 0),
+                |&i| map[i].clone(),
+                full,
+                5,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn to_json(&self, map_a: &[String], map_b: &[String]) -> Result<serde_json::Value> {
+        Ok(json!({
+            "setA": map_a.iter().cloned().zip(self.cnt_a.iter().map(|&c| json!(c))).collect::<serde_json::Map<_, _>>(),
+            "setB": map_b.iter().cloned().zip(self.cnt_b.iter().map(|&c| json!(c))).collect::<serde_json::Map<_, _>>(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn push_simple() -> Result<()> {
+        let lut = Lut::from([("A".to_string(), 0), ("B".to_string(), 1)]);
+        let lut_b = Lut::from([
+            ("a".to_string(), 0),
+            ("b".to_string(), 1),
+            ("c".to_string(), 2),
+        ]);
+        let mut c = DupCount::create(&serde_yaml::Value::Null, &lut, &lut_b)?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![1, 2], vec![0]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![0], vec![1, 2]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![2], vec![0, 1]]))?;
+
+        let names = |n: &[&str]| n.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let (map_a, map_b) = (names(&["A", "B"]), names(&["a", "b", "c"]));
+        assert_eq!(
+            c.to_json(&map_a, &map_b)?,
+            json!({"setA": {"A": 1, "B": 2}, "setB": {"a": 1, "b": 3, "c": 2}})
+        );
+
+        let mut out = vec![];
+        c.print(&mut out, true, &map_a, &map_b, 3)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "Pr[multi-match]: \n  66.7%/2: B\n  33.3%/1: A\n\
+             Pr[multi-match]: \n  100.0%/3: b\n  66.7%/2: c\n  33.3%/1: a\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn create_no_args() {
+        let lut = Lut::new();
+        assert!(DupCount::create(&serde_yaml::Value::from(1), &lut, &lut).is_err());
+    }
+}
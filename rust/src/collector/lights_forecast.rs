@@ -0,0 +1,441 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a collector forecasting the amount of lights a seating would get, based
+//! on the solutions left.
+//!
+//! Besides the distribution of the lights the probabilities for a blackout (no lights except the
+//! ones of pairs which are a match in every solution left) and for winning (every seated pair is a
+//! match) are reported. If no seating is given, the seating of the most probable solution is used.
+//! This is the solution left whose pairs are the most likely ones (highest sum of the pair counts),
+//! reduced to one partner per individual of set_a. To determine it the collector needs the
+//! solutions left (see [`Collector::needs_solutions`]), which are kept by the simulation in its
+//! memory-bounded store. The forecast is computed once when finishing the collection.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use anyhow::{ensure, Context, Result};
+use serde_json::json;
+
+use crate::collector::Collector;
+use crate::constraint::parse_utils::convert_map_s_to_ids;
+use crate::matching_repr::{IdBase, MaskedMatching};
+use crate::solution_store::SolutionStore;
+use crate::{Lut, MapS};
+
+/// the name this collector is registered with
+pub const NAME: &str = "lightsForecast";
+
+/// Counts how many lights a seating gets with the solutions left
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightsForecast {
+    /// the seating for which the lights are counted, `None` to use the seating of the most
+    /// probable solution
+    seating: Option<MaskedMatching>,
+    /// how often each amount of lights occured for `seating` (index = amount of lights)
+    lights: Vec<u64>,
+    /// how often each pair (a, b) occured (indexed by `[a][b]`)
+    pairs: Vec<Vec<u64>>,
+    /// the amount of solutions pushed
+    total: u64,
+    /// the forecast computed when finishing the collection
+    forecast: Option<Forecast>,
+}
+
+/// The forecast for a single seating
+#[derive(Debug, Clone, PartialEq)]
+struct Forecast {
+    /// the seating, `None` if no seating could be determined (no solution left)
+    seating: Option<MaskedMatching>,
+    /// how often each amount of lights occured (index = amount of lights)
+    lights: Vec<u64>,
+    /// the amount of lights which occur in every solution (a blackout)
+    blackout: usize,
+    /// the amount of lights if every seated pair is a match (a win)
+    win: usize,
+}
+
+impl Forecast {
+    /// how often the seating got exactly `l` lights
+    fn cnt(&self, l: usize) -> u64 {
+        self.lights.get(l).copied().unwrap_or(0)
+    }
+}
+
+impl LightsForecast {
+    /// Create the collector, `args` is the seating to forecast (mapping set_a -> set_b). Without
+    /// arguments the seating of the most probable solution is used.
+    pub fn create(
+        args: &serde_yaml::Value,
+        lut_a: &Lut,
+        lut_b: &Lut,
+    ) -> Result<Box<dyn Collector>> {
+        let pairs = vec![vec![0; lut_b.len()]; lut_a.len()];
+        if args.is_null() {
+            return Ok(Box::new(LightsForecast {
+                seating: None,
+                lights: vec![],
+                pairs,
+                total: 0,
+                forecast: None,
+            }));
+        }
+
+        let seating_s: MapS = serde_yaml::from_value(args.clone())
+            .context("expected the seating as mapping setA -> setB")?;
+        ensure!(!seating_s.is_empty(), "the seating is empty");
+        let (seating, _) = convert_map_s_to_ids(&seating_s, lut_a, lut_b)?;
+        Ok(Box::new(LightsForecast {
+            lights: vec![0; seating.len() + 1],
+            seating: Some(seating.try_into()?),
+            pairs,
+            total: 0,
+            forecast: None,
+        }))
+    }
+
+    /// The seating of the most probable solution (see the module documentation), `None` if no
+    /// solution is left
+    fn most_probable_seating(&self, solutions: &SolutionStore) -> Result<Option<MaskedMatching>> {
+        let mut best: Option<(u64, MaskedMatching)> = None;
+        for m in solutions.iter() {
+            let m = m?;
+            let score = m
+                .iter_pairs()
+                .map(|(a, b)| self.pairs[a as usize][b as usize])
+                .sum::<u64>();
+            if best.as_ref().is_none_or(|(s, _)| score > *s) {
+                best = Some((score, m));
+            }
+        }
+        let Some((_, m)) = best else {
+            return Ok(None);
+        };
+
+        // only seat the most likely partner of each individual of set_a
+        let mut seating: HashMap<IdBase, IdBase> = HashMap::new();
+        for (a, b) in m.iter_pairs() {
+            let cnt = |b: IdBase| self.pairs[a as usize][b as usize];
+            seating
+                .entry(a)
+                .and_modify(|x| {
+                    if cnt(b) > cnt(*x) {
+                        *x = b
+                    }
+                })
+                .or_insert(b);
+        }
+        Ok(Some(seating.try_into()?))
+    }
+
+    /// Compute the forecast for the seating, the seating is determined from the `solutions` if it
+    /// is not fixed
+    fn compute_forecast(&self, solutions: &SolutionStore) -> Result<Forecast> {
+        let (seating, lights) = match &self.seating {
+            Some(seating) => (Some(seating.clone()), self.lights.clone()),
+            None => match self.most_probable_seating(solutions)? {
+                Some(seating) => {
+                    let mut lights = vec![0; seating.len() + 1];
+                    for m in solutions.iter() {
+                        lights[seating.calculate_lights(&m?) as usize] += 1;
+                    }
+                    (Some(seating), lights)
+                }
+                None => (None, vec![]),
+            },
+        };
+        Ok(self.forecast_for(seating, lights))
+    }
+
+    /// The forecast for `seating` which got the amounts of `lights`
+    fn forecast_for(&self, seating: Option<MaskedMatching>, lights: Vec<u64>) -> Forecast {
+        let (blackout, win) = match &seating {
+            Some(seating) => (
+                seating
+                    .iter_pairs()
+                    .filter(|&(a, b)| {
+                        self.total > 0 && self.pairs[a as usize][b as usize] == self.total
+                    })
+                    .count(),
+                seating.iter().filter(|s| s.count() > 0).count(),
+            ),
+            None => (0, 0),
+        };
+        Forecast {
+            seating,
+            lights,
+            blackout,
+            win,
+        }
+    }
+
+    /// The forecast computed when finishing. If the collection wasn't finished, only a fixed
+    /// seating can be forecasted.
+    fn forecast(&self) -> Forecast {
+        match &self.forecast {
+            Some(fc) => fc.clone(),
+            None => self.forecast_for(self.seating.clone(), self.lights.clone()),
+        }
+    }
+}
+
+/// the `seating` with the names of the individuals
+fn seating_names(
+    seating: &MaskedMatching,
+    map_a: &[String],
+    map_b: &[String],
+) -> BTreeMap<String, String> {
+    seating
+        .iter_pairs()
+        .map(|(a, b)| (map_a[a as usize].clone(), map_b[b as usize].clone()))
+        .collect()
+}
+
+impl Collector for LightsForecast {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn push(&mut self, m: &MaskedMatching) -> Result<()> {
+        self.total += 1;
+        m.count_pairs(&mut self.pairs);
+        if let Some(seating) = &self.seating {
+            let l = seating.calculate_lights(m) as usize;
+            if let Some(c) = self.lights.get_mut(l) {
+                *c += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_solutions(&self) -> bool {
+        self.seating.is_none()
+    }
+
+    fn finish(&mut self, solutions: &SolutionStore) -> Result<()> {
+        self.forecast = Some(self.compute_forecast(solutions)?);
+        Ok(())
+    }
+
+    fn print(
+        &self,
+        f: &mut dyn Write,
+        _full: bool,
+        map_a: &[String],
+        map_b: &[String],
+        total: u128,
+    ) -> Result<()> {
+        let fc = self.forecast();
+        let Some(seating) = &fc.seating else {
+            writeln!(
+                f,
+                "Pr[lights] for the most probable solution: no solution left"
+            )?;
+            return Ok(());
+        };
+        let pct = |cnt: u64| (cnt as f64 / total as f64) * 100.0;
+
+        let seating = seating_names(seating, map_a, map_b);
+        if self.seating.is_some() {
+            write!(f, "Pr[lights] for {:?}:", seating)?;
+        } else {
+            write!(
+                f,
+                "Pr[lights] for the most probable solution {:?}:",
+                seating
+            )?;
+        }
+        for (l, cnt) in fc.lights.iter().enumerate() {
+            write!(f, " {}: {:.1}%/{}", l, pct(*cnt), cnt)?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "  Pr[blackout]: {:.1}%/{} Pr[win]: {:.1}%/{}",
+            pct(fc.cnt(fc.blackout)),
+            fc.cnt(fc.blackout),
+            pct(fc.cnt(fc.win)),
+            fc.cnt(fc.win)
+        )?;
+        Ok(())
+    }
+
+    fn to_json(&self, map_a: &[String], map_b: &[String]) -> Result<serde_json::Value> {
+        let fc = self.forecast();
+        Ok(json!({
+            "seating": fc.seating.as_ref().map(|s| seating_names(s, map_a, map_b)),
+            "mostProbable": self.seating.is_none(),
+            "lights": fc.lights,
+            "total": self.total,
+            "blackout": fc.cnt(fc.blackout),
+            "win": fc.cnt(fc.win),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::game::builder::GameBuilder;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    /// the names of the individuals
+    fn names(n: &[&str]) -> Vec<String> {
+        n.iter().map(|x| x.to_string()).collect()
+    }
+
+    /// a lookup table for the individuals
+    fn lut(n: &[&str]) -> Lut {
+        n.iter()
+            .enumerate()
+            .map(|(i, x)| (x.to_string(), i))
+            .collect()
+    }
+
+    /// push the solutions `ms` to `c` and finish the collection
+    fn push_all(c: &mut Box<dyn Collector>, ms: &[MaskedMatching]) -> Result<()> {
+        for m in ms {
+            c.push(m)?;
+        }
+        c.finish(&SolutionStore::from(ms.to_vec()))
+    }
+
+    #[test]
+    fn push_simple() -> Result<()> {
+        let (map_a, map_b) = (names(&["A", "B"]), names(&["a", "b"]));
+        let args = serde_yaml::from_str("{A: a, B: b}")?;
+        let mut c = LightsForecast::create(&args, &lut(&["A", "B"]), &lut(&["a", "b"]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![0], vec![1]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![1], vec![0]]))?;
+        c.push(&MaskedMatching::from_matching_ref(&[vec![1], vec![0]]))?;
+
+        assert_eq!(
+            c.to_json(&map_a, &map_b)?,
+            json!({
+                "seating": {"A": "a", "B": "b"},
+                "mostProbable": false,
+                "lights": [2, 0, 1],
+                "total": 3,
+                "blackout": 2,
+                "win": 1,
+            })
+        );
+
+        let mut out = vec![];
+        c.print(&mut out, false, &map_a, &map_b, 3)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "Pr[lights] for {\"A\": \"a\", \"B\": \"b\"}: 0: 66.7%/2 1: 0.0%/0 2: 33.3%/1\n  \
+             Pr[blackout]: 66.7%/2 Pr[win]: 33.3%/1\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn blackout_known_match() -> Result<()> {
+        // A-a is a match in every solution, so a blackout still has one light
+        let (map_a, map_b) = (names(&["A", "B", "C"]), names(&["a", "b", "c"]));
+        let args = serde_yaml::from_str("{A: a, B: c, C: b}")?;
+        let mut c = LightsForecast::create(&args, &lut(&["A", "B", "C"]), &lut(&["a", "b", "c"]))?;
+        c.push(&MaskedMatching::from(&[0, 1, 2][..]))?;
+        c.push(&MaskedMatching::from(&[0, 2, 1][..]))?;
+
+        let j = c.to_json(&map_a, &map_b)?;
+        assert_eq!(j["lights"], json!([0, 1, 0, 1]));
+        assert_eq!(j["blackout"], json!(1));
+        assert_eq!(j["win"], json!(1));
+        Ok(())
+    }
+
+    #[test]
+    fn most_probable_seating() -> Result<()> {
+        let (map_a, map_b) = (names(&["A", "B", "C"]), names(&["a", "b", "c"]));
+        let (lut_a, lut_b) = (lut(&["A", "B", "C"]), lut(&["a", "b", "c"]));
+        let mut c = LightsForecast::create(&serde_yaml::Value::Null, &lut_a, &lut_b)?;
+        assert!(c.needs_solutions());
+        c.finish(&SolutionStore::default())?;
+
+        let mut out = vec![];
+        c.print(&mut out, false, &map_a, &map_b, 0)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "Pr[lights] for the most probable solution: no solution left\n"
+        );
+
+        // A-a and C-c are the most likely pairs
+        let mut c = LightsForecast::create(&serde_yaml::Value::Null, &lut_a, &lut_b)?;
+        push_all(
+            &mut c,
+            &[
+                MaskedMatching::from(&[0, 1, 2][..]),
+                MaskedMatching::from(&[0, 2, 1][..]),
+                MaskedMatching::from(&[1, 0, 2][..]),
+            ],
+        )?;
+
+        let mut out = vec![];
+        c.print(&mut out, false, &map_a, &map_b, 3)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "Pr[lights] for the most probable solution {\"A\": \"a\", \"B\": \"b\", \"C\": \"c\"}: \
+             0: 0.0%/0 1: 66.7%/2 2: 0.0%/0 3: 33.3%/1\n  \
+             Pr[blackout]: 0.0%/0 Pr[win]: 33.3%/1\n"
+        );
+        assert_eq!(c.to_json(&map_a, &map_b)?["mostProbable"], json!(true));
+        Ok(())
+    }
+
+    #[test]
+    fn most_probable_seating_dups() -> Result<()> {
+        // only the more likely partner of A is seated
+        let lut_a = lut(&["A", "B"]);
+        let lut_b = lut(&["a", "b", "c"]);
+        let mut c = LightsForecast::create(&serde_yaml::Value::Null, &lut_a, &lut_b)?;
+        push_all(
+            &mut c,
+            &[
+                MaskedMatching::from_matching_ref(&[vec![0, 2], vec![1]]),
+                MaskedMatching::from_matching_ref(&[vec![0], vec![1, 2]]),
+            ],
+        )?;
+
+        let j = c.to_json(&names(&["A", "B"]), &names(&["a", "b", "c"]))?;
+        assert_eq!(j["seating"], json!({"A": "a", "B": "b"}));
+        // both are known matches -> blackout and win at the same time
+        assert_eq!(j["lights"], json!([0, 0, 2]));
+        assert_eq!(j["blackout"], json!(2));
+        assert_eq!(j["win"], json!(2));
+        Ok(())
+    }
+
+    #[test]
+    fn most_probable_seating_sim() -> Result<()> {
+        // the solutions are kept for the collector even if not requested otherwise
+        let mut g = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+            .keep_rem(false)
+            .collector("lightsForecast".parse()?)
+            .box_(1, [("A", "a")], 1)
+            .build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        assert_eq!(is.left_poss.len(), 2);
+
+        let j = is.collectors[0].to_json(&names(&["A", "B", "C"]), &names(&["a", "b", "c"]))?;
+        assert_eq!(j["seating"], json!({"A": "a", "B": "b", "C": "c"}));
+        assert_eq!(j["lights"], json!([0, 1, 0, 1]));
+        Ok(())
+    }
+
+    #[test]
+    fn create_invalid() {
+        let lut = Lut::from([("A".to_string(), 0)]);
+        for args in ["{}", "[A]", "{A: x}"] {
+            let args = serde_yaml::from_str(args).unwrap();
+            assert!(LightsForecast::create(&args, &lut, &lut).is_err());
+        }
+    }
+}
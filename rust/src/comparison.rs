@@ -9,7 +9,7 @@
 //! This module is the only one needed to the outside (`write_pages`). Everything is plugged together here.
 
 mod data;
-mod plotly;
+pub(crate) mod plotly;
 mod presentation;
 mod theme;
 
@@ -18,8 +18,10 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 
 use crate::comparison::data::gather_cmp_data;
+use crate::comparison::plotly::PLOTLY_SCRIPT;
 use crate::comparison::presentation::information;
 use crate::comparison::presentation::lights;
+use crate::comparison::presentation::money;
 use crate::comparison::presentation::ruleset;
 use crate::comparison::presentation::summary;
 
@@ -104,10 +106,12 @@ pub fn write_pages(
         for lang in [Language::De, Language::En] {
             let mut plots_light = information::plots(&data, theme_light);
             plots_light.append(&mut lights::plots(&data, theme_light));
+            plots_light.append(&mut money::plots(&data, theme_light));
             let html_light = build_graph_hextra_tabs(&plots_light);
 
             let mut plots_dark = information::plots(&data, theme_dark);
             plots_dark.append(&mut lights::plots(&data, theme_dark));
+            plots_dark.append(&mut money::plots(&data, theme_dark));
             let html_dark = build_graph_hextra_tabs(&plots_dark);
 
             let md_ruleset_tab = ruleset::tab_md(&data);
@@ -173,7 +177,7 @@ fn build_graph_hextra_tabs(plots: &[(String, String)]) -> String {
         .fold(String::new(), |a, b| a + &b);
 
     format!(
-        r#"<script src="https://cdn.plot.ly/plotly-3.3.1.min.js"></script>
+        r#"{PLOTLY_SCRIPT}
 {{{{< tabs >}}}}
 {}
 {{{{< /tabs >}}}}"#,
@@ -8,7 +8,11 @@
 //! This mostly means the functions perform some general styling. An exception is the heatmap which
 //! contains some additional functionality which needs to be tested
 
-pub(super) mod heatmap;
+pub(crate) mod heatmap;
 pub(super) mod heatmap_utils;
 pub(super) mod layout;
-pub(super) mod scatter;
+pub(crate) mod scatter;
+
+/// the script tag loading plotly, needs to be included once in each page containing plots
+pub(crate) const PLOTLY_SCRIPT: &str =
+    r#"<script src="https://cdn.plot.ly/plotly-3.3.1.min.js"></script>"#;
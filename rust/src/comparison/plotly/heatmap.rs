@@ -15,9 +15,10 @@ use serde::Serialize;
 
 use crate::comparison::data::CmpData;
 use crate::comparison::plotly::heatmap_utils::*;
+use crate::comparison::plotly::layout::plotly_gen_layout;
 use crate::comparison::plotly::layout::plotly_new_plot;
 use crate::comparison::plotly::layout::styled_axis;
-use crate::comparison::theme::plotly_colorscale;
+use crate::comparison::theme::{lut_theme, plotly_colorscale, plotly_diverging_colorscale};
 
 /// Single input datum used to construct heatmap entries.
 ///
@@ -125,3 +126,40 @@ where
         plotly_colorscale(palette),
     )
 }
+
+/// Render a square matrix with values in `-1..=1` (e.g. correlations) as heatmap (inline Plotly
+/// HTML) in the theme with the id `theme`.
+///
+/// - `labels` label the rows and the columns (same order)
+/// - `z` is the matrix, `None` represents an empty cell
+/// - `z_title` names the value in the hover
+pub(crate) fn build_matrix_heatmap(
+    labels: Vec<String>,
+    z: Vec<Vec<Option<f64>>>,
+    theme: u8,
+    title: &str,
+    z_title: &str,
+) -> String {
+    let palette = lut_theme(theme);
+    let mut plot = plotly_new_plot();
+
+    let heatmap = HeatMap::new(labels.clone(), labels, z)
+        .color_scale(plotly_diverging_colorscale(&palette))
+        .zmin(-1.0)
+        .zmid(0.0)
+        .zmax(1.0)
+        .hover_template(format!(
+            "{}: %{{z:.2f}}<br>%{{y}} & %{{x}}<extra></extra>",
+            z_title
+        ));
+    plot.add_trace(heatmap);
+
+    plot.set_layout(
+        plotly_gen_layout(palette)
+            .title(Title::with_text(title))
+            .x_axis(styled_axis(&palette, "", true))
+            .y_axis(styled_axis(&palette, "", false)),
+    );
+
+    plot.to_inline_html(None)
+}
@@ -9,7 +9,8 @@ use plotly::{common::Mode, Layout, Scatter};
 use serde::Serialize;
 
 use crate::comparison::data::CmpData;
-use crate::comparison::plotly::layout::{plotly_new_plot, styled_axis};
+use crate::comparison::plotly::layout::{plotly_gen_layout, plotly_new_plot, styled_axis};
+use crate::comparison::theme::lut_theme;
 
 /// Build a scatter plot HTML from a list of named series. Each series is
 /// `(name, x_values, y_values, text_values)`.
@@ -39,6 +40,28 @@ where
     plot.to_inline_html(None)
 }
 
+/// Build a line plot (as inline HTML) from a list of named series (see [`scatter_from_series`])
+/// in the theme with the id `theme`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn build_line_plot<Tx, Ty>(
+    series: &[(String, Vec<Tx>, Vec<Ty>, Vec<String>)],
+    theme: u8,
+    title: &str,
+    x_title: &str,
+    y_title: &str,
+) -> String
+where
+    Tx: Serialize + Clone + 'static,
+    Ty: Serialize + Clone + 'static,
+{
+    let palette = lut_theme(theme);
+    let layout = plotly_gen_layout(palette)
+        .title(title)
+        .x_axis(styled_axis(&palette, x_title, true))
+        .y_axis(styled_axis(&palette, y_title, false));
+    scatter_from_series(&layout, series, Mode::LinesMarkers)
+}
+
 /// generic function to build a scatter plot with plotly
 ///
 /// # Arguments
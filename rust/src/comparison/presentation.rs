@@ -11,5 +11,6 @@
 
 pub(super) mod information;
 pub(super) mod lights;
+pub(super) mod money;
 pub(super) mod ruleset;
 pub(super) mod summary;
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 //! This renders the plots which show information regarding the amount of information/uncertainty
-//! left or the amount of information gained over the course of time
+//! left or the amount of information gained over the course of time (also in comparison to the
+//! strategies of the solver)
 
 use plotly::common::Mode;
 
@@ -11,6 +12,7 @@ use crate::comparison::data::CmpData;
 use crate::comparison::plotly::layout::plotly_gen_layout;
 use crate::comparison::plotly::scatter::build_scatter_plot;
 use crate::comparison::theme::lut_theme;
+use crate::solver::remaining::MAX_LEFT;
 
 /// Build the set of plots (scatter/heatmap) regarding information theory (knowledge (change) in bits)
 ///
@@ -112,5 +114,184 @@ pub(crate) fn plots(cmp_data: &[(String, CmpData)], theme: u8) -> Vec<(String, S
                 },
             ),
         ),
+        (
+            "Remaining".to_owned(),
+            build_scatter_plot(
+                cmp_data,
+                &layout,
+                &palette,
+                "Expected remaining events to solve",
+                "#MB/#MN",
+                "E[#events]",
+                Mode::Lines,
+                // only events for which the remaining events were estimated
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.num_unified(
+                                |e| e.remaining.is_some(),
+                                |e| e.remaining.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| i.remaining_expected(|_| true, |_| true, |_| false))
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.comment(
+                                |e| e.remaining.is_some(),
+                                |e| e.remaining.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+            ),
+        ),
+        (
+            "Win".to_owned(),
+            build_scatter_plot(
+                cmp_data,
+                &layout,
+                &palette,
+                &format!(
+                    "Probability to win (only estimated once at most {} solutions are left)",
+                    MAX_LEFT
+                ),
+                "#MB/#MN",
+                "P(win) [%]",
+                Mode::Lines,
+                // only events for which the remaining events were estimated
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.num_unified(
+                                |e| e.remaining.is_some(),
+                                |e| e.remaining.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| i.win_probability(|_| true, |_| true, |_| false))
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.comment(
+                                |e| e.remaining.is_some(),
+                                |e| e.remaining.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+            ),
+        ),
+        (
+            "Efficiency".to_owned(),
+            build_scatter_plot(
+                cmp_data,
+                &layout,
+                &palette,
+                "Efficiency of the decisions (expected information compared to the strategies)",
+                "#MB/#MN",
+                "efficiency [%]",
+                Mode::LinesMarkers,
+                // only events which were compared with the strategies
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.num_unified(
+                                |e| e.rating.is_some_and(|r| r.efficiency().is_some()),
+                                |e| e.rating.is_some_and(|r| r.efficiency().is_some()),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| i.efficiency(|_| true, |_| true, |_| false))
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.comment(
+                                |e| e.rating.is_some_and(|r| r.efficiency().is_some()),
+                                |e| e.rating.is_some_and(|r| r.efficiency().is_some()),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+            ),
+        ),
+        (
+            "Luck".to_owned(),
+            build_scatter_plot(
+                cmp_data,
+                &layout,
+                &palette,
+                "Cumulative luck (information of the outcomes minus the expected information)",
+                "#MB/#MN",
+                "luck [bit]",
+                Mode::Lines,
+                // only events with a known outcome of lights
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.num_unified(
+                                |e| e.surprise.is_some(),
+                                |e| e.surprise.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| i.luck(|_| true, |_| true, |_| false))
+                        .scan(0.0, |acc, l| {
+                            *acc += l;
+                            Some(*acc)
+                        })
+                        .collect()
+                },
+                |cd| {
+                    cd.eval_data
+                        .iter()
+                        .filter_map(|i| {
+                            i.comment(
+                                |e| e.surprise.is_some(),
+                                |e| e.surprise.is_some(),
+                                |_| false,
+                            )
+                        })
+                        .collect()
+                },
+            ),
+        ),
     ]
 }
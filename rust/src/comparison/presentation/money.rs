@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This renders the plots which show the prize money over the course of time
+
+use plotly::common::Mode;
+
+use crate::comparison::data::CmpData;
+use crate::comparison::plotly::layout::plotly_gen_layout;
+use crate::comparison::plotly::scatter::build_scatter_plot;
+use crate::comparison::theme::lut_theme;
+
+/// Build plots about the prize money
+///
+/// Accepts the comparison dataset and a `theme` index.
+///
+/// Returns pairs `(tab label, plot HTML)` to be embedded in the generated pages.
+pub(crate) fn plots(cmp_data: &[(String, CmpData)], theme: u8) -> Vec<(String, String)> {
+    let palette = lut_theme(theme);
+    let layout = plotly_gen_layout(palette);
+
+    vec![(
+        "Pot".to_owned(),
+        build_scatter_plot(
+            cmp_data,
+            &layout,
+            &palette,
+            "Prize pot",
+            "#MB/#MN",
+            "pot",
+            Mode::LinesMarkers,
+            // only seasons/events for which the pot is tracked
+            |cd| {
+                cd.eval_data
+                    .iter()
+                    .filter_map(|i| {
+                        i.num_unified(|e| e.pot.is_some(), |e| e.pot.is_some(), |_| false)
+                    })
+                    .collect()
+            },
+            |cd| {
+                cd.eval_data
+                    .iter()
+                    .filter_map(|i| i.pot(|_| true, |_| true, |_| false))
+                    .collect()
+            },
+            |cd| {
+                cd.eval_data
+                    .iter()
+                    .filter_map(|i| i.comment(|e| e.pot.is_some(), |e| e.pot.is_some(), |_| false))
+                    .collect()
+            },
+        ),
+    )]
+}
@@ -8,7 +8,7 @@ use num_format::ToFormattedString;
 
 use crate::comparison::data::CmpData;
 use crate::comparison::Language;
-use crate::constraint::compare::{SumCounts, SumOffersMB, SumOffersMN};
+use crate::constraint::compare::{SumCounts, SumOffersMB, SumOffersMN, SumPot};
 
 /// Build a summary Markdown tab for all rulesets.
 ///
@@ -29,6 +29,8 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
             sold_but_match_active: true,
             offers_noted: true,
             offer_and_match: 0,
+            offers_rated: 0,
+            offers_rational: 0,
             offers_cnt: 0,
             offered_money: 0,
         },
@@ -38,16 +40,17 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
             offers_cnt: 0,
             offered_money: 0,
         },
+        pot: None,
     };
 
     let mut tab_lines = vec![
-        r#"| {{< i18n "season" >}} | {{< i18n "won" >}} | {{< i18n "solvable" >}} | {{< i18n "matchesFound" >}} | {{< i18n "blackouts" >}} | | {{< i18n "sold" >}} | {{< i18n "soldButGood" >}} | | {{< i18n "offers" >}} | {{< i18n "offerAndMatch" >}} | {{< i18n "offeredMoney" >}} |"#.to_owned(),
-        "| --- |:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:| ---:|".to_owned(),
+        r#"| {{< i18n "season" >}} | {{< i18n "won" >}} | {{< i18n "solvable" >}} | {{< i18n "matchesFound" >}} | {{< i18n "blackouts" >}} | | {{< i18n "sold" >}} | {{< i18n "soldButGood" >}} | | {{< i18n "offers" >}} | {{< i18n "offerAndMatch" >}} | {{< i18n "offersRational" >}} | {{< i18n "offeredMoney" >}} | | {{< i18n "payout" >}} | {{< i18n "singleWinnings" >}} |"#.to_owned(),
+        "| --- |:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:|:---:| ---:|:---:| ---:| ---:|".to_owned(),
     ];
 
     for (name, cd) in cmp_data {
         tab_lines.push(format!(
-            "| {} | {{{{< badge content=\"{}\" color=\"{}\" >}}}} | {{{{< badge content=\"{}\" color=\"{}\" >}}}} | {} | {} | | {} / {} | {} | | {} | {} | {} / {} |",
+            "| {} | {{{{< badge content=\"{}\" color=\"{}\" >}}}} | {{{{< badge content=\"{}\" color=\"{}\" >}}}} | {} | {} | | {} / {} | {} | | {} | {} | {} | {} / {} | | {} | {} |",
             name,
 
             if let Some(won) = &cd.cnts.won_in {
@@ -85,6 +88,7 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
             } else {
                 "".to_string()
             },
+            rational_str(&cd.cnts.offers_mb),
             if cd.cnts.offers_mb.offers_noted {
                 cd.cnts.offers_mb.offered_money.to_formatted_string(&lang.number_formatting())
             } else {
@@ -95,14 +99,16 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
             } else {
                 "".to_string()
             },
+            payout_str(cd.cnts.pot.as_ref(), lang),
+            winnings_str(cd.cnts.pot.as_ref(), lang),
         ));
         total_counts.add(&cd.cnts);
     }
     tab_lines[2..].sort();
 
-    tab_lines.push("| | | | | | | | | | | | |".to_string());
+    tab_lines.push("| | | | | | | | | | | | | | | | |".to_string());
     tab_lines.push(format!(
-        "| {} | | | {} | {} | | {} / {} | {} | | {} | {} | {} / {} |",
+        "| {} | | | {} | {} | | {} / {} | {} | | {} | {} | {} | {} / {} | | {} | {} |",
         "{{< i18n \"total\" >}}",
         total_counts.matches_found,
         total_counts.blackouts,
@@ -123,6 +129,7 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
         } else {
             "".to_string()
         },
+        rational_str(&total_counts.offers_mb),
         if total_counts.offers_mb.offers_noted {
             total_counts
                 .offers_mb
@@ -139,7 +146,35 @@ pub(crate) fn tab_md(cmp_data: &Vec<(String, CmpData)>, lang: Language) -> Strin
         } else {
             "".to_string()
         },
+        payout_str(total_counts.pot.as_ref(), lang),
+        winnings_str(total_counts.pot.as_ref(), lang),
     ));
 
     tab_lines.join("\n")
 }
+
+/// the pot paid out to the group (empty if the pot is not tracked)
+fn payout_str(pot: Option<&SumPot>, lang: Language) -> String {
+    pot.map(|p| p.payout.to_formatted_string(&lang.number_formatting()))
+        .unwrap_or_default()
+}
+
+/// the money won by individuals in total (empty if the pot is not tracked)
+fn winnings_str(pot: Option<&SumPot>, lang: Language) -> String {
+    pot.map(|p| {
+        p.winnings
+            .values()
+            .sum::<u128>()
+            .to_formatted_string(&lang.number_formatting())
+    })
+    .unwrap_or_default()
+}
+
+/// how many of the rated decisions on offers were rational (empty if none was rated)
+fn rational_str(offers: &SumOffersMB) -> String {
+    if offers.offers_rated > 0 {
+        format!("{} / {}", offers.offers_rational, offers.offers_rated)
+    } else {
+        "".to_string()
+    }
+}
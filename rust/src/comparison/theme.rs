@@ -19,6 +19,16 @@ pub(super) fn lut_theme(theme: u8) -> Flavor {
     }
 }
 
+/// get a diverging colorscale (negative -> neutral -> positive) which can be used with plotly in
+/// the specific theme. The neutral color is the background of the theme.
+pub(super) fn plotly_diverging_colorscale(palette: &Flavor) -> ColorScale {
+    ColorScale::Vector(vec![
+        ColorScaleElement(0.00, palette.get_color(ColorName::Red).hex.to_string()),
+        ColorScaleElement(0.50, palette.get_color(ColorName::Surface0).hex.to_string()),
+        ColorScaleElement(1.00, palette.get_color(ColorName::Green).hex.to_string()),
+    ])
+}
+
 /// get a colorscale which can be used with plotly in the specific theme
 pub(super) fn plotly_colorscale(_palette: &Flavor) -> ColorScale {
     let palette = PALETTE.latte;
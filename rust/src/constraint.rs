@@ -25,6 +25,7 @@
 pub mod check_type;
 pub mod compare;
 pub mod evaluate_predicates;
+pub mod offer_rating;
 pub mod parse;
 pub mod parse_utils;
 
@@ -34,28 +35,36 @@ pub(super) mod report_hdr;
 pub(super) mod report_summary;
 pub(super) mod simulate;
 
+mod lint;
 mod report_predicates;
+mod sensitivity;
 
 use std::hash::{Hash, Hasher};
 
 use anyhow::Result;
 use rust_decimal::{dec, Decimal};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::constraint::check_type::CheckType;
+use crate::constraint::offer_rating::OfferRating;
 use crate::matching_repr::{bitset::Bitset, MaskedMatching};
 use crate::ruleset_data::dummy::DummyData;
 use crate::ruleset_data::RuleSetData;
+use crate::solution_store::SolutionStore;
+use crate::solver::efficiency::DecisionRating;
+use crate::solver::remaining::RemainingEvents;
 use crate::tree::TreeConfig;
 use crate::{LightCnt, MapS};
 
 /// An offer attached to an event. There are various ways in which offers can be made which is
 /// represented in this enum.
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum Offer {
     /// an offer where a single person wins the money
     Single {
         /// the amount of money which was offered
+        #[serde(skip_serializing_if = "Option::is_none")]
         amount: Option<u128>,
         /// the individual which accepted this offer
         by: String,
@@ -68,6 +77,7 @@ pub enum Offer {
     /// an offer where the a pair was offered money only for them
     SinglePair {
         /// the amount of money which was offered
+        #[serde(skip_serializing_if = "Option::is_none")]
         amount: Option<u128>,
         /// the individual from set_b to which this offer was made
         #[serde(rename = "byA")]
@@ -84,6 +94,7 @@ pub enum Offer {
     /// an offer where the group was offered money for the whole group
     Group {
         /// the amount of money which was offered
+        #[serde(skip_serializing_if = "Option::is_none")]
         amount: Option<u128>,
         /// the individual which accepted this offer
         by: String,
@@ -91,6 +102,7 @@ pub enum Offer {
     /// an offer where a pair was offered money for the whole group
     GroupPair {
         /// the amount of money which was offered
+        #[serde(skip_serializing_if = "Option::is_none")]
         amount: Option<u128>,
         /// the individual from set_b to which this offer was made
         #[serde(rename = "byA")]
@@ -114,7 +126,7 @@ impl Offer {
 }
 
 /// collects the different types a constraint can have (MB/MN)
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum ConstraintType {
     /// a matching-night constraint
     Night {
@@ -123,6 +135,7 @@ pub enum ConstraintType {
         /// comment for this event
         comment: String,
         /// offer made for this constraint if set
+        #[serde(skip_serializing_if = "Option::is_none")]
         offer: Option<Offer>,
     },
     /// a match-box constraint
@@ -132,6 +145,7 @@ pub enum ConstraintType {
         /// comment for this event
         comment: String,
         /// offer made for this constraint if set
+        #[serde(skip_serializing_if = "Option::is_none")]
         offer: Option<Offer>,
     },
 }
@@ -249,7 +263,15 @@ pub struct Constraint {
     /// vector is filled)
     left_after: Option<u128>,
     /// all solutions left after applying this constraint (might not be filled)
-    left_poss: Vec<MaskedMatching>,
+    left_poss: SolutionStore,
+    /// how many further events are needed to solve the game after this constraint (if estimated)
+    remaining: Option<RemainingEvents>,
+    /// how good the decision for this constraint was compared to the strategies (if rated)
+    rating: Option<DecisionRating>,
+    /// how rational the decision on the offer for this constraint was (if rated)
+    offer_rating: Option<OfferRating>,
+    /// the prize pot after this constraint (if the pot is tracked)
+    pot_after: Option<u128>,
 
     /// ruleset-specific data where ruleset-specific stats can be collected
     pub(crate) ruleset_data: Option<Box<dyn RuleSetData>>,
@@ -267,7 +289,7 @@ impl Hash for Constraint {
 
         // Sort the map_s entries by key to ensure stable hashing
         let mut sorted_entries: Vec<_> = self.map_s.iter().collect();
-        sorted_entries.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b)); // Sort by key lexicographically
+        sorted_entries.sort_by_key(|(key_a, _)| *key_a); // Sort by key lexicographically
 
         // Hash each sorted entry
         for (key, value) in sorted_entries {
@@ -299,7 +321,11 @@ impl Default for Constraint {
             eliminated_tab: vec![vec![0; 3]; 3],
             information: None,
             left_after: None,
-            left_poss: vec![],
+            left_poss: Default::default(),
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot_after: None,
             ruleset_data: Some(Box::new(DummyData::default())),
             known_lights: 0,
             tree_cfg: vec![],
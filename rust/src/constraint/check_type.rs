@@ -7,12 +7,13 @@
 
 use std::collections::BTreeMap;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{matching_repr::IdBase, LightCnt};
 
 /// Type used to decide how to check a matching against a constraint.
-#[derive(Deserialize, Debug, Clone, Hash, PartialEq)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Hash, PartialEq)]
 pub enum CheckType {
     /// `Eq` ensures that the *values* of the provided maps map to the same key in all remaining
     /// solutions. The keys given in the constraints don't matter at all
@@ -29,6 +30,18 @@ pub enum CheckType {
     HintCntMatch(usize),
 }
 
+/// How surprising the actual outcome (amount of lights) of a constraint was
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Surprise {
+    /// the probability of the actual outcome
+    pub prob: f64,
+    /// the percentile of the actual amount of lights among all outcomes (`P[L < l] + P[L = l]/2`)
+    pub percentile: f64,
+    /// the information of the actual outcome minus the expected information (positive if more was
+    /// learned than expected)
+    pub luck: f64,
+}
+
 impl CheckType {
     /// Return the lights-count if this [`CheckType`] is `Lights`.
     pub fn as_lights(&self) -> Option<LightCnt> {
@@ -109,6 +122,28 @@ impl CheckType {
             _ => None,
         }
     }
+
+    /// calculate how surprising the actual outcome was compared to all possible outcomes
+    ///
+    /// Depending on the check-type this might not be applicable -> `None`
+    pub(super) fn calc_surprise(&self) -> Option<Surprise> {
+        match self {
+            CheckType::Lights(l, ls) => {
+                let total = ls.values().sum::<u128>() as f64;
+                if total == 0.0 {
+                    return None;
+                }
+                let prob = *ls.get(&(*l as IdBase))? as f64 / total;
+                let below = ls.range(..*l as IdBase).map(|(_, c)| *c).sum::<u128>() as f64;
+                Some(Surprise {
+                    prob,
+                    percentile: below / total + prob / 2.0,
+                    luck: -prob.log2() + self.calc_expected_value()?,
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -203,4 +238,24 @@ mod tests {
         let x = ct.calc_expected_value();
         assert_eq!(x, None);
     }
+
+    #[test]
+    fn calc_surprise_simple() {
+        let ls = vec![(1, 3), (2, 1)].into_iter().collect::<BTreeMap<_, _>>();
+        let s = CheckType::Lights(2, ls.clone()).calc_surprise().unwrap();
+        assert_eq!(s.prob, 0.25);
+        assert_eq!(s.percentile, 0.875);
+        // I = 2 bits, E[I] = 0.81 bits
+        assert_eq!(round2(s.luck), 1.19);
+
+        let s = CheckType::Lights(1, ls.clone()).calc_surprise().unwrap();
+        assert_eq!(s.prob, 0.75);
+        assert_eq!(s.percentile, 0.375);
+        assert_eq!(round2(s.luck), -0.4);
+
+        // impossible outcome
+        assert_eq!(CheckType::Lights(0, ls).calc_surprise(), None);
+        assert_eq!(CheckType::Lights(0, BTreeMap::new()).calc_surprise(), None);
+        assert_eq!(CheckType::Sold.calc_surprise(), None);
+    }
 }
@@ -7,13 +7,22 @@
 //! The root is [`ComparisonData`] (which is at some point serialized/stored so the comparison can take
 //! place later)
 
+use std::collections::BTreeMap;
+use std::fmt;
+
 use anyhow::{Context, Result};
 use rust_decimal::{dec, Decimal};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constraint::{Constraint, ConstraintGetters, ConstraintType},
+    collector::CollectedStats,
+    constraint::{
+        check_type::Surprise, offer_rating::OfferRating, Constraint, ConstraintGetters,
+        ConstraintType,
+    },
     matching_repr::MaskedMatching,
+    solver::efficiency::DecisionRating,
+    solver::remaining::RemainingEvents,
     LightCnt,
 };
 
@@ -21,12 +30,31 @@ use crate::{
 ///
 /// - `events` are the chronological evaluation events (MB/MN/Initial).
 /// - `cnts` are aggregated counters and summary data.
+/// - `collected` are the statistics of the user-defined collectors.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ComparisonData {
     /// stats for the trail of events which happened in the season
     pub(crate) events: Vec<EvalEvent>,
     /// summary stats for the whole season
     pub(crate) cnts: SumCounts,
+    /// stats of the user-defined collectors (see [`crate::collector`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) collected: Vec<CollectedStats>,
+    /// the conditional probabilities queried in the game-config (`queryConditional`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) conditional: Vec<ConditionalStats>,
+}
+
+/// The probabilities of the 1:1 matchings in the solutions left given that all pairs of the
+/// `condition` are a match
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConditionalStats {
+    /// the pairs (set_a -> set_b) which are assumed to be a match
+    pub condition: BTreeMap<String, String>,
+    /// the amount of solutions left fulfilling the condition
+    pub total: u128,
+    /// the probability (in percent) of each 1:1 matching (set_a -> set_b -> probability)
+    pub probs: BTreeMap<String, BTreeMap<String, f64>>,
 }
 
 /// One recorded event (MB/MN/Initial).
@@ -166,6 +194,51 @@ impl EvalEvent {
         MB(eval_mb) => Some(eval_mb.lights_total? - eval_mb.lights_known_before),
         Initial(ini) => None
     );
+
+    eval_event_query_data!(
+        remaining_expected,
+        " (expected amount of further events to solve the game, if estimated)",
+        f64,
+        MN(eval_mn) => Some(eval_mn.remaining?.expected),
+        MB(eval_mb) => Some(eval_mb.remaining?.expected),
+        Initial(ini) => None
+    );
+
+    eval_event_query_data!(
+        win_probability,
+        " (probability to still win the game in percent, if estimated)",
+        f64,
+        MN(eval_mn) => Some(eval_mn.remaining?.win * 100.0),
+        MB(eval_mb) => Some(eval_mb.remaining?.win * 100.0),
+        Initial(ini) => None
+    );
+
+    eval_event_query_data!(
+        efficiency,
+        " (share of the best expected information achieved by the decision in percent, if rated)",
+        f64,
+        MN(eval_mn) => Some(eval_mn.rating?.efficiency()? * 100.0),
+        MB(eval_mb) => Some(eval_mb.rating?.efficiency()? * 100.0),
+        Initial(ini) => None
+    );
+
+    eval_event_query_data!(
+        luck,
+        " (information of the actual outcome minus the expected information in bits, if known)",
+        f64,
+        MN(eval_mn) => Some(eval_mn.surprise?.luck),
+        MB(eval_mb) => Some(eval_mb.surprise?.luck),
+        Initial(ini) => None
+    );
+
+    eval_event_query_data!(
+        pot,
+        " (prize pot after the event, if tracked)",
+        u128,
+        MN(eval_mn) => eval_mn.pot,
+        MB(eval_mb) => eval_mb.pot,
+        Initial(ini) => None
+    );
 }
 
 /// a collection of stats for the initial configuration to be used in a comparison with other seasons
@@ -197,6 +270,21 @@ pub struct EvalMB {
     pub offer: bool,
     /// the matching in this event (if the check-type is lights)
     pub matching: Option<MaskedMatching>,
+    /// how many further events are needed to solve the game after this event (if estimated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<RemainingEvents>,
+    /// how good the decision for this event was compared to the strategies (if rated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<DecisionRating>,
+    /// how surprising the outcome of this event was (if the check-type is lights)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surprise: Option<Surprise>,
+    /// how rational the decision on the offer for this event was (if rated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offer_rating: Option<OfferRating>,
+    /// the prize pot after this event (if the pot is tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot: Option<u128>,
 }
 
 /// a collection of stats for a matching-night to be used in a comparison with other seasons
@@ -219,6 +307,18 @@ pub struct EvalMN {
     pub offer: bool,
     /// the matching in this event (if the check-type is lights)
     pub matching: Option<MaskedMatching>,
+    /// how many further events are needed to solve the game after this event (if estimated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<RemainingEvents>,
+    /// how good the decision for this event was compared to the strategies (if rated)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<DecisionRating>,
+    /// how surprising the outcome of this event was (if the check-type is lights)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surprise: Option<Surprise>,
+    /// the prize pot after this event (if the pot is tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot: Option<u128>,
 }
 
 /// Aggregated counts and summary metrics for a run / ruleset.
@@ -240,6 +340,9 @@ pub(crate) struct SumCounts {
     pub(crate) offers_mn: SumOffersMN,
     /// stats on the offers regarding match-boxes
     pub(crate) offers_mb: SumOffersMB,
+    /// the prize money of the season (if the pot is tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pot: Option<SumPot>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -252,6 +355,44 @@ impl Default for SumCounts {
             solvable_in: None,
             offers_mn: Default::default(),
             offers_mb: Default::default(),
+            pot: None,
+        }
+    }
+}
+
+/// The prize money paid out in a season
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SumPot {
+    /// the pot paid out to the group (`0` if the season was not won in time)
+    pub(crate) payout: u128,
+    /// the money won by individuals via single offers (by name)
+    pub(crate) winnings: BTreeMap<String, u128>,
+}
+
+impl fmt::Display for SumPot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Payout: {}", self.payout)?;
+        if !self.winnings.is_empty() {
+            write!(
+                f,
+                "  Won by individuals: {}",
+                self.winnings
+                    .iter()
+                    .map(|(name, m)| format!("{name}: {m}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SumPot {
+    /// Increment this [`SumPot`] with values found in `other`. Works in-place
+    pub(crate) fn add(&mut self, other: &Self) {
+        self.payout += other.payout;
+        for (name, m) in &other.winnings {
+            *self.winnings.entry(name.clone()).or_default() += m;
         }
     }
 }
@@ -274,6 +415,12 @@ pub(crate) struct SumOffersMB {
     pub(crate) offer_and_match: u64,
     /// how much money was offered in total
     pub(crate) offered_money: u128,
+    /// how many decisions on offers were rated
+    #[serde(default)]
+    pub(crate) offers_rated: u64,
+    /// how many of the rated decisions on offers maximized the expected value
+    #[serde(default)]
+    pub(crate) offers_rational: u64,
 }
 /// Collect sums regarding offers made for MNs
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -299,6 +446,8 @@ impl Default for SumOffersMB {
             offers_noted: false,
             offers_cnt: 0,
             offer_and_match: 0,
+            offers_rated: 0,
+            offers_rational: 0,
             offered_money: 0,
         }
     }
@@ -326,6 +475,8 @@ impl SumOffersMB {
         self.offered_money += other.offered_money;
         self.offer_and_match += other.offer_and_match;
         self.offers_noted |= other.offers_noted;
+        self.offers_rated += other.offers_rated;
+        self.offers_rational += other.offers_rational;
     }
 }
 impl SumOffersMN {
@@ -346,6 +497,9 @@ impl SumCounts {
 
         self.offers_mn.add(&other.offers_mn);
         self.offers_mb.add(&other.offers_mb);
+        if let Some(pot) = &other.pot {
+            self.pot.get_or_insert_with(Default::default).add(pot);
+        }
     }
 }
 
@@ -368,6 +522,10 @@ impl Constraint {
                 bits_left_after: (self.left_after.context("total_left unset")? as f64).log2(),
                 comment: meta_b,
                 matching: self.is_lights().then(|| self.map.clone()),
+                remaining: self.remaining,
+                rating: self.rating,
+                surprise: self.surprise(),
+                pot: self.pot_after,
             }))),
             ConstraintType::Box { num, .. } => Ok(Some(EvalEvent::MB(EvalMB {
                 offer: {
@@ -384,6 +542,11 @@ impl Constraint {
                 bits_left_after: (self.left_after.context("total_left unset")? as f64).log2(),
                 comment: meta_b,
                 matching: self.is_lights().then(|| self.map.clone()),
+                remaining: self.remaining,
+                rating: self.rating,
+                surprise: self.surprise(),
+                offer_rating: self.offer_rating,
+                pot: self.pot_after,
             }))),
         }
     }
@@ -420,7 +583,11 @@ mod tests {
             },
             build_tree: false,
             tree_cfg: vec![],
-            left_poss: vec![],
+            left_poss: Default::default(),
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot_after: None,
             ruleset_data: Some(Box::new(DummyData::default())),
             known_lights: 0,
         };
@@ -445,6 +612,8 @@ mod tests {
                 sold_but_match_active: true,
                 offers_noted: false,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
                 offered_money: 0,
                 offers_cnt: 0,
             },
@@ -457,6 +626,7 @@ mod tests {
             matches_found: 1,
             won_in: None,
             solvable_in: Some((true, "".to_string())),
+            pot: None,
         };
         let b = SumCounts {
             blackouts: 2,
@@ -466,6 +636,8 @@ mod tests {
                 sold_but_match_active: true,
                 offers_noted: false,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
                 offered_money: 0,
                 offers_cnt: 0,
             },
@@ -478,6 +650,10 @@ mod tests {
             matches_found: 0,
             won_in: Some((true, "MN#1".to_string())),
             solvable_in: Some((false, "".to_string())),
+            pot: Some(SumPot {
+                payout: 100,
+                winnings: BTreeMap::from([("A".to_string(), 5)]),
+            }),
         };
         a.add(&b);
 
@@ -485,6 +661,14 @@ mod tests {
         assert_eq!(a.matches_found, 1);
         assert_eq!(a.offers_mn.sold_cnt, 4);
         assert!(a.offers_mb.sold_but_match_active);
+        assert_eq!(a.pot, b.pot);
+        a.add(&b);
+        assert_eq!(a.pot.as_ref().map(|p| p.payout), Some(200));
+        assert_eq!(
+            a.pot.as_ref().map(|p| p.to_string()),
+            Some("Payout: 200  Won by individuals: A: 10".to_string())
+        );
+        assert_eq!(a.pot.map(|p| p.winnings["A"]), Some(10));
     }
 
     #[test]
@@ -499,6 +683,24 @@ mod tests {
             comment: "mb".to_string(),
             offer: true,
             matching: Default::default(),
+            remaining: Some(RemainingEvents {
+                expected: 2.5,
+                worst: 4,
+                sampled: 8,
+                win: 0.75,
+            }),
+            rating: Some(DecisionRating {
+                chosen: 0.5,
+                best: Some(1.0),
+                random: None,
+            }),
+            surprise: Some(Surprise {
+                prob: 0.25,
+                percentile: 0.875,
+                luck: 1.25,
+            }),
+            offer_rating: None,
+            pot: Some(150_000),
         };
         let ev_mb = EvalEvent::MB(mb.clone());
 
@@ -512,6 +714,10 @@ mod tests {
             comment: "mn".to_string(),
             offer: false,
             matching: Default::default(),
+            remaining: None,
+            rating: None,
+            surprise: None,
+            pot: None,
         };
         let ev_mn = EvalEvent::MN(mn.clone());
 
@@ -541,6 +747,31 @@ mod tests {
         assert_eq!(ev_mb.new_lights(|_| false, |_| true, |_| false), Some(2));
         assert_eq!(ev_mn.new_lights(|_| true, |_| false, |_| false), Some(2));
 
+        // remaining_expected: only if estimated
+        assert_eq!(
+            ev_mb.remaining_expected(|_| false, |_| true, |_| false),
+            Some(2.5)
+        );
+        assert_eq!(
+            ev_mn.remaining_expected(|_| true, |_| false, |_| false),
+            None
+        );
+        assert_eq!(
+            ev_ini.remaining_expected(|_| false, |_| false, |_| true),
+            None
+        );
+        assert_eq!(
+            ev_mb.win_probability(|_| false, |_| true, |_| false),
+            Some(75.0)
+        );
+        assert_eq!(ev_mn.win_probability(|_| true, |_| false, |_| false), None);
+        assert_eq!(ev_mb.efficiency(|_| false, |_| true, |_| false), Some(50.0));
+        assert_eq!(ev_mn.efficiency(|_| true, |_| false, |_| false), None);
+        assert_eq!(ev_mb.luck(|_| false, |_| true, |_| false), Some(1.25));
+        assert_eq!(ev_mn.luck(|_| true, |_| false, |_| false), None);
+        assert_eq!(ev_mb.pot(|_| false, |_| true, |_| false), Some(150_000));
+        assert_eq!(ev_mn.pot(|_| true, |_| false, |_| false), None);
+
         // lights_known_before: present for MB/MN; Initial -> None
         assert_eq!(
             ev_mb.lights_known_before(|_| false, |_| true, |_| false),
@@ -7,6 +7,9 @@
 //! Note: There is also evaluate_predicates which contains functions serving as predicates during
 //! the evaluation.
 
+use crate::constraint::offer_rating::OfferRating;
+use crate::solver::efficiency::DecisionRating;
+use crate::solver::remaining::RemainingEvents;
 use crate::{constraint::Constraint, Rem};
 
 use anyhow::{bail, ensure, Result};
@@ -35,11 +38,16 @@ impl ConstraintSolvable for Constraint {
             return Ok(None);
         }
 
+        let mut sols = self.left_poss.iter();
         // choose one solution to be the prototype for the partial solution
-        let mut sol = self.left_poss[0].clone();
+        let Some(sol) = sols.next() else {
+            return Ok(None);
+        };
+        let mut sol = sol?;
 
         // overlay all other possible solutions to check if there is a common partial solution
-        for i in &self.left_poss[1..] {
+        for i in sols {
+            let i = &i?;
             if i.len() != sol.len() {
                 // println!("length check failed");
                 bail!("inequal length between the solutions");
@@ -119,6 +127,26 @@ impl Constraint {
 
         Some(rem)
     }
+
+    /// Store how many further events are needed to solve the game after this constraint
+    pub(crate) fn set_remaining_events(&mut self, remaining: Option<RemainingEvents>) {
+        self.remaining = remaining;
+    }
+
+    /// Store how good the decision for this constraint was compared to the strategies
+    pub(crate) fn set_decision_rating(&mut self, rating: Option<DecisionRating>) {
+        self.rating = rating;
+    }
+
+    /// Store how rational the decision on the offer for this constraint was
+    pub(crate) fn set_offer_rating(&mut self, offer_rating: Option<OfferRating>) {
+        self.offer_rating = offer_rating;
+    }
+
+    /// Store the prize pot after this constraint
+    pub(crate) fn set_pot_after(&mut self, pot: Option<u128>) {
+        self.pot_after = pot;
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +159,7 @@ mod tests {
 
     use crate::constraint::CheckType;
     use crate::matching_repr::MaskedMatching;
+    use crate::solution_store::SolutionStore;
 
     #[test]
     #[allow(clippy::identity_op)]
@@ -218,18 +247,18 @@ mod tests {
     fn is_solvable_after_simple() {
         // left possibilities not captured -> cannot tell => none
         let c = Constraint {
-            left_poss: vec![],
+            left_poss: Default::default(),
             ..Default::default()
         };
         assert!(c.is_solvable_after().unwrap().is_none());
 
         // only one possibility left => definitely solvable
         let c = Constraint {
-            left_poss: vec![MaskedMatching::from_matching_ref(&[
+            left_poss: SolutionStore::from(vec![MaskedMatching::from_matching_ref(&[
                 vec![0],
                 vec![1],
                 vec![2],
-            ])],
+            ])]),
             ..Default::default()
         };
         assert!(c.is_solvable_after().unwrap().unwrap());
@@ -237,22 +266,22 @@ mod tests {
         // multiple total solutions left, but there is one unambiguous partial working solution which applies to
         // all solutions left
         let c = Constraint {
-            left_poss: vec![
+            left_poss: SolutionStore::from(vec![
                 MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
                 MaskedMatching::from_matching_ref(&[vec![0, 3], vec![1], vec![2]]),
                 MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2, 3]]),
-            ],
+            ]),
             ..Default::default()
         };
         assert!(c.is_solvable_after().unwrap().unwrap());
 
         // multiple total solutions left, also not one unambiguous partial solution existing
         let c = Constraint {
-            left_poss: vec![
+            left_poss: SolutionStore::from(vec![
                 MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
                 MaskedMatching::from_matching_ref(&[vec![0, 3], vec![1], vec![2]]),
                 MaskedMatching::from_matching_ref(&[vec![3], vec![1], vec![2, 0]]),
-            ],
+            ]),
             ..Default::default()
         };
         assert!(!c.is_solvable_after().unwrap().unwrap());
@@ -6,8 +6,12 @@
 //!
 //! Note there is also evaluate which contains the non-predicate functions
 
+use anyhow::Result;
+
+use crate::constraint::offer_rating::OfferRating;
 use crate::constraint::{CheckType, Constraint, ConstraintType, Offer};
-use crate::matching_repr::{bitset::Bitset, MaskedMatching};
+use crate::matching_repr::bitset::Bitset;
+use crate::solution_store::SolutionStore;
 
 /// a trait which collect all functionalities to evaluate a constraint
 ///
@@ -24,9 +28,11 @@ pub trait ConstraintEval {
     /// whether this constraint was sold -> no information gain
     fn is_sold(&self) -> bool;
     /// whether this is a match-box and the match is definitive in the solution
-    fn is_mb_hit(&self, sols: Option<&Vec<MaskedMatching>>) -> bool;
+    fn is_mb_hit(&self, sols: Option<&SolutionStore>) -> Result<bool>;
     /// get the offer if there has been one for this constraint
     fn try_get_offer(&self) -> Option<Offer>;
+    /// how rational the decision on the offer for this constraint was (if rated)
+    fn offer_rating(&self) -> Option<OfferRating>;
     /// whether this constraint might win the game
     fn might_won(&self) -> bool;
     /// whether the game was won with thie constraint
@@ -38,6 +44,18 @@ impl Constraint {
     pub fn is_lights(&self) -> bool {
         matches!(self.r#check, CheckType::Lights { .. })
     }
+
+    /// the expected information (in bits) of this constraint given the distribution of the lights
+    /// among the solutions left before it (only if lights are the check-type and the
+    /// distribution is known)
+    pub fn expected_information(&self) -> Option<f64> {
+        match &self.check {
+            CheckType::Lights(_, ls) if ls.values().any(|&c| c > 0) => {
+                Some(-self.check.calc_expected_value()?)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl ConstraintEval for Constraint {
@@ -70,19 +88,24 @@ impl ConstraintEval for Constraint {
         false
     }
 
-    fn is_mb_hit(&self, solutions: Option<&Vec<MaskedMatching>>) -> bool {
+    fn is_mb_hit(&self, solutions: Option<&SolutionStore>) -> Result<bool> {
         if let Some(sols) = solutions {
             if let ConstraintType::Box { .. } = self.r#type {
-                return sols.iter().all(|sol| {
-                    self.map.iter_pairs().all(|(a, b)| {
+                for sol in sols.iter() {
+                    let sol = sol?;
+                    let hit = self.map.iter_pairs().all(|(a, b)| {
                         sol.slot_mask(a as usize)
                             .unwrap_or(&Bitset::empty())
                             .contains_idx(b)
-                    })
-                });
+                    });
+                    if !hit {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
             }
         }
-        false
+        Ok(false)
     }
 
     fn might_won(&self) -> bool {
@@ -107,6 +130,10 @@ impl ConstraintEval for Constraint {
             ConstraintType::Box { offer, .. } => offer.clone(),
         }
     }
+
+    fn offer_rating(&self) -> Option<OfferRating> {
+        self.offer_rating
+    }
 }
 
 #[cfg(test)]
@@ -183,11 +210,11 @@ mod tests {
     #[test]
     fn is_mb_hit_simple() {
         // match is 0 -> 2
-        let sol = vec![MaskedMatching::from_matching_ref(&[
+        let sol = SolutionStore::from(vec![MaskedMatching::from_matching_ref(&[
             vec![2],
             vec![1],
             vec![0],
-        ])];
+        ])]);
 
         let c = Constraint {
             r#type: ConstraintType::Night {
@@ -198,7 +225,7 @@ mod tests {
             map: MaskedMatching::from_matching_ref(&[vec![2]]),
             ..Default::default()
         };
-        assert!(!c.is_mb_hit(Some(&sol)));
+        assert!(!c.is_mb_hit(Some(&sol)).unwrap());
 
         let c = Constraint {
             r#type: ConstraintType::Box {
@@ -209,7 +236,7 @@ mod tests {
             map: MaskedMatching::from_matching_ref(&[vec![0]]),
             ..Default::default()
         };
-        assert!(!c.is_mb_hit(Some(&sol)));
+        assert!(!c.is_mb_hit(Some(&sol)).unwrap());
 
         let c = Constraint {
             r#type: ConstraintType::Box {
@@ -220,6 +247,6 @@ mod tests {
             map: MaskedMatching::from_matching_ref(&[vec![2]]),
             ..Default::default()
         };
-        assert!(c.is_mb_hit(Some(&sol)));
+        assert!(c.is_mb_hit(Some(&sol)).unwrap());
     }
 }
@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module contains the checks of the linter (see [`crate::lint`]) which concern a single
+//! constraint as it was parsed from the yaml file.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+use crate::constraint::parse::ConstraintParse;
+use crate::constraint::{CheckType, ConstraintType};
+use crate::lint::spans::{PathSeg, SpanIndex};
+use crate::lint::{did_you_mean, Diagnostic};
+use crate::yaml_path;
+use crate::Lut;
+
+/// Check whether `name` is contained in `lut`. If not an error with a suggestion is returned.
+fn check_name(
+    name: &str,
+    lut: &Lut,
+    set: &str,
+    position: Option<crate::lint::Position>,
+) -> Option<Diagnostic> {
+    if lut.contains_key(name) {
+        return None;
+    }
+    Some(
+        Diagnostic::error(format!("'{}' is not part of {}", name, set), position)
+            .with_suggestion(did_you_mean(name, lut.keys())),
+    )
+}
+
+impl ConstraintParse {
+    /// The kind of event ("Night"/"Box") and its number
+    pub(crate) fn kind_and_num(&self) -> (&'static str, Decimal) {
+        match &self.r#type {
+            ConstraintType::Night { num, .. } => ("Night", *num),
+            ConstraintType::Box { num, .. } => ("Box", *num),
+        }
+    }
+
+    /// Lint this constraint which is the `idx`th constraint in the file.
+    ///
+    /// - `lut_a`/`lut_b`: the known names
+    /// - `map_len`: how many pairs a night must contain
+    /// - `spans`: positions of the nodes in the yaml file
+    pub(crate) fn lint(
+        &self,
+        idx: usize,
+        lut_a: &Lut,
+        lut_b: &Lut,
+        map_len: usize,
+        spans: &SpanIndex,
+    ) -> Vec<Diagnostic> {
+        let mut diags = vec![];
+        let pos = |rest: &[PathSeg]| {
+            let mut p = yaml_path!["constraints", idx].to_vec();
+            p.extend_from_slice(rest);
+            p
+        };
+        let is_night = matches!(self.r#type, ConstraintType::Night { .. });
+        let name = {
+            let (kind, num) = self.kind_and_num();
+            format!("{kind} #{num}")
+        };
+
+        // unknown names
+        for (k, v) in &self.map_s {
+            let path = pos(&yaml_path!["map", k]);
+            diags.extend(check_name(k, lut_a, "setA", spans.key(&path)));
+            diags.extend(check_name(v, lut_b, "setB", spans.value(&path)));
+        }
+        if let Some((a, bs)) = &self.exclude_s {
+            diags.extend(check_name(
+                a,
+                lut_a,
+                "setA",
+                spans.value(&pos(&yaml_path!["exclude", 0])),
+            ));
+            for (i, b) in bs.iter().enumerate() {
+                diags.extend(check_name(
+                    b,
+                    lut_b,
+                    "setB",
+                    spans.value(&pos(&yaml_path!["exclude", 1, i])),
+                ));
+            }
+        }
+
+        // more lights than pairs
+        if let CheckType::Lights(l, _) = self.check {
+            if l as usize > self.map_s.len() {
+                diags.push(Diagnostic::error(
+                    format!(
+                        "{} has {} lights but only {} pairs",
+                        name,
+                        l,
+                        self.map_s.len()
+                    ),
+                    spans.value(&pos(&yaml_path!["check"])),
+                ));
+            }
+        }
+
+        if is_night {
+            // someone from setB is seated with multiple people
+            let mut by_value: BTreeMap<&String, Vec<&String>> = BTreeMap::new();
+            for (k, v) in &self.map_s {
+                by_value.entry(v).or_default().push(k);
+            }
+            for (v, mut ks) in by_value.into_iter().filter(|(_, ks)| ks.len() > 1) {
+                ks.sort_by_key(|k| (spans.key(&pos(&yaml_path!["map", *k])), *k));
+                diags.push(Diagnostic::error(
+                    format!(
+                        "'{}' is seated multiple times in {} (with {})",
+                        v,
+                        name,
+                        ks.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")
+                    ),
+                    spans.value(&pos(&yaml_path!["map", ks[1]])),
+                ));
+            }
+
+            // not everyone is seated
+            if self.map_s.len() != map_len {
+                let mut missing = lut_a
+                    .iter()
+                    .filter(|(a, _)| {
+                        // in case of NToN the individuals can also be seated on the other side
+                        !(self.map_s.contains_key(*a)
+                            || lut_a == lut_b && self.map_s.values().any(|v| v == *a))
+                    })
+                    .collect::<Vec<_>>();
+                missing.sort_by_key(|(_, i)| **i);
+                let mut msg = format!(
+                    "{} contains {} pairs but {} are expected",
+                    name,
+                    self.map_s.len(),
+                    map_len
+                );
+                if !missing.is_empty() && self.map_s.len() < map_len {
+                    msg += &format!(
+                        ", missing: {}",
+                        missing
+                            .iter()
+                            .map(|(a, _)| a.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+                diags.push(Diagnostic::error(msg, spans.key(&pos(&yaml_path!["map"]))));
+            }
+
+            if self.exclude_s.is_some() {
+                diags.push(Diagnostic::error(
+                    "exclude is not supported for nights".to_string(),
+                    spans.key(&pos(&yaml_path!["exclude"])),
+                ));
+            }
+        } else {
+            match self.check {
+                CheckType::Lights(..) | CheckType::HintCntMatch(..) if self.map_s.len() != 1 => {
+                    diags.push(Diagnostic::error(
+                        format!(
+                            "{} must contain exactly one pair for this check (was: {})",
+                            name,
+                            self.map_s.len()
+                        ),
+                        spans.key(&pos(&yaml_path!["map"])),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if self.hidden && !self.tree_cfg.is_empty() {
+            diags.push(Diagnostic::warning(
+                format!("treeCfg of {} has no effect since it is hidden", name),
+                spans.key(&pos(&yaml_path!["treeCfg"])),
+            ));
+        }
+
+        diags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rust_decimal::dec;
+
+    use crate::lint::{Position, Severity};
+    use crate::tree::TreeConfigParse;
+    use crate::MapS;
+
+    /// build the luts A..D / a..d
+    fn luts() -> (Lut, Lut) {
+        (
+            Lut::from_iter(
+                ["A", "B", "C", "D"]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (s.to_string(), i)),
+            ),
+            Lut::from_iter(
+                ["a", "b", "c", "d"]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (s.to_string(), i)),
+            ),
+        )
+    }
+
+    /// build a night with `map` and `lights`
+    fn night(map: &[(&str, &str)], lights: u8) -> ConstraintParse {
+        ConstraintParse {
+            r#type: ConstraintType::Night {
+                num: dec![1],
+                comment: "".to_string(),
+                offer: None,
+            },
+            map_s: MapS::from_iter(map.iter().map(|(a, b)| (a.to_string(), b.to_string()))),
+            check: CheckType::Lights(lights, Default::default()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lint_valid() {
+        let (lut_a, lut_b) = luts();
+        let c = night(&[("A", "a"), ("B", "b"), ("C", "c"), ("D", "d")], 2);
+        assert_eq!(c.lint(0, &lut_a, &lut_b, 4, &SpanIndex::default()), vec![]);
+    }
+
+    #[test]
+    fn lint_unknown_name() {
+        let (lut_a, lut_b) = luts();
+        let c = ConstraintParse {
+            map_s: MapS::from_iter([("A".to_string(), "e".to_string())]),
+            check: CheckType::Lights(0, Default::default()),
+            ..Default::default()
+        };
+        let diags = c.lint(0, &lut_a, &lut_b, 4, &SpanIndex::default());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].message, "'e' is not part of setB");
+    }
+
+    #[test]
+    fn lint_night() {
+        let (lut_a, lut_b) = luts();
+        let c = night(&[("A", "a"), ("B", "a")], 3);
+        let msgs = c
+            .lint(0, &lut_a, &lut_b, 4, &SpanIndex::default())
+            .into_iter()
+            .map(|d| (d.severity, d.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            msgs,
+            vec![
+                (
+                    Severity::Error,
+                    "Night #1 has 3 lights but only 2 pairs".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "'a' is seated multiple times in Night #1 (with A, B)".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "Night #1 contains 2 pairs but 4 are expected, missing: C, D".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_box_hidden_tree() {
+        let (lut_a, lut_b) = luts();
+        let c = ConstraintParse {
+            r#type: ConstraintType::Box {
+                num: dec![1],
+                comment: "".to_string(),
+                offer: None,
+            },
+            map_s: MapS::from_iter([
+                ("A".to_string(), "a".to_string()),
+                ("B".to_string(), "b".to_string()),
+            ]),
+            check: CheckType::Lights(1, Default::default()),
+            hidden: true,
+            tree_cfg: vec![serde_yaml::from_str::<TreeConfigParse>("id: x").unwrap()],
+            ..Default::default()
+        };
+        let msgs = c
+            .lint(0, &lut_a, &lut_b, 4, &SpanIndex::default())
+            .into_iter()
+            .map(|d| (d.severity, d.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            msgs,
+            vec![
+                (
+                    Severity::Error,
+                    "Box #1 must contain exactly one pair for this check (was: 2)".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "treeCfg of Box #1 has no effect since it is hidden".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_position() {
+        let src = "constraints:\n  - map:\n      A: e\n";
+        let (spans, _) = SpanIndex::new(src).unwrap();
+        let (lut_a, lut_b) = luts();
+        let c = ConstraintParse {
+            map_s: MapS::from_iter([("A".to_string(), "e".to_string())]),
+            check: CheckType::Lights(0, Default::default()),
+            ..Default::default()
+        };
+        let diags = c.lint(0, &lut_a, &lut_b, 4, &spans);
+        assert_eq!(
+            diags[0].position,
+            Some(Position {
+                line: 3,
+                column: 10
+            })
+        );
+    }
+}
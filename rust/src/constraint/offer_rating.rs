@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module rates the decision made on an offer for a match box (sell the box or open it).
+//!
+//! The model is kept simple: A confirmed match is worth its share of the prize pot (the pot
+//! divided by the number of matches needed to win). Opening the box is therefore worth the
+//! probability of the pair being a match times this share. Selling the box is worth the money
+//! offered. The decision with the higher expected value is considered rational.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The rating of the decision made on an offer for a match box
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct OfferRating {
+    /// the probability that the pair in the box is a match at the time of the offer
+    pub prob: f64,
+    /// the expected value (money) of opening the box
+    pub open: f64,
+    /// the money offered for selling the box
+    pub sell: f64,
+    /// whether the box was sold
+    pub sold: bool,
+}
+
+impl OfferRating {
+    /// Rate the offer of `amount` for a box with a pair which is a match with probability `prob`.
+    /// `pot` is the prize pot of the season and `pairs` the number of matches needed to win it.
+    pub(crate) fn new(prob: f64, amount: u128, pot: u128, pairs: usize, sold: bool) -> Self {
+        let share = if pairs > 0 {
+            pot as f64 / pairs as f64
+        } else {
+            0.0
+        };
+        OfferRating {
+            prob,
+            open: prob * share,
+            sell: amount as f64,
+            sold,
+        }
+    }
+
+    /// whether selling the box had the higher expected value
+    pub fn sell_best(&self) -> bool {
+        self.sell > self.open
+    }
+
+    /// whether the decision made maximized the expected value
+    pub fn rational(&self) -> bool {
+        self.sold == self.sell_best()
+    }
+}
+
+impl fmt::Display for OfferRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            if self.sold { "sell" } else { "open" },
+            if self.rational() { "✓" } else { "✗" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn offer_rating_simple() {
+        // a match is worth 20000, opening the box 5000
+        let r = OfferRating::new(0.25, 10_000, 200_000, 10, true);
+        assert_eq!(r.open, 5_000.0);
+        assert_eq!(r.sell, 10_000.0);
+        assert!(r.sell_best());
+        assert!(r.rational());
+        assert_eq!(r.to_string(), "sell ✓");
+
+        // opening the box is worth 15000
+        let r = OfferRating::new(0.75, 10_000, 200_000, 10, true);
+        assert!(!r.sell_best());
+        assert!(!r.rational());
+        assert_eq!(r.to_string(), "sell ✗");
+
+        let r = OfferRating { sold: false, ..r };
+        assert!(r.rational());
+        assert_eq!(r.to_string(), "open ✓");
+
+        // no pairs to find -> opening is worthless
+        let r = OfferRating::new(1.0, 1, 200_000, 0, false);
+        assert_eq!(r.open, 0.0);
+        assert!(!r.rational());
+    }
+}
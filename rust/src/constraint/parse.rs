@@ -12,10 +12,12 @@ use anyhow::{Context, Result};
 use std::collections::BTreeMap;
 
 use rust_decimal::dec;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::constraint::parse_utils::convert_map_s_to_ids;
 use crate::constraint::{CheckType, Constraint, ConstraintType};
+use crate::game::format::sorted;
 use crate::matching_repr::bitset::Bitset;
 use crate::matching_repr::IdBase;
 use crate::ruleset_data::RuleSetData;
@@ -24,35 +26,47 @@ use crate::{LightCnt, Lut, MapS, Rename};
 
 /// this struct is only used when parsing the yaml file.
 /// The function [`ConstraintParse::finalize_parsing`] is intended to convert this to a regular constraint.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
 pub struct ConstraintParse {
     /// of what type this constraint is (e.g. MB/MN)
     pub(super) r#type: ConstraintType,
     /// the string+hashmap representation of the matching related to the constraint
-    #[serde(rename = "map")]
+    #[serde(rename = "map", serialize_with = "sorted")]
     pub(super) map_s: MapS,
     /// how the constraint needs to be checked (e.g. via lights)
     pub(super) check: CheckType,
     /// whether this constraint shall be hidden in the report/output
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub(super) hidden: bool,
     /// an option to disable the exluce functionality. Usually required when it is proven that
     /// a matches b0, but a also matches b1 which is left open and/or is specified in another
     /// constraint
-    #[serde(default, rename = "noExclude")]
+    #[serde(
+        default,
+        rename = "noExclude",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub(super) no_exclude: bool,
     /// a string representation to manually exclude matchings where
     /// individual from set_a matches with any of the individuals from set_b specified here
-    #[serde(rename = "exclude")]
+    #[serde(rename = "exclude", skip_serializing_if = "Option::is_none")]
     pub(super) exclude_s: Option<(String, Vec<String>)>,
     /// whether the result of this is still unknown (despite how check is set)
-    #[serde(default, rename = "resultUnknown")]
+    #[serde(
+        default,
+        rename = "resultUnknown",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub(super) result_unknown: bool,
     /// whether to hide the ruleset_data for this constraint
-    #[serde(default, rename = "hideRulesetData")]
+    #[serde(
+        default,
+        rename = "hideRulesetData",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub(super) hide_ruleset_data: bool,
     /// how to build .dot-tree(s) for this constraint,  leave empty to not built a tree at all
-    #[serde(default, rename = "treeCfg")]
+    #[serde(default, rename = "treeCfg", skip_serializing_if = "Vec::is_empty")]
     pub(super) tree_cfg: Vec<TreeConfigParse>,
 }
 
@@ -77,6 +91,18 @@ impl Default for ConstraintParse {
 }
 
 impl ConstraintParse {
+    /// Create a constraint of type `r#type` on the matching `map` (set_a -> set_b) which is
+    /// checked via `check`. All other options are set as if they were omitted in a game-config.
+    pub fn new(r#type: ConstraintType, map: MapS, check: CheckType) -> Self {
+        ConstraintParse {
+            r#type,
+            map_s: map,
+            check,
+            hide_ruleset_data: false,
+            ..Default::default()
+        }
+    }
+
     /// Convert a [`ConstraintParse`] (raw YAML-deserialized structure) into a runtime [`super::Constraint`].
     ///
     /// This performs:
@@ -151,6 +177,10 @@ impl ConstraintParse {
             information: None,
             left_after: None,
             left_poss: Default::default(),
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot_after: None,
             ruleset_data: (!self.hidden && !self.hide_ruleset_data).then_some(ruleset_data),
             known_lights,
             build_tree: !tree_cfg.is_empty(),
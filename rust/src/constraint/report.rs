@@ -39,7 +39,7 @@ impl Constraint {
         let mut ret = Vec::with_capacity(self.tree_cfg.len());
 
         // calculate the order in which the layers shall be shown
-        let ordering = tree_ordering(&self.left_poss, map_a);
+        let ordering = tree_ordering(&self.left_poss, map_a)?;
         // delegate drawing the tree to a dedicated module
         for c in &self.tree_cfg {
             c.dot_tree(
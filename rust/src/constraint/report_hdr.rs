@@ -12,6 +12,7 @@ use std::collections::HashMap;
 
 use comfy_table::{presets::NOTHING, Row, Table};
 
+use crate::constraint::check_type::Surprise;
 use crate::constraint::{CheckType, Constraint, ConstraintGetters};
 use crate::{prob_comfy_cell, LightCnt, MapS, Rem};
 
@@ -23,6 +24,8 @@ struct CheckTypeRender<'a> {
     i: Option<Vec<(LightCnt, f64)>>,
     /// the expeced value of the information
     e: Option<f64>,
+    /// how surprising the actual outcome was
+    s: Option<Surprise>,
 }
 
 impl fmt::Display for CheckTypeRender<'_> {
@@ -46,6 +49,15 @@ impl fmt::Display for CheckTypeRender<'_> {
                 if let Some(e) = self.e {
                     writeln!(f, "-> E[I]/bits: {:.2} = H", -e)?;
                 }
+                if let Some(s) = &self.s {
+                    writeln!(
+                        f,
+                        "-> P[l]: {:.1}% (percentile: {:.0}%), I - E[I] = {:+.2} bits",
+                        s.prob * 100.0,
+                        s.percentile * 100.0,
+                        s.luck
+                    )?;
+                }
                 write!(f, "{} lights ", l)?;
             }
         }
@@ -300,6 +312,7 @@ impl Constraint {
                 } else {
                     None
                 },
+                s: self.surprise(),
             },
             footer: format!(
                 "=> I = {} bits",
@@ -327,6 +340,7 @@ mod tests {
             check: &CheckType::Eq {},
             i: Some(vec![(0, 0.0)]),
             e: Some(0.0),
+            s: None,
         };
         assert_eq!(ctr.to_string(), "Eq ");
 
@@ -334,6 +348,7 @@ mod tests {
             check: &CheckType::HintCntMatch(2),
             i: Some(vec![(0, 0.0)]),
             e: Some(0.0),
+            s: None,
         };
         assert_eq!(ctr.to_string(), "Xcnt(2) ");
 
@@ -341,6 +356,7 @@ mod tests {
             check: &CheckType::Sold {},
             i: Some(vec![(0, 1.0)]),
             e: Some(0.0),
+            s: None,
         };
         assert_eq!(ctr.to_string(), "Nothing ");
 
@@ -348,6 +364,7 @@ mod tests {
             check: &CheckType::Nothing {},
             i: Some(vec![(0, 1.0)]),
             e: Some(0.0),
+            s: None,
         };
         assert_eq!(ctr.to_string(), "Nothing ");
 
@@ -355,6 +372,7 @@ mod tests {
             check: &CheckType::Lights(3, Default::default()),
             i: Some(vec![(0, 1.0)]),
             e: Some(-0.5),
+            s: None,
         };
         assert_eq!(
             ctr.to_string(),
@@ -365,6 +383,7 @@ mod tests {
             check: &CheckType::Lights(3, Default::default()),
             i: Some(vec![(0, 1.0)]),
             e: None,
+            s: None,
         };
         assert_eq!(ctr.to_string(), "-> I[l]/bits: {0: 1.00}\n3 lights ");
 
@@ -372,6 +391,7 @@ mod tests {
             check: &CheckType::Lights(3, Default::default()),
             i: None,
             e: None,
+            s: None,
         };
         assert_eq!(ctr.to_string(), "3 lights ");
 
@@ -379,8 +399,24 @@ mod tests {
             check: &CheckType::Lights(3, Default::default()),
             i: None,
             e: Some(-0.5),
+            s: None,
         };
         assert_eq!(ctr.to_string(), "-> E[I]/bits: 0.50 = H\n3 lights ");
+
+        let ctr = CheckTypeRender {
+            check: &CheckType::Lights(3, Default::default()),
+            i: None,
+            e: None,
+            s: Some(Surprise {
+                prob: 0.125,
+                percentile: 0.9375,
+                luck: 1.5,
+            }),
+        };
+        assert_eq!(
+            ctr.to_string(),
+            "-> P[l]: 12.5% (percentile: 94%), I - E[I] = +1.50 bits\n3 lights "
+        );
     }
 
     #[test]
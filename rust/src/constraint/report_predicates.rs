@@ -5,6 +5,7 @@
 //! This module provides some simple predicates for the use of generating a report. They all decide
 //! whether some kind of information shall be reported/shown or not
 
+use crate::constraint::check_type::Surprise;
 use crate::constraint::{CheckType, Constraint, ConstraintType};
 
 // internal helper functions
@@ -36,6 +37,15 @@ impl Constraint {
         }
     }
 
+    /// how surprising the actual outcome of this constraint was (only if the outcome is known and
+    /// the expected information is shown)
+    pub(super) fn surprise(&self) -> Option<Surprise> {
+        if self.result_unknown || !self.show_expected_information() {
+            return None;
+        }
+        self.check.calc_surprise()
+    }
+
     /// whether to show how often a 1:1 matching occured in the past
     pub(super) fn show_past_cnt(&self) -> bool {
         let r = match &self.r#type {
@@ -10,8 +10,11 @@ use core::fmt;
 use comfy_table::Cell;
 
 use crate::constraint::evaluate_predicates::ConstraintEval;
+use crate::constraint::offer_rating::OfferRating;
 use crate::constraint::{CheckType, Constraint, ConstraintGetters, ConstraintType};
 use crate::matching_repr::bitset::Bitset;
+use crate::solver::efficiency::DecisionRating;
+use crate::solver::remaining::RemainingEvents;
 use crate::LightCnt;
 
 /// A struct representing a row in the summary table. The idea is this is produced by the
@@ -32,11 +35,58 @@ pub(crate) struct SummaryRow {
     /// to which other constraint the distance is at its minimum (distance + label of the
     /// constraint)
     min_dist: Option<(String, usize)>,
+    /// how many further events are needed to solve the game (if estimated)
+    remaining: Option<RemainingEvents>,
+    /// how good the decision for this constraint was (if rated)
+    rating: Option<DecisionRating>,
+    /// how rational the decision on the offer for this constraint was (if rated)
+    offer_rating: Option<OfferRating>,
+    /// the prize pot after this constraint (if tracked)
+    pot: Option<u128>,
+}
+
+/// The columns of the summary table which are only shown if they are filled for any row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct OptionalColumns {
+    /// show the remaining events
+    remaining: bool,
+    /// show the rating of the decisions
+    efficiency: bool,
+    /// show the rating of the decisions on offers
+    offer: bool,
+    /// show the prize pot
+    pot: bool,
+}
+
+impl OptionalColumns {
+    /// the optional columns which are filled for any of the `rows`
+    pub(crate) fn of(rows: &[SummaryRow]) -> Self {
+        OptionalColumns {
+            remaining: rows.iter().any(|r| r.remaining.is_some()),
+            efficiency: rows.iter().any(|r| r.rating.is_some()),
+            offer: rows.iter().any(|r| r.offer_rating.is_some()),
+            pot: rows.iter().any(|r| r.pot.is_some()),
+        }
+    }
+
+    /// the headers of the columns which are shown
+    pub(crate) fn header(&self) -> Vec<&'static str> {
+        [
+            (self.remaining, "rem"),
+            (self.efficiency, "eff"),
+            (self.offer, "offer"),
+            (self.pot, "pot"),
+        ]
+        .into_iter()
+        .filter_map(|(show, hdr)| show.then_some(hdr))
+        .collect()
+    }
 }
 
 impl SummaryRow {
-    /// render the [`SummaryRow`] to a row so it can be used by comfy_table
-    pub(crate) fn render<F>(&self, style: F) -> Vec<Cell>
+    /// render the [`SummaryRow`] to a row so it can be used by comfy_table (including the
+    /// optional columns `cols`)
+    pub(crate) fn render<F>(&self, style: F, cols: OptionalColumns) -> Vec<Cell>
     where
         F: Fn(Cell) -> Cell,
     {
@@ -76,6 +126,30 @@ impl SummaryRow {
                 .map(|x| format!("{}/{}", x.1, x.0))
                 .unwrap_or("".to_string()),
         ));
+        if cols.remaining {
+            ret.push(Cell::new(
+                self.remaining
+                    .map(|x| x.to_string())
+                    .unwrap_or("".to_string()),
+            ));
+        }
+        if cols.efficiency {
+            ret.push(Cell::new(
+                self.rating.map(|x| x.to_string()).unwrap_or("".to_string()),
+            ));
+        }
+        if cols.offer {
+            ret.push(Cell::new(
+                self.offer_rating
+                    .map(|x| x.to_string())
+                    .unwrap_or("".to_string()),
+            ));
+        }
+        if cols.pot {
+            ret.push(Cell::new(
+                self.pot.map(|x| x.to_string()).unwrap_or("".to_string()),
+            ));
+        }
         // apply the style specified from the outside
         ret.into_iter().map(style).collect::<Vec<_>>()
     }
@@ -278,6 +352,10 @@ impl Constraint {
             info,
             new_count: self.new_matches(past),
             min_dist,
+            remaining: self.remaining,
+            rating: self.rating,
+            offer_rating: self.offer_rating,
+            pot: self.pot_after,
         }
     }
 
@@ -336,9 +414,56 @@ mod tests {
             info: Some(1.0),
             new_count: Some(5),
             min_dist: Some(("MN1".to_string(), 5)),
+            remaining: Some(RemainingEvents {
+                expected: 3.5,
+                worst: 5,
+                sampled: 10,
+                win: 1.0,
+            }),
+            rating: None,
+            offer_rating: None,
+            pot: None,
         };
-        let cells = sr.render(|c| c);
+        let cells = sr.render(|c| c, OptionalColumns::default());
         assert_eq!(cells.len(), 7);
+        let cols = OptionalColumns::of(std::slice::from_ref(&sr));
+        assert_eq!(cols.header(), vec!["rem"]);
+        let cells = sr.render(|c| c, cols);
+        assert_eq!(cells.len(), 8);
+        assert_eq!(cells[7].content(), "3.5/5");
+
+        let sr = SummaryRow {
+            rating: Some(DecisionRating {
+                chosen: 0.5,
+                best: Some(2.0),
+                random: None,
+            }),
+            ..sr
+        };
+        let cols = OptionalColumns::of(std::slice::from_ref(&sr));
+        assert_eq!(cols.header(), vec!["rem", "eff"]);
+        let cells = sr.render(|c| c, cols);
+        assert_eq!(cells.len(), 9);
+        assert_eq!(cells[8].content(), "25%");
+
+        let sr = SummaryRow {
+            offer_rating: Some(OfferRating::new(0.5, 1_000, 200_000, 10, true)),
+            ..sr
+        };
+        let cols = OptionalColumns::of(std::slice::from_ref(&sr));
+        assert_eq!(cols.header(), vec!["rem", "eff", "offer"]);
+        let cells = sr.render(|c| c, cols);
+        assert_eq!(cells.len(), 10);
+        assert_eq!(cells[9].content(), "sell ✗");
+
+        let sr = SummaryRow {
+            pot: Some(180_000),
+            ..sr
+        };
+        let cols = OptionalColumns::of(std::slice::from_ref(&sr));
+        assert_eq!(cols.header(), vec!["rem", "eff", "offer", "pot"]);
+        let cells = sr.render(|c| c, cols);
+        assert_eq!(cells[10].content(), "180000");
     }
 
     #[test]
@@ -488,6 +613,10 @@ mod tests {
             info: Some(0.5),
             new_count: Some(2),
             min_dist: None,
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot: None,
         };
         assert_eq!(sr.label, ref_sr.label);
         assert_eq!(sr.light_status, ref_sr.light_status);
@@ -536,6 +665,10 @@ mod tests {
             info: None,
             new_count: None,
             min_dist: None,
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot: None,
         };
         assert_eq!(sr.label, ref_sr.label);
         assert_eq!(sr.light_status, ref_sr.light_status);
@@ -584,6 +717,10 @@ mod tests {
             info: Some(0.5),
             new_count: None,
             min_dist: None,
+            remaining: None,
+            rating: None,
+            offer_rating: None,
+            pot: None,
         };
         assert_eq!(sr.label, ref_sr.label);
         assert_eq!(sr.light_status, ref_sr.light_status);
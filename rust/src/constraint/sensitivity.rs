@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module provides the functionality related to the constraint which is needed for the
+//! sensitivity analysis of the light counts. The idea is to check the season with a variant of a
+//! constraint where the amount of lights is off by one.
+
+use crate::constraint::{CheckType, Constraint};
+use crate::LightCnt;
+
+impl Constraint {
+    /// whether the amount of lights of this constraint is a candidate to be shifted in the
+    /// sensitivity analysis
+    pub(crate) fn is_shiftable(&self) -> bool {
+        !self.result_unknown && matches!(self.check, CheckType::Lights(..))
+    }
+
+    /// Create a variant of this constraint with the amount of lights shifted by `delta`.
+    ///
+    /// Returns `None` if the constraint is not shiftable (see [`Constraint::is_shiftable`]) or if
+    /// the shifted amount of lights is not possible with the map of this constraint.
+    ///
+    /// The variant does not collect any stats apart from the ones of the check. An exclude is only
+    /// kept as long as the variant still has lights. Shifting to a match does *not* add an exclude.
+    pub(crate) fn with_shifted_lights(&self, delta: i16) -> Option<Constraint> {
+        if !self.is_shiftable() {
+            return None;
+        }
+        let lights = self.check.as_lights()? as i16 + delta;
+        let max = self.map.iter().filter(|b| !b.is_empty()).count() as i16;
+        if lights < 0 || lights > max {
+            return None;
+        }
+        let lights = lights as LightCnt;
+
+        Some(Constraint {
+            r#type: self.r#type.clone(),
+            check: CheckType::Lights(lights, Default::default()),
+            hidden: self.hidden,
+            map: self.map.clone(),
+            map_s: self.map_s.clone(),
+            exclude: self.exclude.filter(|_| lights > 0),
+            known_lights: self.known_lights,
+            ruleset_data: None,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rust_decimal::dec;
+
+    use crate::constraint::ConstraintType;
+    use crate::matching_repr::{bitset::Bitset, MaskedMatching};
+
+    #[test]
+    fn with_shifted_lights_bounds() {
+        let c = Constraint {
+            check: CheckType::Lights(1, Default::default()),
+            map: MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![]]),
+            ..Default::default()
+        };
+        assert_eq!(
+            c.with_shifted_lights(-1).map(|c| c.check),
+            Some(CheckType::Lights(0, Default::default()))
+        );
+        assert_eq!(
+            c.with_shifted_lights(1).map(|c| c.check),
+            Some(CheckType::Lights(2, Default::default()))
+        );
+        // only two pairs in the map -> three lights are not possible
+        assert!(c.with_shifted_lights(2).is_none());
+        assert!(c.with_shifted_lights(-2).is_none());
+    }
+
+    #[test]
+    fn with_shifted_lights_not_shiftable() {
+        let c = Constraint {
+            check: CheckType::Sold,
+            ..Default::default()
+        };
+        assert!(c.with_shifted_lights(1).is_none());
+
+        let c = Constraint {
+            check: CheckType::Lights(1, Default::default()),
+            map: MaskedMatching::from_matching_ref(&[vec![0], vec![1]]),
+            result_unknown: true,
+            ..Default::default()
+        };
+        assert!(c.with_shifted_lights(1).is_none());
+    }
+
+    #[test]
+    fn with_shifted_lights_exclude() {
+        let c = Constraint {
+            r#type: ConstraintType::Box {
+                num: dec![1],
+                comment: "".to_string(),
+                offer: None,
+            },
+            check: CheckType::Lights(1, Default::default()),
+            map: MaskedMatching::from_matching_ref(&[vec![], vec![1]]),
+            exclude: Some((1, Bitset::from_idxs(&[0, 2]))),
+            ..Default::default()
+        };
+        let v = c.with_shifted_lights(-1).unwrap();
+        assert_eq!(v.exclude, None);
+        assert_eq!(v.r#type, c.r#type);
+        assert!(v.ruleset_data.is_none());
+    }
+}
@@ -11,6 +11,7 @@ use anyhow::Result;
 use crate::constraint::ConstraintSim;
 use crate::constraint::{CheckType, Constraint};
 use crate::matching_repr::{bitset::Bitset, MaskedMatching};
+use crate::solution_store::SolutionStore;
 
 impl ConstraintSim for Constraint {
     /// Process a matching `m` and apply side effects:
@@ -19,13 +20,13 @@ impl ConstraintSim for Constraint {
     /// - if `build_tree` is enabled we collect `left_poss` examples for tree building.
     fn process(&mut self, m: &MaskedMatching) -> Result<bool> {
         // check fits actually has a value and make it immutable
-        let fits = self.fits(m) || self.result_unknown;
+        let fits = self.admits(m);
 
         if !fits {
             self.eliminate(m);
         } else {
             if self.build_tree && !self.hidden {
-                self.left_poss.push(m.clone());
+                self.left_poss.push(m)?;
             }
             if let Some(rs_dat) = self.ruleset_data.as_mut() {
                 rs_dat.push(m)?;
@@ -37,6 +38,18 @@ impl ConstraintSim for Constraint {
 }
 
 impl Constraint {
+    /// Set where the solutions left after this constraint are stored (e.g. to limit how many are
+    /// held in memory). Has to be set before the simulation.
+    pub(crate) fn set_left_poss_store(&mut self, store: SolutionStore) {
+        self.left_poss = store;
+    }
+
+    /// whether `m` is still a possible solution after this constraint. In contrast to
+    /// [`Constraint::process`] no stats apart from the ones of the `check` are collected.
+    pub(crate) fn admits(&mut self, m: &MaskedMatching) -> bool {
+        self.fits(m) || self.result_unknown
+    }
+
     /// Internal predicate: whether `m` would satisfy the constraint's `check`.
     fn fits(&mut self, m: &MaskedMatching) -> bool {
         // first step is to check if the constraint filters out this matching
@@ -151,7 +164,7 @@ mod tests {
             assert_eq!(x, *f);
         }
         assert_eq!(
-            c.left_poss,
+            c.left_poss.to_vec().unwrap(),
             ms[0..3].iter().map(|(_, m)| m.clone()).collect::<Vec<_>>()
         );
 
@@ -262,7 +275,7 @@ mod tests {
             assert!(x);
         }
         assert_eq!(
-            c.left_poss,
+            c.left_poss.to_vec().unwrap(),
             ms[0..].iter().map(|(_, m)| m.clone()).collect::<Vec<_>>()
         );
     }
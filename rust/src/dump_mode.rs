@@ -4,7 +4,8 @@
 
 //! This module implements different ways to dump the remaining possible solutions
 
-use crate::matching_repr::MaskedMatching;
+use crate::matching_repr::rank::RankCodec;
+use crate::solution_store::SolutionStore;
 use std::io;
 
 /// select how the remaining possible solutions should be dumped
@@ -18,6 +19,9 @@ pub enum DumpMode {
     Winning,
     /// all *winning* matchings (a -> b) should be shown with the ids translated to names
     WinningNames,
+    /// all full-matches (a -> bs) should be shown as their rank (see
+    /// [`crate::matching_repr::rank`]), matchings which can't be ranked are shown as ids
+    Ranks,
 }
 
 impl DumpMode {
@@ -28,18 +32,20 @@ impl DumpMode {
     pub(super) fn dump<W: io::Write>(
         &self,
         mut out: W,
-        left_poss: &[MaskedMatching],
+        left_poss: &SolutionStore,
         map_a: &[String],
         map_b: &[String],
     ) -> io::Result<()> {
         match self {
             DumpMode::Full => {
                 for p in left_poss.iter() {
+                    let p = p.map_err(io::Error::other)?;
                     writeln!(out, "{:?}", p.prepare_debug_print())?;
                 }
             }
             DumpMode::FullNames => {
                 for p in left_poss.iter() {
+                    let p = p.map_err(io::Error::other)?;
                     writeln!(
                         out,
                         "{:?}",
@@ -50,6 +56,7 @@ impl DumpMode {
             }
             DumpMode::Winning => {
                 for p in left_poss.iter() {
+                    let p = p.map_err(io::Error::other)?;
                     for pw in p.iter_unwrapped() {
                         writeln!(out, "{:?}", pw.prepare_debug_print())?;
                     }
@@ -57,6 +64,7 @@ impl DumpMode {
             }
             DumpMode::WinningNames => {
                 for p in left_poss.iter() {
+                    let p = p.map_err(io::Error::other)?;
                     for pw in p.iter_unwrapped() {
                         writeln!(
                             out,
@@ -67,6 +75,16 @@ impl DumpMode {
                     }
                 }
             }
+            DumpMode::Ranks => {
+                let codec = RankCodec::new(map_a.len(), map_b.len());
+                for p in left_poss.iter() {
+                    let p = p.map_err(io::Error::other)?;
+                    match codec.encode(&p) {
+                        Some(code) => writeln!(out, "{code}")?,
+                        None => writeln!(out, "{:?}", p.prepare_debug_print())?,
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -75,15 +93,16 @@ impl DumpMode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::matching_repr::MaskedMatching;
     use pretty_assertions::assert_eq;
 
     #[test]
     fn dump_full_simple() {
-        let data = vec![
+        let data = SolutionStore::from(vec![
             MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
             MaskedMatching::from_matching_ref(&[vec![2], vec![1], vec![0]]),
             MaskedMatching::from_matching_ref(&[vec![0], vec![2, 3], vec![1]]),
-        ];
+        ]);
         let mut buf = Vec::new();
 
         DumpMode::Full.dump(&mut buf, &data, &[], &[]).unwrap();
@@ -107,11 +126,11 @@ mod tests {
             .map(|x| x.to_string())
             .collect::<Vec<_>>();
 
-        let data = vec![
+        let data = SolutionStore::from(vec![
             MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
             MaskedMatching::from_matching_ref(&[vec![2], vec![1], vec![0]]),
             MaskedMatching::from_matching_ref(&[vec![0], vec![2, 3], vec![1]]),
-        ];
+        ]);
         let mut buf = Vec::new();
 
         DumpMode::FullNames
@@ -146,11 +165,11 @@ mod tests {
             .map(|x| x.to_string())
             .collect::<Vec<_>>();
 
-        let data = vec![
+        let data = SolutionStore::from(vec![
             MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
             MaskedMatching::from_matching_ref(&[vec![2], vec![1], vec![0]]),
             MaskedMatching::from_matching_ref(&[vec![0], vec![2, 3], vec![1]]),
-        ];
+        ]);
         let mut buf = Vec::new();
 
         DumpMode::Winning
@@ -177,11 +196,11 @@ mod tests {
             .map(|x| x.to_string())
             .collect::<Vec<_>>();
 
-        let data = vec![
+        let data = SolutionStore::from(vec![
             MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
             MaskedMatching::from_matching_ref(&[vec![2], vec![1], vec![0]]),
             MaskedMatching::from_matching_ref(&[vec![0], vec![2, 3], vec![1]]),
-        ];
+        ]);
         let mut buf = Vec::new();
 
         DumpMode::WinningNames
@@ -208,4 +227,36 @@ mod tests {
         );
         assert_eq!(lines.len(), 4)
     }
+
+    #[test]
+    fn dump_ranks_simple() {
+        let map = vec!["A", "B", "C", "D"]
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+
+        let data = SolutionStore::from(vec![
+            MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2], vec![3]]),
+            MaskedMatching::from_matching_ref(&[vec![3], vec![2], vec![1], vec![0]]),
+            MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2, 3], vec![]]),
+            MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2], vec![3, 7]]),
+        ]);
+        let mut buf = Vec::new();
+
+        DumpMode::Ranks.dump(&mut buf, &data, &map, &map).unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        // lehmer rank << 1
+        assert_eq!(lines[0], "0");
+        assert_eq!(lines[1], "46");
+        // assignment rank << 1 | 1 (digits 1, 2, 3, 3 with base 5)
+        assert_eq!(
+            lines[2],
+            (((1 + 2 * 5 + 3 * 25 + 3 * 125) << 1) | 1).to_string()
+        );
+        // can't be ranked
+        assert_eq!(lines[3], "[[0], [1], [2], [3, 7]]");
+        assert_eq!(lines.len(), 4)
+    }
 }
@@ -4,39 +4,58 @@
 
 //! This module represents the whole game.
 //! A game has te following lifecycle:
-//! 1. parsed from yaml as [`parse::GameParse`] -> parse module
+//! 1. parsed from yaml (or json/toml, see [`format`]) as [`parse::GameParse`] -> parse module
+//!    (alternatively built in code via [`builder::GameBuilder`])
 //! 2. converted to a regular [`Game`] -> parse module
 //! 3. simulated [`Game::sim`] -> main module
 //! 4. evaluated [`Game::eval`] -> eval module
 //! 5. report generated and printed `Game::report` -> eval/report module
 
+pub mod builder;
 pub mod cache;
 pub mod cache_report;
+pub mod count;
+pub mod format;
+pub mod live;
 pub mod parse;
 pub mod parse_utils;
+pub mod serve;
 
 mod compare;
+mod efficiency;
 mod eval;
 mod eval_utils;
+mod lint;
 mod md_output;
+mod offers;
+mod persons;
+mod query_conditional;
 mod query_matchings;
 mod query_pairs;
+mod remaining;
 mod report_summary;
 mod report_trail;
 mod report_utils;
+mod sensitivity;
 
 use std::collections::HashSet;
 use std::path::PathBuf;
 
 use anyhow::Result;
 
+use crate::collector::Collector;
 use crate::constraint::Constraint;
 use crate::dump_mode::DumpMode;
+use crate::game::parse::PotConfig;
 use crate::iterstate::IterState;
+use crate::matching_repr::rank::RankCodec;
 use crate::matching_repr::{IdBase, MaskedMatching};
 use crate::progressbar::ProgressBarTrait;
 use crate::ruleset::RuleSet;
-use crate::Lut;
+use crate::solution_store::{MemBudget, SolutionStore};
+use crate::solver::remaining::{SurvivorTrail, MAX_LEFT};
+use crate::survivors::Survivors;
+use crate::{Lut, Map};
 
 /// a struct to represent a complete game.
 #[derive(Debug)]
@@ -72,6 +91,22 @@ pub struct Game {
     /// query these individuals from set_a and set_b regarding how often they occur with which
     /// other individuals from the other set
     query_pair: (HashSet<IdBase>, HashSet<IdBase>),
+    /// query the 1:1 matchings given that all pairs (set_a -> set_b) of a condition are a match
+    query_conditional: Vec<Map>,
+    /// user-defined statistics to collect on the possible solutions left
+    collectors: Vec<Box<dyn Collector>>,
+    /// how many MiB the solutions left may occupy in memory before they are spilled to disk
+    /// (unbounded if not set)
+    mem_budget: Option<usize>,
+    /// for how many solutions (at most) the remaining events are simulated after each event (not
+    /// estimated if not set)
+    remaining_events: Option<usize>,
+    /// whether to rate the decisions made for each event (see [`crate::solver::efficiency`])
+    rate_decisions: bool,
+    /// how many matching nights the season has (see [`Game::nights`])
+    nights: Option<usize>,
+    /// the prize pot of the season and how it changes (see [`Game::pot`])
+    pot: Option<PotConfig>,
 
     /// *read* the cache from this file if set
     cache_file: Option<PathBuf>,
@@ -95,6 +130,13 @@ impl Default for Game {
             stem: "abc".to_string(),
             query_matchings: vec![],
             query_pair: (Default::default(), Default::default()),
+            query_conditional: vec![],
+            collectors: vec![],
+            mem_budget: None,
+            remaining_events: None,
+            rate_decisions: false,
+            nights: None,
+            pot: None,
             cache_file: None,
             cache_to: None,
         }
@@ -116,6 +158,11 @@ impl Game {
         }
     }
 
+    /// The lookup tables (name -> id) of set_a and set_b
+    pub fn luts(&self) -> (&Lut, &Lut) {
+        (&self.lut_a, &self.lut_b)
+    }
+
     /// Return a formatted number-of-players string "A/B".
     pub(super) fn players_str(&self) -> String {
         format!("{}/{}", self.map_a.len(), self.map_b.len())
@@ -131,7 +178,7 @@ impl Game {
         &mut self,
         dump_mode: Option<DumpMode>,
     ) -> Result<IterState<T, Constraint>> {
-        let mut is = {
+        let mut is: IterState<T, Constraint> = {
             // mathematically calculate amount of permutations (for the progressbar)
             let perm_amount = self.rule_set.get_perms_amount(
                 self.map_a.len(),
@@ -141,7 +188,9 @@ impl Game {
 
             IterState::new(
                 // whether to store the permutations which are valid solutions
-                dump_mode.is_some() || self.keep_rem,
+                dump_mode.is_some()
+                    || self.keep_rem
+                    || self.collectors.iter().any(|c| c.needs_solutions()),
                 perm_amount,
                 self.constraints_orig.clone(),
                 // query which constraint eliminated a matching
@@ -153,12 +202,78 @@ impl Game {
             )?
         };
 
+        is.collectors = self.collectors.clone();
+        is.query_conditional = self
+            .query_conditional
+            .iter()
+            .map(|c| {
+                let each = vec![vec![0; self.map_b.len()]; self.map_a.len()];
+                (c.clone(), (each, 0))
+            })
+            .collect();
+
+        // all solutions collected in this simulation share the same budget and are stored as
+        // their rank
+        let budget = self.mem_budget.map(MemBudget::from_mib);
+        let codec = RankCodec::new(self.map_a.len(), self.map_b.len());
+        let store = SolutionStore::with_budget(budget).compact(codec);
+        is.left_poss = store.clone();
+        for c in is.constraints.iter_mut() {
+            c.set_left_poss_store(store.clone());
+        }
+        if self.remaining_events.is_some() || self.rate_decisions {
+            let mut trail = SurvivorTrail::new(is.constraints.len(), MAX_LEFT);
+            // the permutations read from a cache tell nothing about the constraints it covers
+            trail.forget_before(self.cache_covers());
+            is.survivor_trail = Some(trail);
+        }
+
         // run the entire simulation
         self.rule_set
             .iter_perms(&self.lut_a, &self.lut_b, &mut is, &self.cache_file)?;
+        for c in &mut is.collectors {
+            c.finish(&is.left_poss)?;
+        }
 
         Ok(is)
     }
+
+    /// Additionally collect the statistics of `collector` on the possible solutions left. The
+    /// results are appended to the report.
+    pub fn add_collector(&mut self, collector: Box<dyn Collector>) {
+        self.collectors.push(collector);
+    }
+
+    /// Limit the memory the solutions left (overall and after each event) may occupy to `mib` MiB.
+    /// Once the budget is exhausted, further solutions are spilled to disk.
+    pub fn set_mem_budget(&mut self, mib: Option<usize>) {
+        self.mem_budget = mib;
+    }
+
+    /// Estimate after each event how many further events are needed to solve the game by
+    /// simulating the strategies of the solver on (at most) `samples` of the solutions left (see
+    /// [`crate::solver::remaining`]). Only done if at most [`MAX_LEFT`] solutions are left.
+    pub fn set_remaining_events(&mut self, samples: Option<usize>) {
+        self.remaining_events = samples;
+    }
+
+    /// Rate the decisions made for each event by comparing them with the ones the strategies of
+    /// the solver would have made (see [`crate::solver::efficiency`]). Nights are only compared
+    /// with the strategies if at most [`MAX_LEFT`] solutions are left before them.
+    pub fn set_rate_decisions(&mut self, rate: bool) {
+        self.rate_decisions = rate;
+    }
+
+    /// Lazily yield the solutions which are still possible after all constraints of this game
+    /// (see [`crate::survivors`]). If a cache was selected, the permutations are read from it.
+    pub fn survivors(&self) -> Survivors {
+        Survivors::new(
+            self.rule_set.clone(),
+            (self.lut_a.clone(), self.lut_b.clone()),
+            self.constraints_orig.clone(),
+            self.cache_file.clone(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module allows to construct a [`Game`] in code instead of reading it from a game-config.
+//!
+//! The [`GameBuilder`] collects the same data a game-config contains and then finalizes it via
+//! [`GameParse::finalize_parsing`]. So a game built here is validated exactly like a game read
+//! from disk.
+//!
+//! ```
+//! # use ayto::game::builder::GameBuilder;
+//! # use ayto::ruleset::parse::RuleSetParse;
+//! let game = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+//!     .rule_set(RuleSetParse::Eq)
+//!     .box_(1, [("A", "a")], 0)
+//!     .night(1, [("A", "b"), ("B", "a"), ("C", "c")], 1)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(game.constraints_orig.len(), 2);
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+
+use crate::collector::CollectorSpec;
+use crate::constraint::check_type::CheckType;
+use crate::constraint::parse::ConstraintParse;
+use crate::constraint::ConstraintType;
+use crate::game::parse::{GameParse, PotConfig, QueryPair};
+use crate::game::Game;
+use crate::ignore_ops::IgnoreOps;
+use crate::ruleset::parse::RuleSetParse;
+use crate::{LightCnt, MapS};
+
+/// Builds a [`Game`] step by step
+#[derive(Debug)]
+pub struct GameBuilder {
+    /// the game-config which is built up
+    gp: GameParse,
+    /// the stem for the output file-names (also determines the output directory)
+    stem: PathBuf,
+    /// which constraints to ignore
+    ignore: IgnoreOps,
+}
+
+impl GameBuilder {
+    /// Start building a game with the individuals of `set_a` and `set_b`. By default the
+    /// ruleset is [`RuleSetParse::Eq`] and there are no constraints.
+    pub fn new<A, B>(set_a: A, set_b: B) -> Self
+    where
+        A: IntoIterator,
+        A::Item: Into<String>,
+        B: IntoIterator,
+        B::Item: Into<String>,
+    {
+        GameBuilder {
+            gp: GameParse {
+                no_offerings_noted: false,
+                keep_rem: true,
+                constraints_orig: vec![],
+                rule_set: RuleSetParse::Eq,
+                frontmatter: serde_yaml::Value::Null,
+                query_matchings_s: vec![],
+                query_pair_s: QueryPair::default(),
+                query_conditional_s: vec![],
+                collectors: vec![],
+                nights: None,
+                pot: None,
+                map_a: set_a.into_iter().map(Into::into).collect(),
+                map_b: set_b.into_iter().map(Into::into).collect(),
+                rename_a: Default::default(),
+                rename_b: Default::default(),
+                gen_cache: false,
+                use_cache: None,
+                cache_fallback: None,
+            },
+            stem: PathBuf::from("game"),
+            ignore: IgnoreOps::Nothing,
+        }
+    }
+
+    /// Continue building on the game-config `gp` (e.g. read from disk)
+    pub fn from_parse(gp: GameParse) -> Self {
+        GameBuilder {
+            gp,
+            stem: PathBuf::from("game"),
+            ignore: IgnoreOps::Nothing,
+        }
+    }
+
+    /// Set the ruleset of the game
+    pub fn rule_set(mut self, rule_set: RuleSetParse) -> Self {
+        self.gp.rule_set = rule_set;
+        self
+    }
+
+    /// Set whether the remaining solutions shall be kept after the simulation
+    pub fn keep_rem(mut self, keep_rem: bool) -> Self {
+        self.gp.keep_rem = keep_rem;
+        self
+    }
+
+    /// Set the stem for the output file-names (.json, .dot, .md). The output is placed in the
+    /// directory containing the stem.
+    pub fn stem(mut self, stem: &Path) -> Self {
+        self.stem = stem.to_path_buf();
+        self
+    }
+
+    /// Set which constraints to ignore
+    pub fn ignore(mut self, ignore: IgnoreOps) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    /// Set how many matching nights the season has
+    pub fn nights(mut self, nights: usize) -> Self {
+        self.gp.nights = Some(nights);
+        self
+    }
+
+    /// Set the prize pot of the season at the beginning (`initial`) and by how much it is reduced
+    /// with each blackout
+    pub fn pot(mut self, initial: u128, blackout: u128) -> Self {
+        self.gp.pot = Some(PotConfig { initial, blackout });
+        self
+    }
+
+    /// Enable the collector specified by `spec` (see [`crate::collector`])
+    pub fn collector(mut self, spec: CollectorSpec) -> Self {
+        self.gp.collectors.push(spec);
+        self
+    }
+
+    /// Additionally show the probabilities of the 1:1 matchings given that all pairs of `map` are
+    /// a match
+    pub fn conditional<M, A, B>(mut self, map: M) -> Self
+    where
+        M: IntoIterator<Item = (A, B)>,
+        A: Into<String>,
+        B: Into<String>,
+    {
+        let map = map
+            .into_iter()
+            .map(|(a, b)| (a.into(), b.into()))
+            .collect::<MapS>();
+        self.gp.query_conditional_s.push(map);
+        self
+    }
+
+    /// Add a matching-night with number `num` seating the pairs of `map` which resulted in
+    /// `lights` lights
+    pub fn night<M, A, B>(self, num: impl Into<Decimal>, map: M, lights: LightCnt) -> Self
+    where
+        M: IntoIterator<Item = (A, B)>,
+        A: Into<String>,
+        B: Into<String>,
+    {
+        let r#type = ConstraintType::Night {
+            num: num.into(),
+            comment: String::new(),
+            offer: None,
+        };
+        self.event(r#type, map, CheckType::Lights(lights, Default::default()))
+    }
+
+    /// Add a match-box with number `num` for the pairs of `map` (usually a single pair) which
+    /// resulted in `lights` lights
+    pub fn box_<M, A, B>(self, num: impl Into<Decimal>, map: M, lights: LightCnt) -> Self
+    where
+        M: IntoIterator<Item = (A, B)>,
+        A: Into<String>,
+        B: Into<String>,
+    {
+        let r#type = ConstraintType::Box {
+            num: num.into(),
+            comment: String::new(),
+            offer: None,
+        };
+        self.event(r#type, map, CheckType::Lights(lights, Default::default()))
+    }
+
+    /// Add an arbitrary constraint, e.g. a sold match-box or one with a comment
+    pub fn constraint(mut self, constraint: ConstraintParse) -> Self {
+        self.gp.constraints_orig.push(constraint);
+        self
+    }
+
+    /// Add a constraint of `r#type` on `map` which is checked via `check`
+    fn event<M, A, B>(self, r#type: ConstraintType, map: M, check: CheckType) -> Self
+    where
+        M: IntoIterator<Item = (A, B)>,
+        A: Into<String>,
+        B: Into<String>,
+    {
+        let map = map
+            .into_iter()
+            .map(|(a, b)| (a.into(), b.into()))
+            .collect::<MapS>();
+        self.constraint(ConstraintParse::new(r#type, map, check))
+    }
+
+    /// Build the game. This validates the game the same way as a game-config read from disk.
+    pub fn build(self) -> Result<Game> {
+        self.gp.finalize_parsing(&self.stem, &self.ignore)
+    }
+}
+
+/// A small game shared by the tests: a box without a match and a night with 2 lights leave 5 of
+/// the 24 permutations of 4 individuals per set
+#[cfg(test)]
+pub(crate) fn box_night() -> GameBuilder {
+    GameBuilder::new(["A", "B", "C", "D"], ["a", "b", "c", "d"])
+        .box_(1, [("A", "a")], 0)
+        .night(1, [("A", "b"), ("B", "a"), ("C", "c"), ("D", "d")], 2)
+}
+
+/// Simulate `g` and collect `f` of each event as stored for the comparison (events for which `f`
+/// returns `None` are skipped)
+#[cfg(test)]
+pub(crate) fn events_of<T>(
+    g: &mut Game,
+    f: impl Fn(&crate::constraint::compare::EvalEvent) -> Option<T>,
+) -> Result<Vec<T>> {
+    let is: crate::iterstate::IterState<crate::progressbar::MockProgressBar, _> = g.sim(None)?;
+    Ok(g.comparison_data(&is)?
+        .events
+        .iter()
+        .filter_map(f)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::constraint::ConstraintGetters;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    #[test]
+    fn build_same_as_parsed() -> Result<()> {
+        let src = r#"
+rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+constraints:
+  - type: !Box {num: 1, comment: ""}
+    check: !Lights [0]
+    map:
+      A: a
+  - type: !Night {num: 1, comment: ""}
+    check: !Lights [1]
+    map:
+      A: b
+      B: a
+      C: c
+"#;
+        let mut parsed = GameParse::new_from_str(src)?
+            .finalize_parsing(Path::new("game"), &IgnoreOps::Nothing)?;
+        let mut built = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+            .box_(1, [("A", "a")], 0)
+            .night(1, [("A", "b"), ("B", "a"), ("C", "c")], 1)
+            .build()?;
+
+        assert_eq!(built.map_a, parsed.map_a);
+        assert_eq!(built.lut_b, parsed.lut_b);
+        assert_eq!(built.constraints_orig.len(), 2);
+        for (b, p) in built.constraints_orig.iter().zip(&parsed.constraints_orig) {
+            assert_eq!(b.type_str(), p.type_str());
+            assert_eq!(b.matching(), p.matching());
+        }
+
+        let is_b: IterState<MockProgressBar, _> = built.sim(None)?;
+        let is_p: IterState<MockProgressBar, _> = parsed.sim(None)?;
+        assert_eq!(is_b.left_poss, is_p.left_poss);
+        assert_eq!(is_b.total, is_p.total);
+        Ok(())
+    }
+
+    #[test]
+    fn build_from_parse() -> Result<()> {
+        // the frontmatter is optional, unknown keys are ignored
+        let src = r#"
+rule_set: !Eq
+setA: [A, B, C]
+setB: [a, b, c]
+solution: {A: a}
+constraints:
+  - type: !Box {num: 1, comment: ""}
+    check: !Lights [0]
+    map:
+      A: a
+"#;
+        let game = GameBuilder::from_parse(GameParse::new_from_str(src)?)
+            .night(1, [("A", "b"), ("B", "a"), ("C", "c")], 1)
+            .build()?;
+        assert_eq!(
+            game.constraints_orig
+                .iter()
+                .map(|c| c.type_str())
+                .collect::<Vec<_>>(),
+            vec!["MB#1", "MN#1"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn build_validates() {
+        // unknown individual
+        assert!(GameBuilder::new(["A", "B"], ["a", "b"])
+            .box_(1, [("X", "a")], 0)
+            .build()
+            .is_err());
+        // duplicate individual
+        assert!(GameBuilder::new(["A", "A"], ["a", "b"]).build().is_err());
+        // the ruleset requires more individuals in set_b
+        assert!(GameBuilder::new(["A", "B"], ["a", "b"])
+            .rule_set(RuleSetParse::SomeoneIsTrip)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn build_constraint() -> Result<()> {
+        let r#type = ConstraintType::Box {
+            num: Decimal::ONE,
+            comment: "E01".to_string(),
+            offer: None,
+        };
+        let map = MapS::from([("A".to_string(), "a".to_string())]);
+        let g = GameBuilder::new(["A", "B"], ["a", "b"])
+            .constraint(ConstraintParse::new(r#type, map, CheckType::Sold))
+            .stem(Path::new("out/s01"))
+            .build()?;
+        assert_eq!(g.constraints_orig[0].comment(), "E01");
+        assert_eq!(g.stem, "s01");
+        assert_eq!(g.dir, PathBuf::from("out"));
+        Ok(())
+    }
+}
@@ -10,7 +10,8 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::constraint::{ConstraintGetters, ConstraintImpact};
 use crate::game::Game;
@@ -87,6 +88,47 @@ where
     input_hashes
 }
 
+/// The amount of `constraints` (from the start) whose effect is already contained in the cache at
+/// `path`, with `caches` being the candidates computed by [`get_caches`] for these constraints. All
+/// constraints are considered covered if `path` is none of the candidates.
+#[must_use]
+fn covered_constraints<T, S>(constraints: &[T], caches: &[S], path: &Path) -> usize
+where
+    T: ConstraintImpact,
+    S: CachableSpec,
+{
+    constraints
+        .iter()
+        .enumerate()
+        // there is a candidate for each constraint with an impact
+        .filter(|(_, c)| c.has_impact())
+        .zip(caches)
+        .find(|(_, s)| s.path() == path)
+        .map_or(constraints.len(), |((idx, _), _)| idx + 1)
+}
+
+/// Remove the caches in `written` which are none of the `caches` (the candidates of the current
+/// game-config) anymore and drop them from `written`. These were generated for an earlier version
+/// of the game-config (e.g. before an event was corrected) and won't be selected again.
+pub fn remove_superseded_caches<S: CachableSpec>(
+    written: &mut Vec<PathBuf>,
+    caches: &[S],
+) -> Result<()> {
+    let (kept, superseded) = std::mem::take(written)
+        .into_iter()
+        .partition::<Vec<_>, _>(|p| caches.iter().any(|c| c.path() == p));
+    *written = kept;
+    for p in superseded {
+        match std::fs::remove_file(&p) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(e).with_context(|| format!("removing the cache {:?} failed", p))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// argument specification for [`CacheMode`] so this can be used with clap
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum CacheModeArg {
@@ -101,7 +143,7 @@ pub enum CacheModeArg {
 }
 
 /// specifies the strategy to choose a cache
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
 pub enum CacheMode {
     /// select the most recent available cache
     MostRecent,
@@ -136,7 +178,7 @@ impl CacheModeArg {
 }
 
 /// specifies the strategy used as fallback if the cache could not be found
-#[derive(Debug, Clone, clap::ValueEnum, Deserialize)]
+#[derive(Debug, Clone, clap::ValueEnum, Deserialize, Serialize, JsonSchema)]
 pub enum CacheModeFallback {
     /// select the most recent available cache
     MostRecent,
@@ -202,7 +244,7 @@ impl CacheMode {
 impl Game {
     /// obtain the cache-candidates for this game
     #[must_use]
-    pub fn get_cache_candidates<S: CachableSpec>(&mut self) -> Vec<S> {
+    pub fn get_cache_candidates<S: CachableSpec>(&self) -> Vec<S> {
         let initial_hash = {
             let mut hasher = DefaultHasher::new();
             self.map_a.hash(&mut hasher);
@@ -212,6 +254,17 @@ impl Game {
         get_caches(initial_hash, &self.constraints_orig)
     }
 
+    /// the amount of constraints (from the start) whose effect is already contained in the selected
+    /// cache (0 if no cache is used)
+    #[must_use]
+    pub(crate) fn cache_covers(&self) -> usize {
+        let Some(path) = self.cache_file.clone() else {
+            return 0;
+        };
+        let caches: Vec<CacheSpec> = self.get_cache_candidates();
+        covered_constraints(&self.constraints_orig, &caches, &path)
+    }
+
     /// select a cache according to the specified strategy/strategies
     ///
     /// Needs to be provided `caches`, the list of cache-candidates
@@ -345,6 +398,49 @@ mod tests {
         // paths stay unchecked as I did not re-compute the hash chain manually
     }
 
+    #[test]
+    fn covered_constraints_simple() {
+        let constraints = ["A", "B", "C"].map(|typ| MockConstraint {
+            typ: typ.to_string(),
+            impact: typ != "B",
+            ..Default::default()
+        });
+        let caches: Vec<MockSpec> = get_caches(0, &constraints);
+        assert_eq!(
+            covered_constraints(&constraints, &caches, caches[0].path()),
+            1
+        );
+        // the constraint without impact is covered as well
+        assert_eq!(
+            covered_constraints(&constraints, &caches, caches[1].path()),
+            3
+        );
+        // unknown cache -> nothing is known about any constraint
+        assert_eq!(
+            covered_constraints(&constraints, &caches, Path::new("other.cache")),
+            3
+        );
+    }
+
+    #[test]
+    fn remove_superseded_caches_simple() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let [old, new] = ["old", "new"].map(|name| dir.path().join(name).with_extension("cache"));
+        for p in [&old, &new] {
+            std::fs::write(p, "")?;
+        }
+        let candidates = [MockSpec::new("new".into(), new.clone())];
+
+        // an already removed cache is no error
+        let gone = dir.path().join("gone.cache");
+        let mut written = vec![old.clone(), new.clone(), gone];
+        remove_superseded_caches(&mut written, &candidates)?;
+        assert_eq!(written, vec![new.clone()]);
+        assert!(!old.exists());
+        assert!(new.exists());
+        Ok(())
+    }
+
     #[test]
     fn finalize_requires_missing_args() {
         let arg = CacheModeArg::SpecificCache;
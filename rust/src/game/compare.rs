@@ -6,32 +6,125 @@
 //! a comparison.
 //! Prerequisite is that the evaluation already took place.
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 
-use anyhow::Result;
-use rust_decimal::dec;
+use anyhow::{Context, Result};
+use rust_decimal::{dec, Decimal};
 
-use crate::constraint::compare::{ComparisonData, EvalEvent, EvalInitial, SumCounts};
+use crate::collector::{CollectedStats, Collector};
+use crate::constraint::compare::{ComparisonData, EvalEvent, EvalInitial, SumCounts, SumPot};
 use crate::constraint::evaluate::ConstraintSolvable;
 use crate::constraint::evaluate_predicates::ConstraintEval;
-use crate::constraint::{Constraint, ConstraintGetters};
+use crate::constraint::{Constraint, ConstraintGetters, Offer};
+use crate::game::eval_utils::merge_constraints;
+use crate::game::parse::PotConfig;
 use crate::game::Game;
-use crate::matching_repr::MaskedMatching;
+use crate::iterstate::{IterState, QueryConditionalData};
+use crate::progressbar::ProgressBarTrait;
+use crate::solution_store::SolutionStore;
 
 impl Game {
+    /// The data used in comparisons for the simulation `is`. This is the same data as written to
+    /// disk by [`Game::eval`].
+    pub fn comparison_data<T: ProgressBarTrait>(
+        &self,
+        is: &IterState<T, Constraint>,
+    ) -> Result<ComparisonData> {
+        let mut constraints = merge_constraints(&is.constraints)?;
+        // the stats of the constraints are calculated while applying them
+        let mut rem = (is.each.clone(), is.total);
+        for c in constraints.iter_mut() {
+            rem = c.apply_to_rem(rem).context("Apply to rem failed")?;
+        }
+        self.estimate_remaining_events(
+            &mut constraints,
+            &is.constraints,
+            is.survivor_trail.as_ref(),
+        );
+        self.rate_decisions(
+            &mut constraints,
+            &is.constraints,
+            is.survivor_trail.as_ref(),
+            (is.each.clone(), is.total),
+        )?;
+        self.rate_offers(&mut constraints, (is.each.clone(), is.total))?;
+        self.track_pot(&mut constraints);
+        let solutions = is.keep_rem.then_some(&is.left_poss);
+        self.gen_comparison_data(
+            is.total as f64,
+            &constraints,
+            solutions,
+            &is.collectors,
+            &is.query_conditional,
+        )
+    }
+
+    /// Follow the prize pot over the `merged` constraints and store the pot after each of them in
+    /// the constraints (only if the pot is specified in the game-config)
+    pub(super) fn track_pot(&self, merged: &mut [Constraint]) {
+        let Some(cfg) = &self.pot else {
+            return;
+        };
+        let (after, _) = calculate_pot(merged, cfg, self.required_lights(), self.nights());
+        for (c, p) in merged.iter_mut().zip(after) {
+            c.set_pot_after(Some(p));
+        }
+    }
+
+    /// The prize money paid out in this season given the `merged` constraints (only if the pot
+    /// is specified in the game-config)
+    pub(super) fn pot_summary(&self, merged: &[Constraint]) -> Option<SumPot> {
+        let cfg = self.pot.as_ref()?;
+        Some(calculate_pot(merged, cfg, self.required_lights(), self.nights()).1)
+    }
+
+    /// how many lights are required to win the game
+    fn required_lights(&self) -> usize {
+        self.rule_set
+            .constr_map_len(self.lut_a.len(), self.lut_b.len())
+    }
+
     /// writes data used in comparisons serialized as json to disk
     pub(super) fn write_comparison_data(
         &self,
         total: f64,
         merged_constraints: &[Constraint],
-        solutions: Option<&Vec<MaskedMatching>>,
+        solutions: Option<&SolutionStore>,
+        collectors: &[Box<dyn Collector>],
+        conditional: &QueryConditionalData,
     ) -> Result<()> {
         let out_path = self.dir.join("stats").with_extension("json");
+        let out_data = self.gen_comparison_data(
+            total,
+            merged_constraints,
+            solutions,
+            collectors,
+            conditional,
+        )?;
+
+        // create file
+        let file = File::create(out_path)?;
+        let mut writer = BufWriter::new(file);
+
+        // serialize data to file
+        serde_json::to_writer(&mut writer, &out_data)?;
+        writer.flush()?;
+
+        Ok(())
+    }
 
-        let required_lights = self
-            .rule_set
-            .constr_map_len(self.lut_a.len(), self.lut_b.len());
+    /// collects the data used in comparisons
+    fn gen_comparison_data(
+        &self,
+        total: f64,
+        merged_constraints: &[Constraint],
+        solutions: Option<&SolutionStore>,
+        collectors: &[Box<dyn Collector>],
+        conditional: &QueryConditionalData,
+    ) -> Result<ComparisonData> {
+        let required_lights = self.required_lights();
 
         // all the data is collected here
         let mut out_data = ComparisonData {
@@ -42,8 +135,19 @@ impl Game {
                 solutions,
                 !self.no_offerings_noted,
                 required_lights,
-            ),
+            )?,
+            collected: collectors
+                .iter()
+                .map(|c| {
+                    Ok(CollectedStats {
+                        name: c.name().to_string(),
+                        data: c.to_json(&self.map_a, &self.map_b)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            conditional: self.conditional_stats(conditional),
         };
+        out_data.cnts.pot = self.pot_summary(merged_constraints);
 
         // insert the data for the course of the season
         // start with the initial state
@@ -59,25 +163,99 @@ impl Game {
             }
         }
 
-        // create file
-        let file = File::create(out_path)?;
-        let mut writer = BufWriter::new(file);
+        Ok(out_data)
+    }
+}
 
-        // serialize data to file
-        serde_json::to_writer(&mut writer, &out_data)?;
-        writer.flush()?;
+/// Follow the prize pot over the course of the season (see [`PotConfig`]).
+///
+/// Returns the pot after each of the `merged_constraints` and the prize money paid out. The pot
+/// is only paid out if the season is won in time (within the first `nights` matching nights).
+/// Money from single offers is always paid out if it is save, otherwise only if the season is won
+/// in time as well. The money of an offer for a pair is split between the two (the first one gets
+/// the remainder if it can't be split evenly).
+fn calculate_pot<T: ConstraintEval + ConstraintGetters>(
+    merged_constraints: &[T],
+    cfg: &PotConfig,
+    required_lights: usize,
+    nights: usize,
+) -> (Vec<u128>, SumPot) {
+    let mut pot = cfg.initial;
+    let mut after = Vec::with_capacity(merged_constraints.len());
+    let mut payout = None;
+    // money from single offers (save, only paid out if won)
+    let mut winnings = (BTreeMap::new(), BTreeMap::new());
+
+    for c in merged_constraints {
+        if c.is_blackout() {
+            pot = pot.saturating_sub(cfg.blackout);
+        }
+        // only accepted offers change the pot
+        if let Some(o) = c.try_get_offer().filter(|_| c.is_sold()) {
+            let (amount, reduced_pot, save, by) = match o {
+                Offer::Group { amount, .. } | Offer::GroupPair { amount, .. } => {
+                    pot += amount.unwrap_or_default();
+                    (None, false, false, vec![])
+                }
+                Offer::Single {
+                    amount,
+                    by,
+                    reduced_pot,
+                    save,
+                } => (amount, reduced_pot, save, vec![by]),
+                Offer::SinglePair {
+                    amount,
+                    by_a,
+                    by_b,
+                    reduced_pot,
+                    save,
+                } => (amount, reduced_pot, save, vec![by_a, by_b]),
+            };
+            if let Some(amount) = amount {
+                if reduced_pot {
+                    pot = pot.saturating_sub(amount);
+                }
+                let w = if save {
+                    &mut winnings.0
+                } else {
+                    &mut winnings.1
+                };
+                let cnt = by.len().max(1) as u128;
+                for (i, name) in by.into_iter().enumerate() {
+                    // distribute the remainder so no money gets lost
+                    let share = amount / cnt + u128::from((i as u128) < amount % cnt);
+                    *w.entry(name).or_default() += share;
+                }
+            }
+        }
+        after.push(pot);
+        if payout.is_none() && c.won(required_lights) && c.num() <= Decimal::from(nights) {
+            payout = Some(pot);
+        }
+    }
 
-        Ok(())
+    let mut winnings_save = winnings.0;
+    if payout.is_some() {
+        for (name, m) in winnings.1 {
+            *winnings_save.entry(name).or_default() += m;
+        }
     }
+    (
+        after,
+        SumPot {
+            payout: payout.unwrap_or_default(),
+            winnings: winnings_save,
+        },
+    )
 }
 
 /// computes summary data to be used in a summary
 fn calculate_summary_data<T: ConstraintEval + ConstraintGetters + ConstraintSolvable>(
     merged_constraints: &[T],
-    solutions: Option<&Vec<MaskedMatching>>,
+    solutions: Option<&SolutionStore>,
     offers_noted: bool,
     required_lights: usize,
-) -> SumCounts {
+) -> Result<SumCounts> {
     // initialization
     let mut cnts = SumCounts::default();
     cnts.offers_mb.sold_but_match_active = solutions.is_some();
@@ -96,7 +274,7 @@ fn calculate_summary_data<T: ConstraintEval + ConstraintGetters + ConstraintSolv
             if c.is_sold() {
                 cnts.offers_mb.sold_cnt += 1;
             }
-            if c.is_sold() && c.is_mb_hit(solutions) {
+            if c.is_sold() && c.is_mb_hit(solutions)? {
                 cnts.offers_mb.sold_but_match += 1;
             }
             if let Some(o) = c.try_get_offer() {
@@ -104,10 +282,16 @@ fn calculate_summary_data<T: ConstraintEval + ConstraintGetters + ConstraintSolv
                 if let Some(m) = o.try_get_amount() {
                     cnts.offers_mb.offered_money += m;
                 }
-                if c.is_mb_hit(solutions) {
+                if c.is_mb_hit(solutions)? {
                     cnts.offers_mb.offer_and_match += 1;
                 }
             }
+            if let Some(r) = c.offer_rating() {
+                cnts.offers_mb.offers_rated += 1;
+                if r.rational() {
+                    cnts.offers_mb.offers_rational += 1;
+                }
+            }
         } else if c.is_mn() {
             if c.is_sold() {
                 cnts.offers_mn.sold_cnt += 1;
@@ -162,7 +346,7 @@ fn calculate_summary_data<T: ConstraintEval + ConstraintGetters + ConstraintSolv
                         .then_some((last.num() + dec![1] < dec![11], "End".to_string()))
                 })
         });
-    cnts
+    Ok(cnts)
 }
 
 #[cfg(test)]
@@ -171,7 +355,9 @@ mod tests {
     use rust_decimal::Decimal;
 
     use crate::constraint::compare::{SumOffersMB, SumOffersMN};
+    use crate::constraint::offer_rating::OfferRating;
     use crate::constraint::Offer;
+    use crate::matching_repr::MaskedMatching;
 
     use super::*;
 
@@ -191,6 +377,7 @@ mod tests {
         type_str: String,
         comment: String,
         map: MaskedMatching,
+        offer_rating: Option<OfferRating>,
     }
 
     impl Default for ConstraintMock {
@@ -210,6 +397,7 @@ mod tests {
                 type_str: "".to_string(),
                 comment: "".to_string(),
                 map: Default::default(),
+                offer_rating: None,
             }
         }
     }
@@ -251,14 +439,18 @@ mod tests {
             self.sold
         }
 
-        fn is_mb_hit(&self, _: Option<&Vec<MaskedMatching>>) -> bool {
-            self.mb_hit
+        fn is_mb_hit(&self, _: Option<&SolutionStore>) -> Result<bool> {
+            Ok(self.mb_hit)
         }
 
         fn try_get_offer(&self) -> Option<Offer> {
             self.offer.clone()
         }
 
+        fn offer_rating(&self) -> Option<OfferRating> {
+            self.offer_rating
+        }
+
         fn might_won(&self) -> bool {
             self.might_won
         }
@@ -276,7 +468,7 @@ mod tests {
     #[test]
     fn calculate_summary_data_aggregation() {
         // empty
-        let res = calculate_summary_data::<ConstraintMock>(&[], None, false, 10);
+        let res = calculate_summary_data::<ConstraintMock>(&[], None, false, 10).unwrap();
         let reference = SumCounts {
             blackouts: 0,
             won_in: None,
@@ -296,7 +488,10 @@ mod tests {
                 sold_but_match_active: false,
                 sold_but_match: 0,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
             },
+            pot: None,
         };
         assert_eq!(res, reference);
 
@@ -324,7 +519,8 @@ mod tests {
             None,
             false,
             10,
-        );
+        )
+        .unwrap();
         let reference = SumCounts {
             blackouts: 1,
             won_in: None,
@@ -344,7 +540,10 @@ mod tests {
                 sold_but_match_active: false,
                 sold_but_match: 0,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
             },
+            pot: None,
         };
         assert_eq!(res, reference);
 
@@ -375,7 +574,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         let reference = SumCounts {
             blackouts: 0,
             won_in: None,
@@ -395,7 +595,10 @@ mod tests {
                 sold_but_match_active: false,
                 sold_but_match: 0,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
             },
+            pot: None,
         };
         assert_eq!(res, reference);
 
@@ -428,7 +631,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(
             res.offers_mb,
             SumOffersMB {
@@ -439,6 +643,8 @@ mod tests {
                 sold_but_match_active: false,
                 sold_but_match: 0,
                 offer_and_match: 0,
+                offers_rated: 0,
+                offers_rational: 0,
             }
         );
 
@@ -454,6 +660,8 @@ mod tests {
                         save: true,
                     }),
                     mb_hit: true,
+                    // selling was worth more than opening
+                    offer_rating: Some(OfferRating::new(0.1, 5, 100, 10, true)),
                     ..Default::default()
                 },
                 ConstraintMock {
@@ -465,16 +673,18 @@ mod tests {
                         save: true,
                     }),
                     mb_hit: true,
+                    // selling was worth more than opening
+                    offer_rating: Some(OfferRating::new(0.1, 5, 100, 10, false)),
                     ..Default::default()
                 },
             ],
-            Some(&vec![MaskedMatching::from_matching_ref(&[
-                vec![0],
-                vec![1],
-            ])]),
+            Some(&SolutionStore::from(vec![
+                MaskedMatching::from_matching_ref(&[vec![0], vec![1]]),
+            ])),
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(
             res.offers_mb,
             SumOffersMB {
@@ -485,10 +695,100 @@ mod tests {
                 sold_but_match_active: true,
                 sold_but_match: 1,
                 offer_and_match: 2,
+                offers_rated: 2,
+                offers_rational: 1,
             }
         );
     }
 
+    #[test]
+    fn calculate_pot_simple() {
+        let cfg = PotConfig {
+            initial: 200_000,
+            blackout: 20_000,
+        };
+        let sold_mb = |offer| ConstraintMock {
+            mb: true,
+            sold: true,
+            offer: Some(offer),
+            ..Default::default()
+        };
+        let mut cs = vec![
+            sold_mb(Offer::Group {
+                amount: Some(30_000),
+                by: "A".to_string(),
+            }),
+            ConstraintMock {
+                mn: true,
+                blackout: true,
+                ..Default::default()
+            },
+            sold_mb(Offer::SinglePair {
+                amount: Some(40_001),
+                by_a: "A".to_string(),
+                by_b: "b".to_string(),
+                reduced_pot: true,
+                save: true,
+            }),
+            sold_mb(Offer::Single {
+                amount: Some(5_000),
+                by: "C".to_string(),
+                reduced_pot: false,
+                save: false,
+            }),
+            // declined -> no change
+            ConstraintMock {
+                mb: true,
+                offer: Some(Offer::Group {
+                    amount: Some(10_000),
+                    by: "A".to_string(),
+                }),
+                ..Default::default()
+            },
+            ConstraintMock {
+                mn: true,
+                won: true,
+                num: dec![10],
+                ..Default::default()
+            },
+        ];
+
+        let (after, sum) = calculate_pot(&cs, &cfg, 10, 10);
+        assert_eq!(
+            after,
+            vec![230_000, 210_000, 169_999, 169_999, 169_999, 169_999]
+        );
+        assert_eq!(
+            sum,
+            SumPot {
+                payout: 169_999,
+                winnings: BTreeMap::from([
+                    ("A".to_string(), 20_001),
+                    ("C".to_string(), 5_000),
+                    ("b".to_string(), 20_000),
+                ]),
+            }
+        );
+
+        // not won in time -> no payout and no money which is not save
+        cs[5].num = dec![11];
+        let (_, sum) = calculate_pot(&cs, &cfg, 10, 10);
+        let lost = SumPot {
+            payout: 0,
+            winnings: BTreeMap::from([("A".to_string(), 20_001), ("b".to_string(), 20_000)]),
+        };
+        assert_eq!(sum, lost);
+
+        // the season has more nights
+        let (_, sum) = calculate_pot(&cs, &cfg, 10, 11);
+        assert_eq!(sum.payout, 169_999);
+
+        // and fewer nights
+        cs[5].num = dec![10];
+        let (_, sum) = calculate_pot(&cs, &cfg, 10, 9);
+        assert_eq!(sum, lost);
+    }
+
     #[test]
     fn calculate_summary_data_won_in() {
         // won in time
@@ -509,7 +809,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.won_in, Some((true, "MB+10.9".to_string())));
 
         // "won" out of time
@@ -530,7 +831,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.won_in, Some((false, "MB+11.0".to_string())));
 
         // not won
@@ -543,7 +845,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.won_in, None);
     }
 
@@ -567,7 +870,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.solvable_in, Some((true, "MB+10.9".to_string())));
 
         // solvable out of time
@@ -588,7 +892,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.solvable_in, Some((false, "MB+11".to_string())));
 
         // solvable but nothing comes after (e.g. they won the game through "guessing")
@@ -611,7 +916,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.solvable_in, Some((false, "End".to_string())));
 
         // solvable but nothing comes after (e.g. they won the game through "guessing")
@@ -634,7 +940,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.solvable_in, Some((true, "End".to_string())));
 
         // not solvable
@@ -655,7 +962,8 @@ mod tests {
             None,
             true,
             10,
-        );
+        )
+        .unwrap();
         assert_eq!(res.solvable_in, None);
     }
 }
@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements estimating the effort of a simulation before running it: the exact size
+//! of the search space (see [`crate::ruleset::RuleSet::count_perms`]) and the runtime which is
+//! extrapolated from simulating a short sample of the permutations.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use num_bigint::BigUint;
+
+use crate::constraint::Constraint;
+use crate::game::Game;
+use crate::iterstate::IterStateTrait;
+use crate::matching_repr::MaskedMatching;
+
+/// after how many permutations to check whether the sample time is over
+const CHECK_EVERY: usize = 1024;
+
+/// Checks the permutations against the constraints until the sample time is over
+struct SampleState {
+    /// the constraints of the game
+    constraints: Vec<Constraint>,
+    /// when to stop sampling
+    deadline: Instant,
+    /// the amount of permutations checked so far
+    sampled: u64,
+    /// whether the sampling was stopped before all permutations were checked
+    stopped: bool,
+}
+
+impl IterStateTrait for SampleState {
+    fn start(&mut self) {}
+
+    fn finish(&mut self) {}
+
+    fn step(&mut self, i: usize, p: &MaskedMatching) -> Result<()> {
+        if i.is_multiple_of(CHECK_EVERY) && Instant::now() >= self.deadline {
+            self.stopped = true;
+            bail!("sample time is over");
+        }
+        // the result doesn't matter, only the time it takes
+        let _ = self.constraints.iter_mut().all(|c| c.admits(p));
+        self.sampled += 1;
+        Ok(())
+    }
+}
+
+/// The estimated effort of simulating a game
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSpace {
+    /// the amount of permutations which are generated by the ruleset
+    pub total: BigUint,
+    /// the amount of permutations checked while sampling
+    pub sampled: u64,
+    /// the time it took to check the sampled permutations
+    pub elapsed: Duration,
+}
+
+impl SearchSpace {
+    /// The runtime extrapolated from the sample. `None` if nothing was sampled or if the runtime
+    /// does not fit into a [`Duration`].
+    pub fn estimated_runtime(&self) -> Option<Duration> {
+        if self.sampled == 0 {
+            return None;
+        }
+        let nanos = &self.total * self.elapsed.as_nanos() / self.sampled;
+        let secs = u64::try_from(&nanos / 1_000_000_000u32).ok()?;
+        let sub = u32::try_from(&nanos % 1_000_000_000u32).ok()?;
+        Some(Duration::new(secs, sub))
+    }
+}
+
+impl fmt::Display for SearchSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total.to_string();
+        write!(f, "Search space: {}", total)?;
+        if total.len() > 6 {
+            write!(
+                f,
+                " (~{}.{}e{})",
+                &total[..1],
+                &total[1..3],
+                total.len() - 1
+            )?;
+        }
+        writeln!(f)?;
+
+        match self.estimated_runtime() {
+            Some(t) => writeln!(
+                f,
+                "Estimated runtime: {} (sampled {} permutations in {:.2}s)",
+                fmt_duration(t),
+                self.sampled,
+                self.elapsed.as_secs_f64()
+            ),
+            None if self.sampled == 0 => {
+                writeln!(f, "Estimated runtime: unknown (nothing sampled)")
+            }
+            None => writeln!(f, "Estimated runtime: longer than the universe exists"),
+        }
+    }
+}
+
+/// Format `d` human readable with the two most significant units (e.g. `3d 4h` or `12.34s`)
+fn fmt_duration(d: Duration) -> String {
+    const UNITS: [(&str, u64); 5] = [
+        ("y", 365 * 24 * 3600),
+        ("d", 24 * 3600),
+        ("h", 3600),
+        ("m", 60),
+        ("s", 1),
+    ];
+    let secs = d.as_secs();
+    if secs < 60 {
+        return format!("{:.2}s", d.as_secs_f64());
+    }
+    let (i, (u, len)) = UNITS
+        .iter()
+        .enumerate()
+        .find(|(_, (_, len))| secs >= *len)
+        .expect("secs >= 60 always fits one of the units");
+    let (u2, len2) = UNITS[i + 1];
+    format!("{}{} {}{}", secs / len, u, (secs % len) / len2, u2)
+}
+
+impl Game {
+    /// Estimate the effort of simulating this game. The constraints are checked on the first
+    /// permutations for (about) `sample` to extrapolate the runtime.
+    ///
+    /// Note: caches are not taken into account, the estimate is for simulating from scratch.
+    pub fn count(&self, sample: Duration) -> Result<SearchSpace> {
+        let total = self
+            .rule_set
+            .count_perms(self.map_a.len(), self.map_b.len())?;
+
+        let mut state = SampleState {
+            constraints: self.constraints_orig.clone(),
+            deadline: Instant::now() + sample,
+            sampled: 0,
+            stopped: false,
+        };
+        let start = Instant::now();
+        let res = self
+            .rule_set
+            .iter_perms(&self.lut_a, &self.lut_b, &mut state, &None);
+        let elapsed = start.elapsed();
+        if !state.stopped {
+            res?;
+        }
+
+        Ok(SearchSpace {
+            total,
+            sampled: state.sampled,
+            elapsed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::game::builder::GameBuilder;
+    use crate::ruleset::parse::RuleSetParse;
+
+    #[test]
+    fn count_complete() -> Result<()> {
+        // small enough to be checked completely
+        let g = GameBuilder::new(["A", "B", "C", "D"], ["a", "b", "c", "d"])
+            .box_(1, [("A", "a")], 0)
+            .build()?;
+        let s = g.count(Duration::from_secs(60))?;
+        assert_eq!(s.total, BigUint::from(24u32));
+        assert_eq!(s.sampled, 24);
+        assert!(s.estimated_runtime().unwrap() <= s.elapsed + Duration::from_nanos(1));
+        Ok(())
+    }
+
+    #[test]
+    fn count_sampled() -> Result<()> {
+        let names = (0..12).map(|i| i.to_string()).collect::<Vec<_>>();
+        let g = GameBuilder::new(names.clone(), names).build()?;
+        let s = g.count(Duration::ZERO)?;
+        assert_eq!(s.total, BigUint::from(479001600u32));
+        assert!(s.sampled < 479001600);
+        Ok(())
+    }
+
+    #[test]
+    fn count_large() -> Result<()> {
+        let names = (0..24).map(|i| i.to_string()).collect::<Vec<_>>();
+        let g = GameBuilder::new(names.clone(), names)
+            .rule_set(RuleSetParse::NToN)
+            .build()?;
+        let s = SearchSpace {
+            total: g.rule_set.count_perms(24, 24)?,
+            sampled: 0,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(s.total.to_string(), "316234143225");
+        assert_eq!(s.estimated_runtime(), None);
+        assert_eq!(
+            s.to_string(),
+            "Search space: 316234143225 (~3.16e11)\nEstimated runtime: unknown (nothing sampled)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fmt_duration_simple() {
+        assert_eq!(fmt_duration(Duration::from_millis(1234)), "1.23s");
+        assert_eq!(fmt_duration(Duration::from_secs(61)), "1m 1s");
+        assert_eq!(
+            fmt_duration(Duration::from_secs(3 * 86400 + 4 * 3600)),
+            "3d 4h"
+        );
+        assert_eq!(fmt_duration(Duration::from_secs(400 * 86400)), "1y 35d");
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module rates the decisions made for each event of the season (see
+//! [`crate::solver::efficiency`]). The expected information of each event is taken from the
+//! distribution of its lights, the best match box from the table of the solutions left before it.
+//! Only for comparing seatings with the strategies the solutions left before a night are needed,
+//! these are taken from the [`SurvivorTrail`] recorded during the simulation.
+
+use anyhow::{Context, Result};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::constraint::evaluate_predicates::ConstraintEval;
+use crate::constraint::Constraint;
+use crate::game::eval_utils::merged_first_indices;
+use crate::game::remaining::MN_SAMPLE_THRESHOLD;
+use crate::game::Game;
+use crate::solver::efficiency::{rate_box, rate_night};
+use crate::solver::mb::optimal::OptimalMbOptimizer;
+use crate::solver::mn::entropy_left::EntropyLeftMnOptimizer;
+use crate::solver::remaining::SurvivorTrail;
+use crate::Rem;
+
+impl Game {
+    /// Rate the decisions of each of the `merged` constraints and store the rating in the
+    /// constraints. `raw` are the constraints before merging (as used in the simulation which
+    /// recorded the `trail`), `rem` is the state before the first constraint.
+    ///
+    /// Only events with lights are rated. Constraints covered by the cache used are skipped, the
+    /// solutions left before them are unknown. Nights before which the solutions left were not
+    /// kept (too many) are not compared with the strategies.
+    pub(super) fn rate_decisions(
+        &self,
+        merged: &mut [Constraint],
+        raw: &[Constraint],
+        trail: Option<&SurvivorTrail>,
+        mut rem: Rem,
+    ) -> Result<()> {
+        if !self.rate_decisions {
+            return Ok(());
+        }
+        let covered = self.cache_covers();
+        let mn = EntropyLeftMnOptimizer::new(MN_SAMPLE_THRESHOLD);
+        // reproducible ratings
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // rate based on the state before the first of the constraints merged into `c`
+        for (c, idx) in merged.iter_mut().zip(merged_first_indices(raw)) {
+            let chosen = c
+                .expected_information()
+                .filter(|_| idx >= covered && rem.1 > 0);
+            let rating = chosen.map(|chosen| {
+                if c.is_mb() {
+                    rate_box(chosen, &rem, &OptimalMbOptimizer, &mut rng)
+                } else {
+                    let left = trail.and_then(|t| t.left_before(idx));
+                    rate_night(
+                        chosen,
+                        left.as_deref(),
+                        self.map_a.len(),
+                        self.map_b.len(),
+                        &mn,
+                        &mut rng,
+                    )
+                }
+            });
+            c.set_decision_rating(rating);
+            rem = c.apply_to_rem(rem).context("Apply to rem failed")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use std::io::Write;
+
+    use crate::constraint::compare::EvalEvent;
+    use crate::dump_mode::DumpMode;
+    use crate::game::builder::{box_night, events_of, GameBuilder};
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+    use crate::solver::efficiency::DecisionRating;
+
+    /// the ratings of each event of `g` (as stored for the comparison)
+    fn ratings(g: &mut Game) -> Result<Vec<Option<DecisionRating>>> {
+        events_of(g, |e| match e {
+            EvalEvent::MB(e) => Some(e.rating),
+            EvalEvent::MN(e) => Some(e.rating),
+            EvalEvent::Initial(_) => None,
+        })
+    }
+
+    #[test]
+    fn rate_decisions_simple() -> Result<()> {
+        // not requested
+        let mut g = box_night().build()?;
+        assert_eq!(ratings(&mut g)?, vec![None; 2]);
+
+        let mut g = box_night().build()?;
+        g.set_rate_decisions(true);
+        let r = ratings(&mut g)?;
+        // initially every pair has a probability of 25% => no better box
+        let mb = r[0].unwrap();
+        assert!((mb.chosen - 0.8112781244591328).abs() < 1e-9);
+        assert_eq!(mb.efficiency(), Some(1.0));
+        assert_eq!(mb.random, None);
+        // the night is compared with a random seating as well
+        let mn = r[1].unwrap();
+        assert!(mn.chosen > 0.0 && mn.best.unwrap() > 0.0);
+        assert!(mn.random.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn rate_decisions_many_left() -> Result<()> {
+        // 8! solutions are too many to keep them => the night can't be compared with the
+        // strategies but the expected information is still known
+        let names = (0..8).map(|i| i.to_string()).collect::<Vec<_>>();
+        let mut g = GameBuilder::new(names.clone(), names)
+            .box_(1, [("0", "0")], 0)
+            .night(
+                1,
+                (0..8).map(|i| (i.to_string(), ((i + 1) % 8).to_string())),
+                2,
+            )
+            .build()?;
+        g.set_rate_decisions(true);
+        let r = ratings(&mut g)?;
+        let mb = r[0].unwrap();
+        assert!((mb.chosen - 0.5435644431995964).abs() < 1e-9);
+        assert_eq!(mb.best, Some(mb.chosen));
+        let mn = r[1].unwrap();
+        assert!(mn.chosen > 0.0);
+        assert_eq!((mn.best, mn.random), (None, None));
+        Ok(())
+    }
+
+    #[test]
+    fn rate_decisions_cache() -> Result<()> {
+        let mut g = box_night().build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(Some(DumpMode::Full))?;
+
+        // a cache containing the solutions left after the night
+        let mut cache = tempfile::NamedTempFile::new()?;
+        for p in is.left_poss.iter() {
+            serde_json::to_writer(&mut cache, &p?)?;
+            writeln!(cache)?;
+        }
+
+        // the cache is no candidate of the game => it is unknown which events it covers (without
+        // this the events would be rated based on the solutions left after them)
+        let mut g = box_night().build()?;
+        g.set_rate_decisions(true);
+        g.cache_file = Some(cache.path().to_path_buf());
+        assert_eq!(ratings(&mut g)?, vec![None; 2]);
+        Ok(())
+    }
+}
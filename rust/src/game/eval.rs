@@ -31,6 +31,23 @@ impl Game {
         // EVALUATION
         // preprocess the constraints for printing
         let mut constraints = merge_constraints(&is.constraints)?;
+        // estimate how many events are still needed after each event (if requested)
+        self.estimate_remaining_events(
+            &mut constraints,
+            &is.constraints,
+            is.survivor_trail.as_ref(),
+        );
+        // rate the decisions made for each event (if requested)
+        self.rate_decisions(
+            &mut constraints,
+            &is.constraints,
+            is.survivor_trail.as_ref(),
+            (is.each.clone(), is.total),
+        )?;
+        // rate the decisions on the offers for the match boxes
+        self.rate_offers(&mut constraints, (is.each.clone(), is.total))?;
+        // follow the prize pot (if specified)
+        self.track_pot(&mut constraints);
         // process the constraints and derive the tables with how often each matching occurs
         let report_data =
             gen_report_data(&mut constraints, (is.each.clone(), is.total), &self.map_a)?;
@@ -42,7 +59,13 @@ impl Game {
         // COMPARISON
         // this is gethering data for a comparison at a later point in time
         let solutions = is.keep_rem.then_some(&is.left_poss);
-        self.write_comparison_data(is.total as f64, &constraints, solutions)?;
+        self.write_comparison_data(
+            is.total as f64,
+            &constraints,
+            solutions,
+            &is.collectors,
+            &is.query_conditional,
+        )?;
 
         Ok(())
     }
@@ -61,6 +84,7 @@ impl Game {
         // track table indices
         let mut tab_idx = 0;
         let mut md_tables: Vec<MdTable> = vec![];
+        let mut cond_tables: Vec<MdTable> = vec![];
 
         // generate additional tables
         {
@@ -75,11 +99,39 @@ impl Game {
                 // markdown code
                 tab_idx += m_data.tab_cnt();
             }
+            // the conditional tables are listed after the trail in the markdown output
+            tab_idx = self.report_conditional(
+                &is.query_conditional,
+                print_transposed,
+                tab_idx,
+                &mut cond_tables,
+            );
             let p_data =
                 query_pairs::QueryPairReport::new(&is.query_pair, &self.map_a, &self.map_b)?;
             print!("{p_data}");
+
+            for c in &is.collectors {
+                c.print(
+                    &mut std::io::stdout(),
+                    full,
+                    &self.map_a,
+                    &self.map_b,
+                    is.survivors,
+                )?;
+            }
         }
 
+        // statistics per person (the nights with the match are only counted if the solution is
+        // known), printed before the trail so the summary stays the second to last block of the
+        // output
+        let solution = if is.keep_rem && is.left_poss.len() == 1 {
+            is.left_poss.iter().next().transpose()?
+        } else {
+            None
+        };
+        let persons = self.person_stats(&data, solution.as_ref());
+        tab_idx += self.print_person_stats(&persons);
+
         // this function prints the report which was generated before
         // it also collects the tables which shall be included in the markdown file, for this it
         // appends to md_tables
@@ -91,9 +143,15 @@ impl Game {
             tab_idx,
             &mut md_tables,
         )?;
+        md_tables.extend(cond_tables);
 
         let md_path = self.dir.join(self.stem.clone()).with_extension("md");
-        self.write_page_md(&mut File::create(md_path.clone())?, &md_tables)?;
+        let mut plots = vec![];
+        for c in &is.collectors {
+            plots.extend(c.page_plot(&self.map_a, &self.map_b)?);
+        }
+        plots.extend(self.person_plots(&persons, &data, solution.is_some()));
+        self.write_page_md(&mut File::create(md_path.clone())?, &md_tables, &plots)?;
 
         Ok(())
     }
@@ -114,6 +172,10 @@ impl Game {
         println!("{}", self.summary_table(false, constraints)?);
         println!("{}", self.summary_table(true, constraints)?);
 
+        if let Some(pot) = self.pot_summary(constraints) {
+            println!("{pot}");
+        }
+
         println!(
             "Total permutations: {}  Permutations left: {}  Initial combinations for each pair: {}",
             is.total, is.survivors, is.each[0][0]
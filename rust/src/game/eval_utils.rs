@@ -28,6 +28,26 @@ pub(super) fn merge_constraints<T: ConstraintMerge + Clone>(constraints: &[T]) -
     Ok(merged)
 }
 
+/// the index of the constraint in `constraints` each merged constraint (see [`merge_constraints`])
+/// corresponds to, i.e. the constraint which is not merged into the next one
+pub(super) fn merged_indices<T: ConstraintMerge>(constraints: &[T]) -> Vec<usize> {
+    constraints
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.should_merge())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// the index of the first constraint in `constraints` each merged constraint (see
+/// [`merge_constraints`]) consists of, i.e. the first one of the constraints merged into it
+pub(super) fn merged_first_indices<T: ConstraintMerge>(constraints: &[T]) -> Vec<usize> {
+    let last = merged_indices(constraints);
+    // each merged constraint starts right after the previous one ended
+    let first = std::iter::once(0).chain(last.iter().map(|i| i + 1));
+    first.take(last.len()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +162,33 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn merged_indices_simple() {
+        let mk = |mergeable| MockConstraint {
+            value: 0,
+            mergeable,
+        };
+        assert_eq!(merged_indices::<MockConstraint>(&[]), Vec::<usize>::new());
+        assert_eq!(
+            merged_indices(&[mk(false), mk(true), mk(true), mk(false), mk(false)]),
+            vec![0, 3, 4]
+        );
+    }
+
+    #[test]
+    fn merged_first_indices_simple() {
+        let mk = |mergeable| MockConstraint {
+            value: 0,
+            mergeable,
+        };
+        assert_eq!(
+            merged_first_indices::<MockConstraint>(&[]),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            merged_first_indices(&[mk(false), mk(true), mk(true), mk(false), mk(false)]),
+            vec![0, 1, 4]
+        );
+    }
 }
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module abstracts over the file formats a game-config can be stored in. Besides yaml (the
+//! format used for all the seasons in this repo) json and toml are supported. Which format is used
+//! is detected based on the extension of the file.
+//!
+//! In json and toml there are no tags, so e.g. `type: !Box {..}` is written as `type: {Box: {..}}`
+//! (the externally tagged representation of serde).
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer};
+
+/// The file formats a game-config can be read from/written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// yaml (`.yaml`/`.yml`)
+    Yaml,
+    /// json (`.json`)
+    Json,
+    /// toml (`.toml`)
+    Toml,
+}
+
+impl Format {
+    /// Detect the format based on the extension of `path`
+    pub fn from_path(path: &Path) -> Result<Format> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .with_context(|| format!("{} has no extension", path.display()))?;
+        match ext.to_lowercase().as_str() {
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "json" => Ok(Format::Json),
+            "toml" => Ok(Format::Toml),
+            _ => bail!("unknown format '{}' of {}", ext, path.display()),
+        }
+    }
+
+    /// Deserialize `src` which is stored in this format
+    pub fn parse<T: DeserializeOwned>(&self, src: &str) -> Result<T> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::from_str(src)?,
+            Format::Json => serde_json::from_str(src)?,
+            Format::Toml => toml::from_str(src)?,
+        })
+    }
+
+    /// Serialize `value` to a string in this format
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::to_string(value)?,
+            Format::Json => serde_json::to_string_pretty(value)? + "\n",
+            Format::Toml => {
+                let mut value = serde_json::to_value(value)?;
+                strip_nulls(&mut value);
+                toml::to_string(&value)?
+            }
+        })
+    }
+}
+
+/// toml has no null values. Within lists they are replaced by an empty string (e.g. an unknown
+/// individual of `XTimesDup`), within tables they are dropped.
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Array(a) => {
+            for v in a {
+                if v.is_null() {
+                    *v = serde_json::Value::String(String::new());
+                } else {
+                    strip_nulls(v);
+                }
+            }
+        }
+        serde_json::Value::Object(o) => {
+            o.retain(|_, v| !v.is_null());
+            o.values_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+/// Serialize a hashmap with its keys sorted, so the output is stable
+pub(crate) fn sorted<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(s)
+}
+
+/// Serialize a list of hashmaps with their keys sorted, so the output is stable
+pub(crate) fn sorted_seq<S: Serializer, V: Serialize>(
+    maps: &[HashMap<String, V>],
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    maps.iter()
+        .map(|m| m.iter().collect::<BTreeMap<_, _>>())
+        .collect::<Vec<_>>()
+        .serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn from_path_simple() {
+        assert_eq!(
+            Format::from_path(&PathBuf::from("a/b.yml")).unwrap(),
+            Format::Yaml
+        );
+        assert_eq!(
+            Format::from_path(&PathBuf::from("b.JSON")).unwrap(),
+            Format::Json
+        );
+        assert_eq!(
+            Format::from_path(&PathBuf::from("b.toml")).unwrap(),
+            Format::Toml
+        );
+        assert!(Format::from_path(&PathBuf::from("b.txt")).is_err());
+        assert!(Format::from_path(&PathBuf::from("b")).is_err());
+    }
+
+    #[test]
+    fn strip_nulls_simple() {
+        let mut v = serde_json::json!({"a": null, "b": [null, "x"], "c": {"d": null}});
+        strip_nulls(&mut v);
+        assert_eq!(v, serde_json::json!({"b": ["", "x"], "c": {}}));
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module contains the checks of the linter (see [`crate::lint`]) which concern the game as a
+//! whole. The checks of the single constraints are delegated to the constraint module.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::game::parse::GameParse;
+use crate::lint::spans::SpanIndex;
+use crate::lint::{did_you_mean, Diagnostic, Severity};
+use crate::yaml_path;
+use crate::Lut;
+
+impl GameParse {
+    /// Lint the parsed game-config. `spans` is used to attach the position in the yaml file to
+    /// the findings.
+    pub(crate) fn lint(&self, spans: &SpanIndex) -> Vec<Diagnostic> {
+        let mut diags = vec![];
+
+        // build the luts, duplicates are only reported
+        let mut luts = (Lut::default(), Lut::default());
+        for (lut, map, set) in [
+            (&mut luts.0, &self.map_a, "setA"),
+            (&mut luts.1, &self.map_b, "setB"),
+        ] {
+            for (idx, name) in map.iter().enumerate() {
+                if lut.contains_key(name) {
+                    diags.push(Diagnostic::error(
+                        format!("duplicate entry '{}' in {}", name, set),
+                        spans.value(&yaml_path![set, idx]),
+                    ));
+                } else {
+                    lut.insert(name.clone(), idx);
+                }
+            }
+        }
+        let (lut_a, lut_b) = luts;
+
+        let rule_set = self.rule_set.clone().finalize_parsing();
+        if let Err(e) = rule_set.validate_lut(&lut_a, &lut_b) {
+            diags.push(Diagnostic::error(
+                e.to_string(),
+                spans.key(&yaml_path!["rule_set"]),
+            ));
+        }
+
+        // names which are referenced outside of the constraints
+        let unknown = |name: &String, lut: &Lut, set: &str, path| {
+            (!lut.contains_key(name)).then(|| {
+                Diagnostic::error(format!("'{}' is not part of {}", name, set), path)
+                    .with_suggestion(did_you_mean(name, lut.keys()))
+            })
+        };
+        for (key, rename, lut, set) in [
+            ("renameA", &self.rename_a, &lut_a, "setA"),
+            ("renameB", &self.rename_b, &lut_b, "setB"),
+        ] {
+            for name in rename.keys() {
+                diags.extend(
+                    unknown(name, lut, set, spans.key(&yaml_path![key, name])).map(|d| {
+                        Diagnostic {
+                            severity: Severity::Warning,
+                            ..d
+                        }
+                    }),
+                );
+            }
+        }
+        for (key, names, lut) in [
+            ("setA", &self.query_pair_s.map_a, &lut_a),
+            ("setB", &self.query_pair_s.map_b, &lut_b),
+        ] {
+            for (i, name) in names.iter().enumerate() {
+                diags.extend(unknown(
+                    name,
+                    lut,
+                    key,
+                    spans.value(&yaml_path!["queryPair", key, i]),
+                ));
+            }
+        }
+        for (i, m) in self.query_conditional_s.iter().enumerate() {
+            for (a, b) in m {
+                let path = yaml_path!["queryConditional", i, a];
+                diags.extend(unknown(a, &lut_a, "setA", spans.key(&path)));
+                diags.extend(unknown(b, &lut_b, "setB", spans.value(&path)));
+            }
+        }
+        for (i, m) in self.query_matchings_s.iter().enumerate() {
+            for (a, bs) in m {
+                let path = yaml_path!["queryMatchings", i, a];
+                diags.extend(unknown(a, &lut_a, "setA", spans.key(&path)));
+                for (j, b) in bs.iter().enumerate() {
+                    let mut path = path.to_vec();
+                    path.push(j.into());
+                    diags.extend(unknown(b, &lut_b, "setB", spans.value(&path)));
+                }
+            }
+        }
+
+        // the constraints themselves
+        let map_len = rule_set.constr_map_len(lut_a.len(), lut_b.len());
+        let mut last: HashMap<&str, Decimal> = HashMap::new();
+        for (idx, c) in self.constraints_orig.iter().enumerate() {
+            diags.extend(c.lint(idx, &lut_a, &lut_b, map_len, spans));
+
+            // equal numbers are fine, multiple constraints can describe the same event
+            let (kind, num) = c.kind_and_num();
+            if let Some(prev) = last.insert(kind, num).filter(|prev| *prev > num) {
+                diags.push(Diagnostic::warning(
+                    format!("{} #{} comes after {} #{}", kind, num, kind, prev),
+                    spans.value(&yaml_path!["constraints", idx, "type"]),
+                ));
+                last.insert(kind, prev);
+            }
+        }
+
+        diags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::lint::{lint_str, Position};
+
+    const SRC: &str = "rule_set: !Eq
+frontmatter: {}
+setA: [A, B, Cara]
+setB: [a, b, c]
+renameA:
+  Carra: C
+queryPair:
+  setA: [A]
+constraints:
+  - type: !Box {num: 2, comment: \"\"}
+    check: !Lights [1]
+    map:
+      Cara: c
+  - type: !Box {num: 1, comment: \"\"}
+    check: !Lights [0]
+    map:
+      A: a
+";
+
+    #[test]
+    fn lint_game() {
+        let diags = lint_str(SRC);
+        assert_eq!(
+            diags,
+            vec![
+                Diagnostic::warning(
+                    "'Carra' is not part of setA".to_string(),
+                    Some(Position { line: 6, column: 3 })
+                )
+                .with_suggestion(Some("Cara".to_string())),
+                Diagnostic::warning(
+                    "Box #1 comes after Box #2".to_string(),
+                    Some(Position {
+                        line: 14,
+                        column: 16
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_game_duplicates() {
+        let src = SRC.replace("[a, b, c]", "[a, b, a]");
+        let diags = lint_str(&src);
+        assert_eq!(diags.len(), 5);
+        // setA and setB don't fit the ruleset anymore
+        assert_eq!(diags[0].position, Some(Position { line: 1, column: 1 }));
+        assert_eq!(diags[1].severity, Severity::Error);
+        assert_eq!(diags[1].message, "duplicate entry 'a' in setB");
+        assert_eq!(diags[3].message, "'c' is not part of setB");
+    }
+
+    #[test]
+    fn lint_all_seasons() {
+        for entry in std::fs::read_dir("../data").unwrap() {
+            let dir = entry.unwrap().path();
+            let Some(stem) = dir.file_name() else {
+                continue;
+            };
+            let path = dir.join(stem).with_extension("yaml");
+            if !path.exists() {
+                continue;
+            }
+            let diags = crate::lint::lint_file(&path).unwrap();
+            assert!(
+                diags.iter().all(|d| d.severity == Severity::Warning),
+                "{}: {:?}",
+                path.display(),
+                diags
+            );
+        }
+    }
+}
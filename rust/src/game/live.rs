@@ -0,0 +1,555 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements the live mode which allows to follow an episode while it is
+//! broadcasted. The results (box pair, night seating, amount of lights) are entered in a REPL and
+//! the table of the remaining probabilities updates after each event.
+//!
+//! The live mode starts from the most recent cache (if available) and keeps the remaining
+//! solutions in memory. A new event only has to filter the solutions left after the previous one.
+//! In the end the new events are written back to the game-config in the format used in `data/`.
+
+mod command;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use rust_decimal::Decimal;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::constraint::{Constraint, ConstraintGetters};
+use crate::game::cache::{CacheMode, CacheSpec};
+use crate::game::format::Format;
+use crate::game::live::command::{Command, HELP};
+use crate::game::parse::GameParse;
+use crate::game::report_utils::print_rem_generic;
+use crate::game::Game;
+use crate::ignore_ops::IgnoreOps;
+use crate::iterstate::IterState;
+use crate::lint::did_you_mean;
+use crate::lint::spans::SpanIndex;
+use crate::matching_repr::MaskedMatching;
+use crate::progressbar::{ProgressBar, ProgressBarTrait};
+use crate::{yaml_path, LightCnt, Rem};
+
+/// The kinds of events which can be entered in the live mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum EventKind {
+    /// a match-box
+    Box,
+    /// a matching-night
+    Night,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::Box => write!(f, "Box"),
+            EventKind::Night => write!(f, "Night"),
+        }
+    }
+}
+
+/// The result of an event entered in the live mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LiveCheck {
+    /// the amount of lights
+    Lights(LightCnt),
+    /// the box was sold
+    Sold,
+}
+
+/// An event entered in the live mode
+#[derive(Debug, Clone, PartialEq)]
+struct LiveEvent {
+    /// box or night
+    kind: EventKind,
+    /// the number of the event
+    num: Decimal,
+    /// comment of the event (usually the episode)
+    comment: String,
+    /// the pairs in the order they were entered
+    map: Vec<(String, String)>,
+    /// the result, `None` as long as the event is still entered
+    check: Option<LiveCheck>,
+}
+
+impl LiveEvent {
+    /// label of the event as used in the reports (e.g. `MB#3`)
+    fn label(&self) -> String {
+        match self.kind {
+            EventKind::Box => format!("MB#{}", self.num),
+            EventKind::Night => format!("MN#{}", self.num),
+        }
+    }
+
+    /// Render the event as entry of the constraints in the game-config, indented by `indent`
+    fn to_yaml(&self, indent: usize) -> String {
+        let pad = " ".repeat(indent);
+        let comment = self.comment.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut s = format!(
+            "{}- type: !{} {{num: {}, comment: \"{}\"}}\n",
+            pad, self.kind, self.num, comment
+        );
+        match self.check {
+            Some(LiveCheck::Lights(l)) => s += &format!("{}  check: !Lights [{}]\n", pad, l),
+            Some(LiveCheck::Sold) => s += &format!("{}  check: !Sold\n", pad),
+            None => {}
+        }
+        s += &format!("{}  map:\n", pad);
+        for (a, b) in &self.map {
+            s += &format!("{}    {}: {}\n", pad, a, b);
+        }
+        s
+    }
+}
+
+/// The state of the live mode
+pub struct LiveSession {
+    /// where the game-config is stored
+    path: PathBuf,
+    /// the content of the game-config when the live mode was started
+    src: String,
+    /// the events entered so far (already finished)
+    events: Vec<LiveEvent>,
+    /// the event which is currently entered
+    pending: Option<LiveEvent>,
+    /// whether there are changes which are not written to the game-config yet
+    dirty: bool,
+    /// the game built from the game-config including the events entered
+    game: Game,
+    /// the highest number of each kind of event in the game
+    last_num: HashMap<EventKind, Decimal>,
+    /// the solutions left after the game-config and after each event entered
+    stages: Vec<Vec<MaskedMatching>>,
+}
+
+impl LiveSession {
+    /// Start the live mode for the game-config at `path`. The remaining solutions are computed
+    /// starting from the most recent cache if one is available.
+    pub fn new<T: ProgressBarTrait>(path: &Path) -> Result<Self> {
+        if Format::from_path(path)? != Format::Yaml {
+            bail!("the live mode only supports yaml game-configs");
+        }
+        let src = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        let mut s = LiveSession {
+            path: path.to_path_buf(),
+            src,
+            events: vec![],
+            pending: None,
+            dirty: false,
+            game: Game::default(),
+            last_num: HashMap::new(),
+            stages: vec![],
+        };
+        s.rebuild()?;
+
+        let cs: Vec<CacheSpec> = s.game.get_cache_candidates();
+        if s.game
+            .select_cache(&cs, CacheMode::MostRecent, &None, true)
+            .is_err()
+        {
+            println!("No cache found, simulating from scratch");
+        }
+        // the remaining solutions are the base for the events entered
+        s.game.keep_rem = true;
+        let is: IterState<T, Constraint> = s.game.sim(None)?;
+        s.stages.push(is.left_poss.to_vec()?);
+        Ok(s)
+    }
+
+    /// The game-config with the events entered so far appended to the constraints
+    fn text(&self) -> Result<String> {
+        if self.events.is_empty() {
+            return Ok(self.src.clone());
+        }
+        let (spans, _) = SpanIndex::new(&self.src).map_err(|d| anyhow!(d.to_string()))?;
+        let constraints = yaml_path!["constraints"];
+        if spans.get(&yaml_path!["constraints", 0]).is_none() {
+            bail!("the game-config needs at least one constraint the new events can follow");
+        }
+        // the block sequence starts at the '-' of the first event
+        let indent = spans
+            .value(&constraints)
+            .map_or(2, |p| p.column.saturating_sub(1));
+        let mut last = spans.last_line(&constraints).context("no constraints")?;
+
+        let lines = self.src.split_inclusive('\n').collect::<Vec<_>>();
+        // comments directly below the last event belong to it
+        while let Some(l) = lines.get(last) {
+            let t = l.trim_start();
+            if !t.starts_with('#') || l.len() - t.len() <= indent {
+                break;
+            }
+            last += 1;
+        }
+        let (head, tail) = lines.split_at(last.min(lines.len()));
+        let mut text = head.concat();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        for e in &self.events {
+            text += "\n";
+            text += &e.to_yaml(indent);
+        }
+        let tail = tail.concat();
+        if !tail.is_empty() && !tail.starts_with('\n') {
+            text.push('\n');
+        }
+        text += &tail;
+        Ok(text)
+    }
+
+    /// Build the game again from the game-config and the events entered
+    fn rebuild(&mut self) -> Result<()> {
+        let gp = GameParse::new_from_str(&self.text()?)?;
+        self.last_num.clear();
+        for c in &gp.constraints_orig {
+            let (kind, num) = c.kind_and_num();
+            let kind = if kind == "Box" {
+                EventKind::Box
+            } else {
+                EventKind::Night
+            };
+            let last = self.last_num.entry(kind).or_insert(num);
+            *last = (*last).max(num);
+        }
+        self.game = gp.finalize_parsing(&self.path, &IgnoreOps::Nothing)?;
+        self.game.keep_rem = true;
+        Ok(())
+    }
+
+    /// The remaining amount of solutions after the last event
+    fn rem(&self) -> Rem {
+        let mut each = vec![vec![0; self.game.map_b.len()]; self.game.map_a.len()];
+        let stage = self.stages.last().map(|s| s.as_slice()).unwrap_or_default();
+        for m in stage {
+            m.count_pairs(&mut each);
+        }
+        (each, stage.len() as u128)
+    }
+
+    /// The table of the remaining probabilities after the last event
+    fn table(&self) -> String {
+        print_rem_generic(
+            &self.rem(),
+            &self.game.map_a,
+            &self.game.map_b,
+            |v, h| (v, h),
+            |a, b| self.game.rule_set.ignore_pairing(a, b),
+        )
+        .to_string()
+    }
+
+    /// The prompt to show, tells which event is currently entered
+    fn prompt(&self) -> String {
+        match &self.pending {
+            Some(e) => format!("{} ({} pairs)> ", e.label(), e.map.len()),
+            None => "live> ".to_string(),
+        }
+    }
+
+    /// Start entering a new event of `kind`
+    fn start_event(&mut self, kind: EventKind, comment: String) -> Result<String> {
+        if let Some(e) = &self.pending {
+            bail!("finish {} first (lights/sold) or undo it", e.label());
+        }
+        let num = self
+            .last_num
+            .get(&kind)
+            .map_or(Decimal::ONE, |n| n.floor() + Decimal::ONE);
+        let e = LiveEvent {
+            kind,
+            num,
+            comment,
+            map: vec![],
+            check: None,
+        };
+        let msg = format!("started {}", e.label());
+        self.pending = Some(e);
+        Ok(msg)
+    }
+
+    /// Add the pair `a`, `b` to the event which is currently entered
+    fn add_pair(&mut self, a: String, b: String) -> Result<String> {
+        let e = self.pending.as_ref().context("start a box/night first")?;
+        for (name, lut, set) in [
+            (&a, &self.game.lut_a, "setA"),
+            (&b, &self.game.lut_b, "setB"),
+        ] {
+            if !lut.contains_key(name) {
+                match did_you_mean(name, lut.keys()) {
+                    Some(s) => bail!("'{}' is not part of {} (did you mean '{}'?)", name, set, s),
+                    None => bail!("'{}' is not part of {}", name, set),
+                }
+            }
+        }
+        if e.kind == EventKind::Box && !e.map.is_empty() {
+            bail!("a box consists of a single pair");
+        }
+        if let Some((x, y)) = e.map.iter().find(|(x, y)| *x == a || *y == b) {
+            bail!("{} is already paired as {} - {}", e.label(), x, y);
+        }
+
+        let prob = self.game.pair_prob(&self.rem(), &a, &b).unwrap_or_default();
+        let msg = format!("Pr[{} - {}]: {:.1}%", a, b, prob);
+        if let Some(e) = self.pending.as_mut() {
+            e.map.push((a, b));
+        }
+        Ok(msg)
+    }
+
+    /// Finish the event which is currently entered with `check` and filter the remaining
+    /// solutions accordingly
+    fn finish_event(&mut self, check: LiveCheck) -> Result<String> {
+        let mut e = self.pending.clone().context("start a box/night first")?;
+        if e.map.is_empty() {
+            bail!("{} has no pairs yet", e.label());
+        }
+        if check == LiveCheck::Sold && e.kind != EventKind::Box {
+            bail!("only a box can be sold");
+        }
+        e.check = Some(check);
+
+        self.events.push(e);
+        let next = self.rebuild().and_then(|_| {
+            let mut c = self
+                .game
+                .constraints_orig
+                .last()
+                .context("the event was not added")?
+                .clone();
+            let prev = self.stages.last().context("no remaining solutions")?;
+            let next = prev
+                .iter()
+                .filter(|m| c.admits(m))
+                .cloned()
+                .collect::<Vec<_>>();
+            if next.is_empty() {
+                bail!("no solution is left with this result, please check the input");
+            }
+            Ok((c.type_str(), next))
+        });
+        let (label, next) = match next {
+            Ok(x) => x,
+            Err(err) => {
+                // keep the event pending so the input can be corrected via undo
+                self.events.pop();
+                self.rebuild()?;
+                return Err(err);
+            }
+        };
+
+        self.stages.push(next);
+        self.pending = None;
+        self.dirty = true;
+        Ok(format!("{}\n{}", label, self.table()))
+    }
+
+    /// Undo the last input. This is either the last pair of the event which is currently entered
+    /// or the last finished event.
+    fn undo(&mut self) -> Result<String> {
+        if let Some(e) = self.pending.as_mut() {
+            return Ok(match e.map.pop() {
+                Some((a, b)) => format!("removed {} - {} from {}", a, b, e.label()),
+                None => {
+                    let label = e.label();
+                    self.pending = None;
+                    format!("discarded {}", label)
+                }
+            });
+        }
+        let e = self.events.pop().context("nothing to undo")?;
+        self.stages.pop();
+        self.rebuild()?;
+        self.dirty = true;
+        Ok(format!("removed {}\n{}", e.label(), self.table()))
+    }
+
+    /// Write the game-config including the events entered to disk
+    fn write(&mut self) -> Result<String> {
+        fs::write(&self.path, self.text()?)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        self.dirty = false;
+        Ok(format!(
+            "wrote {} new event(s) to {}",
+            self.events.len(),
+            self.path.display()
+        ))
+    }
+
+    /// Execute `cmd` and return what should be shown to the user
+    fn exec(&mut self, cmd: Command) -> Result<String> {
+        match cmd {
+            Command::Box(comment) => self.start_event(EventKind::Box, comment),
+            Command::Night(comment) => self.start_event(EventKind::Night, comment),
+            Command::Pair(a, b) => self.add_pair(a, b),
+            Command::Lights(l) => self.finish_event(LiveCheck::Lights(l)),
+            Command::Sold => self.finish_event(LiveCheck::Sold),
+            Command::Undo => self.undo(),
+            Command::Show => Ok(self.table()),
+            Command::Write => self.write(),
+            Command::Help => Ok(HELP.to_string()),
+            // leaving is handled by the caller
+            Command::Quit { .. } => Ok(String::new()),
+        }
+    }
+}
+
+/// Run the live mode for the game-config at `path` until the user quits
+pub fn live(path: &Path) -> Result<()> {
+    let mut session = LiveSession::new::<ProgressBar>(path)?;
+    println!("{}", session.table());
+    println!("enter 'help' to show the available commands");
+
+    let mut rl = DefaultEditor::new()?;
+    loop {
+        let line = match rl.readline(&session.prompt()) {
+            Ok(line) => line,
+            // Ctrl-C only discards the current line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => "quit".to_string(),
+            Err(e) => return Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        rl.add_history_entry(line.as_str())?;
+
+        match line.parse::<Command>() {
+            Ok(Command::Quit { save }) => {
+                if save && session.dirty {
+                    println!("{}", session.write()?);
+                }
+                if session.pending.is_some() {
+                    println!("the unfinished event was discarded");
+                }
+                return Ok(());
+            }
+            Ok(cmd) => match session.exec(cmd) {
+                Ok(out) => println!("{}", out),
+                Err(e) => println!("error: {:#}", e),
+            },
+            Err(e) => println!("error: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::progressbar::MockProgressBar;
+
+    const SRC: &str = "rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+constraints:
+  - type: !Box {num: 1, comment: \"E01\"}
+    check: !Lights [0]
+    map:
+      A: a
+    # a comment
+
+# - type: !Night {num: 1, comment: \"\"}
+";
+
+    /// start a session on a temporary copy of `src`
+    fn session(src: &str) -> (tempfile::TempDir, LiveSession) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.yaml");
+        fs::write(&path, src).unwrap();
+        let s = LiveSession::new::<MockProgressBar>(&path).unwrap();
+        (dir, s)
+    }
+
+    /// parse and execute `line`
+    fn run(s: &mut LiveSession, line: &str) -> Result<String> {
+        s.exec(line.parse()?)
+    }
+
+    #[test]
+    fn live_events() -> Result<()> {
+        let (_dir, mut s) = session(SRC);
+        assert_eq!(s.rem().1, 4);
+
+        assert_eq!(run(&mut s, "night E02")?, "started MN#1");
+        assert_eq!(run(&mut s, "A b")?, "Pr[A - b]: 50.0%");
+        assert!(run(&mut s, "A c").is_err());
+        assert!(run(&mut s, "X c").unwrap_err().to_string().contains("setA"));
+        run(&mut s, "B: a")?;
+        run(&mut s, "C c")?;
+        assert!(run(&mut s, "sold").is_err());
+        assert_eq!(s.prompt(), "MN#1 (3 pairs)> ");
+
+        let out = run(&mut s, "lights 3")?;
+        assert!(out.starts_with("MN#1\n"));
+        assert_eq!(s.rem().1, 1);
+        assert!(s.pending.is_none());
+
+        // not consistent with the solution left
+        run(&mut s, "box")?;
+        run(&mut s, "A b")?;
+        assert!(run(&mut s, "lights 0").is_err());
+        assert_eq!(s.events.len(), 1);
+        assert_eq!(run(&mut s, "lights 1")?.lines().next(), Some("MB#2"));
+
+        run(&mut s, "undo")?;
+        assert_eq!(s.events.len(), 1);
+        assert_eq!(s.rem().1, 1);
+        run(&mut s, "undo")?;
+        assert_eq!(s.rem().1, 4);
+        assert!(run(&mut s, "undo").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn live_undo_pending() -> Result<()> {
+        let (_dir, mut s) = session(SRC);
+        run(&mut s, "box")?;
+        assert!(run(&mut s, "night").is_err());
+        run(&mut s, "A b")?;
+        assert_eq!(run(&mut s, "undo")?, "removed A - b from MB#2");
+        assert_eq!(run(&mut s, "undo")?, "discarded MB#2");
+        assert!(s.pending.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn live_write() -> Result<()> {
+        let (_dir, mut s) = session(SRC);
+        run(&mut s, "box E02 \"x\"")?;
+        run(&mut s, "B b")?;
+        run(&mut s, "lights 0")?;
+        run(&mut s, "write")?;
+        assert!(!s.dirty);
+
+        let written = fs::read_to_string(&s.path)?;
+        assert_eq!(
+            written,
+            SRC.replace(
+                "    # a comment\n",
+                "    # a comment
+
+  - type: !Box {num: 2, comment: \"E02 \\\"x\\\"\"}
+    check: !Lights [0]
+    map:
+      B: b
+"
+            )
+        );
+        // the written file is valid and contains the new event
+        let gp = GameParse::new_from_str(&written)?;
+        assert_eq!(gp.constraints_orig.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module parses the lines entered in the live mode into commands.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+use crate::LightCnt;
+
+/// A command entered in the live mode
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Command {
+    /// start a new match-box with an (optional) comment
+    Box(String),
+    /// start a new matching-night with an (optional) comment
+    Night(String),
+    /// add a pair (set_a, set_b) to the event which is currently entered
+    Pair(String, String),
+    /// finish the event which is currently entered with the amount of lights
+    Lights(LightCnt),
+    /// finish the event which is currently entered as sold
+    Sold,
+    /// undo the last input
+    Undo,
+    /// show the table of the current state again
+    Show,
+    /// write the events entered so far to the game-config
+    Write,
+    /// leave the live mode, `save` tells whether to write the events to the game-config
+    Quit {
+        /// whether to write the events entered to the game-config
+        save: bool,
+    },
+    /// show the available commands
+    Help,
+}
+
+/// short description of the available commands
+pub(super) const HELP: &str = "\
+box [comment]    start a new match-box
+night [comment]  start a new matching-night
+<A> <b>          add a pair to the current event (also `<A>: <b>`)
+lights <n>       finish the current event with n lights
+sold             finish the current event (box) as sold
+undo             undo the last input (pair or event)
+show             show the current table again
+write            write the new events to the game-config
+quit             write the new events and leave (`quit!` leaves without writing)
+help             show this help";
+
+impl FromStr for Command {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (cmd, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let rest = rest.trim();
+        Ok(match cmd {
+            "box" => Command::Box(rest.to_string()),
+            "night" => Command::Night(rest.to_string()),
+            "lights" => Command::Lights(
+                rest.parse()
+                    .with_context(|| format!("invalid amount of lights '{}'", rest))?,
+            ),
+            "sold" => Command::Sold,
+            "undo" => Command::Undo,
+            "show" => Command::Show,
+            "write" => Command::Write,
+            "quit" | "exit" => Command::Quit { save: true },
+            "quit!" | "exit!" => Command::Quit { save: false },
+            "help" | "?" => Command::Help,
+            _ => {
+                // everything else has to be a pair
+                let (a, b) = s
+                    .split_once(':')
+                    .or_else(|| s.split_once(char::is_whitespace))
+                    .with_context(|| format!("unknown command '{}' (try 'help')", s))?;
+                let (a, b) = (a.trim(), b.trim());
+                if a.is_empty() || b.is_empty() || b.contains(char::is_whitespace) {
+                    bail!("unknown command '{}' (try 'help')", s)
+                }
+                Command::Pair(a.to_string(), b.to_string())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            "box E05".parse::<Command>().unwrap(),
+            Command::Box("E05".to_string())
+        );
+        assert_eq!(
+            "night".parse::<Command>().unwrap(),
+            Command::Night("".to_string())
+        );
+        assert_eq!(" lights 3 ".parse::<Command>().unwrap(), Command::Lights(3));
+        assert_eq!(
+            "quit!".parse::<Command>().unwrap(),
+            Command::Quit { save: false }
+        );
+        assert!("lights x".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn parse_pairs() {
+        assert_eq!(
+            "Chris Linda".parse::<Command>().unwrap(),
+            Command::Pair("Chris".to_string(), "Linda".to_string())
+        );
+        assert_eq!(
+            "Chris:  Linda".parse::<Command>().unwrap(),
+            Command::Pair("Chris".to_string(), "Linda".to_string())
+        );
+        assert!("Chris".parse::<Command>().is_err());
+        assert!("Chris Linda Tonia".parse::<Command>().is_err());
+    }
+}
@@ -6,19 +6,30 @@
 //! output. The markdown rendering depends on that terminal output split into the various tables
 //! and converted to png files as specified. Similarly the tree (dot) files must have been
 //! generated and rendered to png files.
+//!
+//! Plots contributed by collectors (see [`crate::collector::Collector::page_plot`]) are embedded
+//! directly as interactive plotly plots.
 
 use std::io::Write;
 
 use anyhow::Result;
 
+use crate::collector::PagePlot;
+use crate::comparison::plotly::PLOTLY_SCRIPT;
 use crate::game::report_trail::MdTable;
 use crate::game::Game;
 
 impl Game {
     /// Write the main markdown output file (frontmatter + images/tabs).
     ///
-    /// `md_tables` describes which generated plots / images will be embedded in the page.
-    pub(super) fn write_page_md<W: Write>(&self, mut out: W, md_tables: &[MdTable]) -> Result<()> {
+    /// `md_tables` describes which generated plots / images will be embedded in the page, `plots`
+    /// are the interactive plots embedded after them.
+    pub(super) fn write_page_md<W: Write>(
+        &self,
+        mut out: W,
+        md_tables: &[MdTable],
+        plots: &[PagePlot],
+    ) -> Result<()> {
         writeln!(out, "---")?;
         writeln!(out, "{}", serde_yaml::to_string(&self.frontmatter)?)?;
         writeln!(out, "---")?;
@@ -63,6 +74,28 @@ impl Game {
             }
         }
 
+        if !plots.is_empty() {
+            writeln!(out, "\n{PLOTLY_SCRIPT}")?;
+        }
+        for plot in plots {
+            writeln!(
+                out,
+                "\n{{{{% details title=\"{}\" closed=\"true\" %}}}}",
+                plot.title
+            )?;
+            writeln!(
+                out,
+                "<div class=\"plot-container plot-light\">\n{}\n</div>",
+                plot.light
+            )?;
+            writeln!(
+                out,
+                "<div class=\"plot-container plot-dark\">\n{}\n</div>",
+                plot.dark
+            )?;
+            writeln!(out, "{{{{% /details %}}}}")?;
+        }
+
         writeln!(out, "\n{{{{% translateHdr \"tab-everything\" %}}}}\n:warning: {{{{< i18n \"spoiler-warning\" >}}}} :warning:")?;
         writeln!(out, "{{{{% details closed=\"true\" %}}}}")?;
         writeln!(out, "{{{{% img src=\"/{stem}/{stem}.col.png\" %}}}}")?;
@@ -86,7 +119,7 @@ mod tests {
         };
         let tabs = vec![];
         let mut buf = vec![];
-        game.write_page_md(&mut buf, &tabs).unwrap();
+        game.write_page_md(&mut buf, &tabs, &[]).unwrap();
         let output = String::from_utf8(buf).unwrap();
 
         let expected = r#"---
@@ -141,7 +174,7 @@ title: abc
             },
         ];
         let mut buf = vec![];
-        game.write_page_md(&mut buf, &tabs).unwrap();
+        game.write_page_md(&mut buf, &tabs, &[]).unwrap();
         let output = String::from_utf8(buf).unwrap();
 
         let expected = r#"---
@@ -181,4 +214,39 @@ title: abc
 
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn write_page_md_plots() {
+        let game = Game {
+            stem: "stem".to_string(),
+            frontmatter: serde_yaml::from_str("title: abc").unwrap(),
+            ..Default::default()
+        };
+        let plots = vec![PagePlot {
+            title: "corr".to_string(),
+            light: "<div>light</div>".to_string(),
+            dark: "<div>dark</div>".to_string(),
+        }];
+        let mut buf = vec![];
+        game.write_page_md(&mut buf, &[], &plots).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        let expected = r#"
+{{% translateHdr "tab-individual" %}}
+
+<script src="https://cdn.plot.ly/plotly-3.3.1.min.js"></script>
+
+{{% details title="corr" closed="true" %}}
+<div class="plot-container plot-light">
+<div>light</div>
+</div>
+<div class="plot-container plot-dark">
+<div>dark</div>
+</div>
+{{% /details %}}
+
+{{% translateHdr "tab-everything" %}}
+"#;
+        assert!(output.contains(expected), "{output}");
+    }
 }
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module rates the decisions made on the offers for match boxes (see
+//! [`crate::constraint::offer_rating`]). The probability of the pair in the box is taken from the
+//! remaining solutions before the box.
+
+use anyhow::{Context, Result};
+
+use crate::constraint::evaluate_predicates::ConstraintEval;
+use crate::constraint::offer_rating::OfferRating;
+use crate::constraint::{Constraint, ConstraintGetters};
+use crate::game::Game;
+use crate::Rem;
+
+/// the prize pot of a season if not specified in the game-config
+const DEFAULT_POT: u128 = 200_000;
+
+impl Game {
+    /// The prize pot at the beginning of the season (as specified in the game-config, 200000 by
+    /// default)
+    pub(super) fn pot(&self) -> u128 {
+        self.pot.as_ref().map_or(DEFAULT_POT, |p| p.initial)
+    }
+
+    /// Rate the decisions on the offers of the `merged` constraints and store the rating in the
+    /// constraints. `rem` is the state before the first constraint.
+    ///
+    /// Only match boxes with a single pair, an offer with a known amount and a known outcome (sold
+    /// or lights) are rated.
+    pub(super) fn rate_offers(&self, merged: &mut [Constraint], mut rem: Rem) -> Result<()> {
+        let required_lights = self
+            .rule_set
+            .constr_map_len(self.lut_a.len(), self.lut_b.len());
+        for c in merged.iter_mut() {
+            let rating = self.rate_offer(c, &rem, required_lights);
+            c.set_offer_rating(rating);
+            rem = c.apply_to_rem(rem).context("Apply to rem failed")?;
+        }
+        Ok(())
+    }
+
+    /// Rate the decision on the offer of `c` given the state `rem` before the constraint
+    fn rate_offer(&self, c: &Constraint, rem: &Rem, required_lights: usize) -> Option<OfferRating> {
+        if !c.is_mb() || rem.1 == 0 {
+            return None;
+        }
+        let amount = c.try_get_offer()?.try_get_amount()?;
+        let sold = c.is_sold();
+        if !sold && !c.is_lights() {
+            return None;
+        }
+
+        let mut pairs = c.matching().iter_pairs();
+        let (a, b) = pairs.next()?;
+        if pairs.next().is_some() {
+            return None;
+        }
+        let prob = *rem.0.get(a as usize)?.get(b as usize)? as f64 / rem.1 as f64;
+        Some(OfferRating::new(
+            prob,
+            amount,
+            self.pot(),
+            required_lights,
+            sold,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use rust_decimal::Decimal;
+
+    use crate::constraint::check_type::CheckType;
+    use crate::constraint::compare::EvalEvent;
+    use crate::constraint::parse::ConstraintParse;
+    use crate::constraint::{ConstraintType, Offer};
+    use crate::game::builder::{events_of, GameBuilder};
+
+    /// a match box with number `num` for `a` and `b` with an offer of `amount` checked via `check`
+    fn offered_box(num: u8, a: &str, b: &str, amount: u128, check: CheckType) -> ConstraintParse {
+        ConstraintParse::new(
+            ConstraintType::Box {
+                num: Decimal::from(num),
+                comment: String::new(),
+                offer: Some(Offer::Group {
+                    amount: Some(amount),
+                    by: a.to_string(),
+                }),
+            },
+            [(a.to_string(), b.to_string())].into_iter().collect(),
+            check,
+        )
+    }
+
+    #[test]
+    fn rate_offers_simple() -> Result<()> {
+        let mut g = GameBuilder::new(["A", "B", "C", "D"], ["a", "b", "c", "d"])
+            .pot(100_000, 0)
+            // a match is worth 25000, each pair has a 25% chance -> opening is worth 6250
+            .constraint(offered_box(1, "A", "a", 10_000, CheckType::Sold))
+            .box_(2, [("B", "b")], 0)
+            // 4 of the 18 solutions left -> opening is worth 5556
+            .constraint(offered_box(
+                3,
+                "C",
+                "c",
+                5_000,
+                CheckType::Lights(0, Default::default()),
+            ))
+            .build()?;
+        let ratings = events_of(&mut g, |e| match e {
+            EvalEvent::MB(e) => Some(e.offer_rating),
+            _ => None,
+        })?;
+        assert_eq!(ratings.len(), 3);
+
+        let r = ratings[0].unwrap();
+        assert_eq!(r.prob, 0.25);
+        assert_eq!(r.open, 6_250.0);
+        assert!(r.sold && r.rational());
+
+        // no offer
+        assert_eq!(ratings[1], None);
+
+        let r = ratings[2].unwrap();
+        assert!((r.prob - 4.0 / 18.0).abs() < 1e-9);
+        assert!(!r.sold && r.rational());
+        Ok(())
+    }
+}
@@ -10,85 +10,244 @@ use std::fs::File;
 use std::path::Path;
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use crate::collector::{CollectorSpec, Registry};
 use crate::constraint::parse::ConstraintParse;
 use crate::game::cache::{CacheMode, CacheModeFallback};
+use crate::game::format::{sorted, sorted_seq, Format};
 use crate::game::parse_utils::{apply_renames, build_luts, process_constraints};
+use crate::game::query_conditional::translate_query_conditional;
 use crate::game::query_matchings::translate_query_matchings;
 use crate::game::query_pairs::translate_query_pairs;
 use crate::game::Game;
 use crate::ignore_ops::IgnoreOps;
 use crate::ruleset::parse::RuleSetParse;
-use crate::{Lut, MatchingS, Rename};
+use crate::{Lut, MapS, MatchingS, Rename};
 
 /// query individuals from set_a/set_b which 1:1 matchings are still possible and how often
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub(super) struct QueryPair {
     /// the individualy from set_a which are querried
-    #[serde(rename = "setA", default)]
+    #[serde(rename = "setA", default, skip_serializing_if = "Vec::is_empty")]
     pub(super) map_a: Vec<String>,
     /// the individualy from set_b which are querried
-    #[serde(rename = "setB", default)]
+    #[serde(rename = "setB", default, skip_serializing_if = "Vec::is_empty")]
     pub(super) map_b: Vec<String>,
 }
 
+/// the prize pot of a season and how it changes over the course of the season
+///
+/// Besides the blackouts the pot is changed by accepted offers: Money of group offers is added to
+/// the pot, money of single offers is taken from the pot if the offer reduces the pot.
+///
+/// In the game-config either the full mapping or only the initial pot (`pot: 200000`) can be
+/// given (see [`PotConfigParse`]).
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "PotConfigParse")]
+pub struct PotConfig {
+    /// the prize pot at the beginning of the season
+    pub initial: u128,
+    /// by how much the pot is reduced with each blackout
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub blackout: u128,
+}
+
+/// the forms in which the prize pot can be given in the game-config (with `u64` as untagged enums
+/// don't support `u128`)
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum PotConfigParse {
+    /// only the prize pot at the beginning of the season
+    Initial(u64),
+    /// the prize pot at the beginning of the season and how it changes
+    Full {
+        /// the prize pot at the beginning of the season
+        initial: u64,
+        /// by how much the pot is reduced with each blackout
+        #[serde(default)]
+        blackout: u64,
+    },
+}
+
+impl From<PotConfigParse> for PotConfig {
+    fn from(p: PotConfigParse) -> Self {
+        match p {
+            PotConfigParse::Initial(initial) => PotConfig {
+                initial: initial.into(),
+                blackout: 0,
+            },
+            PotConfigParse::Full { initial, blackout } => PotConfig {
+                initial: initial.into(),
+                blackout: blackout.into(),
+            },
+        }
+    }
+}
+
+/// used to skip the `blackout` field of [`PotConfig`] when serializing
+fn is_zero(x: &u128) -> bool {
+    *x == 0
+}
+
+impl QueryPair {
+    /// whether nothing is querried
+    fn is_empty(&self) -> bool {
+        self.map_a.is_empty() && self.map_b.is_empty()
+    }
+}
+
 /// Small helper used as a default for the `solved` field during deserialization.
 /// We keep this as a free function so it's easily testable.
 fn mk_true() -> bool {
     true
 }
 
+/// Counterpart of [`mk_true`], used to skip the `keepRemaining` field when serializing
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
 /// this struct is only used for parsing the yaml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
 pub struct GameParse {
     /// whether offers are noted in this game
-    #[serde(default)]
-    no_offerings_noted: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(super) no_offerings_noted: bool,
     /// whether the remaining possible solutions should be stored
-    #[serde(rename = "keepRemaining", default = "mk_true")]
-    keep_rem: bool,
+    #[serde(
+        rename = "keepRemaining",
+        default = "mk_true",
+        skip_serializing_if = "is_true"
+    )]
+    pub(super) keep_rem: bool,
     /// the constraints in this game
     #[serde(rename = "constraints")]
-    constraints_orig: Vec<ConstraintParse>,
+    pub(super) constraints_orig: Vec<ConstraintParse>,
     /// the ruleset which is to be applied to this game
-    rule_set: RuleSetParse,
+    pub(super) rule_set: RuleSetParse,
     /// frontmatter to set in the generated markdown output
-    frontmatter: serde_yaml::Value,
+    #[serde(default, skip_serializing_if = "serde_yaml::Value::is_null")]
+    #[schemars(with = "serde_json::Value")]
+    pub(super) frontmatter: serde_yaml::Value,
     /// query these full matchings and when the were eliminated in the process (if so)
-    #[serde(rename = "queryMatchings", default)]
-    query_matchings_s: Vec<MatchingS>,
+    #[serde(
+        rename = "queryMatchings",
+        default,
+        serialize_with = "sorted_seq",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(super) query_matchings_s: Vec<MatchingS>,
     /// query individuals from set_a/set_b which 1:1 matchings are still possible and how often
-    #[serde(rename = "queryPair", default)]
-    query_pair_s: QueryPair,
+    #[serde(
+        rename = "queryPair",
+        default,
+        skip_serializing_if = "QueryPair::is_empty"
+    )]
+    pub(super) query_pair_s: QueryPair,
+    /// query the probabilities of the 1:1 matchings given that all pairs (set_a -> set_b) of an
+    /// entry are a match
+    #[serde(
+        rename = "queryConditional",
+        default,
+        serialize_with = "sorted_seq",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(super) query_conditional_s: Vec<MapS>,
+    /// user-defined statistics to collect on the possible solutions left (see
+    /// [`crate::collector`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) collectors: Vec<CollectorSpec>,
+    /// how many matching nights the season has (used for the probability to win the game)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) nights: Option<usize>,
+    /// the prize pot of the season and how it changes (used to rate the decisions on offers and
+    /// to follow the prize money over the course of the season)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<PotConfigParse>")]
+    pub(super) pot: Option<PotConfig>,
 
     /// the set of individuals in set_a (also maps idx_a to name_a)
     #[serde(rename = "setA")]
-    map_a: Vec<String>,
+    pub(super) map_a: Vec<String>,
     /// the set of individuals in set_b (also maps idx_b to name_b)
     #[serde(rename = "setB")]
-    map_b: Vec<String>,
+    pub(super) map_b: Vec<String>,
 
     /// rename/translate the names of set_a in the outpus
-    #[serde(rename = "renameA", default)]
-    rename_a: Rename,
+    #[serde(
+        rename = "renameA",
+        default,
+        serialize_with = "sorted",
+        skip_serializing_if = "Rename::is_empty"
+    )]
+    pub(super) rename_a: Rename,
     /// rename/translate the names of set_b in the outpus
-    #[serde(rename = "renameB", default)]
-    rename_b: Rename,
+    #[serde(
+        rename = "renameB",
+        default,
+        serialize_with = "sorted",
+        skip_serializing_if = "Rename::is_empty"
+    )]
+    pub(super) rename_b: Rename,
 
     // TODO:(later) eventually move this to the constraint, maybe keep here as default
     /// whether to generate a cache in the end - might get overwritten by CLI arguments
-    #[serde(rename = "gen_cache", default)]
+    #[serde(
+        rename = "gen_cache",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
     pub gen_cache: bool,
 
     /// whether to use a cache and which - might get overwritten by CLI arguments
-    #[serde(rename = "useCache", default)]
+    #[serde(rename = "useCache", default, skip_serializing_if = "Option::is_none")]
     pub use_cache: Option<CacheMode>,
     /// whether to use a fallback and which if the specified cache was not found - might get overwritten by CLI arguments
-    #[serde(rename = "cacheFallback", default)]
+    #[serde(
+        rename = "cacheFallback",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub cache_fallback: Option<CacheModeFallback>,
 }
 
+/// Additionally accept the content of each variant of an externally tagged enum in `schema` (and
+/// its subschemas) on its own, as this is what is left of a yaml tag (e.g. `!Box {..}`) for an
+/// editor validating the config. Unit variants (e.g. `!Eq`) have no content, i.e. `null`.
+///
+/// As the contents of different variants may overlap, the variants are combined with `anyOf`
+/// instead of `oneOf`.
+fn yaml_tags(schema: &mut schemars::Schema) {
+    schemars::transform::transform_subschemas(&mut yaml_tags, schema);
+    let Some(serde_json::Value::Array(variants)) = schema.remove("oneOf") else {
+        return;
+    };
+    let mut any_of = Vec::with_capacity(2 * variants.len());
+    for v in variants {
+        let content = if v.get("const").is_some_and(|c| c.is_string()) {
+            Some(serde_json::json!({"type": "null"}))
+        } else {
+            // the only (required) property is named after the variant
+            v.get("required")
+                .and_then(|r| r.as_array())
+                .filter(|r| r.len() == 1)
+                .and_then(|r| r[0].as_str())
+                .and_then(|name| v.get("properties")?.get(name))
+                .cloned()
+        };
+        if let Some(mut content) = content {
+            if let (Some(obj), Some(desc)) = (content.as_object_mut(), v.get("description")) {
+                obj.entry("description").or_insert_with(|| desc.clone());
+            }
+            any_of.push(content);
+        }
+        any_of.push(v);
+    }
+    schema.insert("anyOf".to_string(), any_of.into());
+}
+
 impl GameParse {
     /// create a [`GameParse`] from a yaml config. This struct can then be finalized to a [`super::Game`]
     pub fn new_from_yaml(yaml_path: &Path) -> Result<GameParse> {
@@ -96,6 +255,44 @@ impl GameParse {
         Ok(gp)
     }
 
+    /// create a [`GameParse`] from a config in any of the supported formats (see [`Format`]). The
+    /// format is detected based on the extension of `path`.
+    pub fn new_from_file(path: &Path) -> Result<GameParse> {
+        let format = Format::from_path(path)?;
+        let src = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        format
+            .parse(&src)
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Serialize the config to a string in the given `format`. Parsing the result again yields
+    /// the same config.
+    pub fn to_string(&self, format: Format) -> Result<String> {
+        format.serialize(self)
+    }
+
+    /// Generate the JSON schema of the game-config. As it is derived from the parsing types
+    /// themselves, it describes what [`GameParse::new_from_yaml`] accepts.
+    ///
+    /// In json/toml configs a variant of an enum is written as a single-key mapping (e.g.
+    /// `type: {Box: {..}}`), in yaml configs as a tag (e.g. `type: !Box {..}`). Editors validate
+    /// only the content of a tagged node, so the schema accepts the content of each variant on its
+    /// own as well (see [`yaml_tags`]).
+    pub fn json_schema() -> schemars::Schema {
+        let mut settings = schemars::generate::SchemaSettings::default();
+        settings.transforms.push(Box::new(yaml_tags));
+        settings
+            .into_generator()
+            .into_root_schema_for::<GameParse>()
+    }
+
+    /// create a [`GameParse`] from a yaml config which is already read into `src`
+    pub fn new_from_str(src: &str) -> Result<GameParse> {
+        let gp: GameParse = serde_yaml::from_str(src)?;
+        Ok(gp)
+    }
+
     /// Consumes a [`GameParse`] and produces a fully-initialised [`super::Game`].
     ///
     /// The function performs the following ordered steps:
@@ -118,6 +315,18 @@ impl GameParse {
     /// # Returns
     /// A fully-populated [`super::Game`] ready for solving or caching.
     pub fn finalize_parsing(self, stem: &Path, ignore: &IgnoreOps) -> Result<Game> {
+        self.finalize_parsing_with(stem, ignore, &Registry::default())
+    }
+
+    /// Same as [`GameParse::finalize_parsing`] but the collectors enabled in the config are
+    /// looked up in `registry` (e.g. to make collectors available which are not part of this
+    /// crate).
+    pub fn finalize_parsing_with(
+        self,
+        stem: &Path,
+        ignore: &IgnoreOps,
+        registry: &Registry,
+    ) -> Result<Game> {
         let mut g = Game {
             no_offerings_noted: self.no_offerings_noted,
             keep_rem: self.keep_rem,
@@ -138,6 +347,13 @@ impl GameParse {
             lut_b: Lut::default(),
             query_matchings: Vec::default(),
             query_pair: (Default::default(), Default::default()),
+            query_conditional: vec![],
+            collectors: vec![],
+            mem_budget: None,
+            remaining_events: None,
+            rate_decisions: false,
+            nights: self.nights,
+            pot: self.pot,
             frontmatter: self.frontmatter,
             cache_file: None,
             cache_to: None,
@@ -166,9 +382,276 @@ impl GameParse {
         // translate the pairs that were querried for tracing
         g.query_pair = translate_query_pairs(&self.query_pair_s, &g.lut_a, &g.lut_b)?;
 
+        // translate the conditions for the conditional probabilities
+        g.query_conditional = translate_query_conditional(
+            &self.query_conditional_s,
+            &g.lut_a,
+            &g.lut_b,
+            &g.rule_set,
+        )?;
+
+        // create the collectors which were enabled
+        for spec in &self.collectors {
+            let c = registry.create(spec, &g.lut_a, &g.lut_b)?;
+            g.collectors.push(c);
+        }
+
         // rename names in map_a and map_b for output use
         apply_renames(&mut g.map_a, &mut g.map_b, &self.rename_a, &self.rename_b);
 
         Ok(g)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn json_schema_simple() {
+        let schema = GameParse::json_schema().to_value();
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["constraints", "rule_set", "setA", "setB"])
+        );
+        // the renames of serde are used
+        assert!(schema["properties"]["queryPair"].is_object());
+        assert!(schema["$defs"]["ConstraintParse"]["properties"]["treeCfg"].is_object());
+        // the variants of the enums can be given as yaml tags or as single-key mappings
+        let variants = &schema["$defs"]["ConstraintType"]["anyOf"];
+        assert!(variants[2]["properties"]["num"].is_object());
+        assert!(variants[3]["properties"]["Box"].is_object());
+    }
+
+    /// Builds the json value of a yaml document as seen by an editor validating it: the tags are
+    /// dropped and only their content is left (e.g. `!Lights 2` -> `2`)
+    #[derive(Default)]
+    struct Untagged {
+        /// the collections currently built (with the key waiting for its value in mappings)
+        open: Vec<(serde_json::Value, Option<String>)>,
+        /// the finished document
+        doc: serde_json::Value,
+    }
+
+    impl yaml_rust2::parser::EventReceiver for Untagged {
+        fn on_event(&mut self, ev: yaml_rust2::parser::Event) {
+            use yaml_rust2::parser::Event;
+            use yaml_rust2::scanner::TScalarStyle;
+            use yaml_rust2::Yaml;
+            let value = match ev {
+                Event::SequenceStart(..) => {
+                    self.open.push((serde_json::json!([]), None));
+                    return;
+                }
+                Event::MappingStart(..) => {
+                    self.open.push((serde_json::json!({}), None));
+                    return;
+                }
+                Event::SequenceEnd | Event::MappingEnd => self.open.pop().unwrap().0,
+                Event::Scalar(v, TScalarStyle::Plain, ..) => match Yaml::from_str(&v) {
+                    Yaml::Integer(i) => serde_json::json!(i),
+                    Yaml::Real(r) => serde_json::json!(r.parse::<f64>().unwrap()),
+                    Yaml::Boolean(b) => serde_json::json!(b),
+                    Yaml::Null => serde_json::Value::Null,
+                    _ => serde_json::json!(v),
+                },
+                Event::Scalar(v, ..) => serde_json::json!(v),
+                _ => return,
+            };
+            match self.open.last_mut() {
+                None => self.doc = value,
+                Some((serde_json::Value::Array(seq), _)) => seq.push(value),
+                Some((serde_json::Value::Object(map), key)) => match key.take() {
+                    Some(k) => {
+                        map.insert(k, value);
+                    }
+                    None => *key = Some(value.as_str().unwrap().to_string()),
+                },
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn json_schema_all_seasons() {
+        let schema = GameParse::json_schema().to_value();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        for entry in std::fs::read_dir("../data").unwrap() {
+            let dir = entry.unwrap().path();
+            let Some(stem) = dir.file_name() else {
+                continue;
+            };
+            let path = dir.join(stem).with_extension("yaml");
+            if !path.exists() {
+                continue;
+            }
+            let src = std::fs::read_to_string(&path).unwrap();
+            // as seen by an editor (tags are dropped) and as a json config (tags are mappings)
+            let mut yaml = Untagged::default();
+            yaml_rust2::parser::Parser::new_from_str(&src)
+                .load(&mut yaml, false)
+                .unwrap();
+            let json = serde_json::to_value(GameParse::new_from_str(&src).unwrap()).unwrap();
+            for config in [yaml.doc, json] {
+                let errors = validator
+                    .iter_errors(&config)
+                    .map(|e| format!("{} at {}", e, e.instance_path()))
+                    .collect::<Vec<_>>();
+                assert!(errors.is_empty(), "{}: {:#?}", path.display(), errors);
+            }
+        }
+    }
+
+    #[test]
+    fn pot_config_forms() -> Result<()> {
+        let src = |pot: &str| {
+            format!(
+                "rule_set: !Eq\nfrontmatter: {{}}\nsetA: [A]\nsetB: [a]\nconstraints: []\npot: {pot}\n"
+            )
+        };
+        let pot =
+            |src: &str| -> Result<Option<PotConfig>> { Ok(GameParse::new_from_str(src)?.pot) };
+        assert_eq!(
+            pot(&src("200000"))?,
+            Some(PotConfig {
+                initial: 200_000,
+                blackout: 0
+            })
+        );
+        assert_eq!(
+            pot(&src("{initial: 100000, blackout: 20000}"))?,
+            Some(PotConfig {
+                initial: 100_000,
+                blackout: 20_000
+            })
+        );
+        assert_eq!(
+            pot(&src("{initial: 100000}"))?,
+            Some(PotConfig {
+                initial: 100_000,
+                blackout: 0
+            })
+        );
+        assert!(pot(&src("{blackout: 100000}")).is_err());
+
+        let schema = GameParse::json_schema().to_value();
+        assert_eq!(
+            schema["$defs"]["PotConfigParse"]["anyOf"][0]["type"],
+            "integer"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn collectors_from_config() -> Result<()> {
+        let src = r#"
+rule_set: !Eq
+frontmatter: {}
+setA: [A, B]
+setB: [a, b]
+constraints: []
+collectors:
+  - name: dupCount
+  - name: lightsForecast
+    args: {A: a, B: b}
+"#;
+        let g = GameParse::new_from_str(src)?
+            .finalize_parsing(Path::new(".trash"), &IgnoreOps::Nothing)?;
+        assert_eq!(
+            g.collectors.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            vec!["dupCount", "lightsForecast"]
+        );
+
+        let invalid = src.replace("{A: a, B: b}", "{A: x}");
+        assert!(GameParse::new_from_str(&invalid)?
+            .finalize_parsing(Path::new(".trash"), &IgnoreOps::Nothing)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn collectors_from_custom_registry() -> Result<()> {
+        /// a collector which isn't part of the default registry
+        #[derive(Debug, Clone)]
+        struct Custom;
+
+        impl crate::collector::Collector for Custom {
+            fn name(&self) -> &str {
+                "custom"
+            }
+
+            fn push(&mut self, _m: &crate::matching_repr::MaskedMatching) -> Result<()> {
+                Ok(())
+            }
+
+            fn print(
+                &self,
+                _f: &mut dyn std::io::Write,
+                _full: bool,
+                _map_a: &[String],
+                _map_b: &[String],
+                _total: u128,
+            ) -> Result<()> {
+                Ok(())
+            }
+
+            fn to_json(&self, _map_a: &[String], _map_b: &[String]) -> Result<serde_json::Value> {
+                Ok(serde_json::Value::Null)
+            }
+        }
+
+        let src = r#"
+rule_set: !Eq
+frontmatter: {}
+setA: [A, B]
+setB: [a, b]
+constraints: []
+collectors:
+  - name: custom
+"#;
+        // unknown to the default registry
+        assert!(GameParse::new_from_str(src)?
+            .finalize_parsing(Path::new(".trash"), &IgnoreOps::Nothing)
+            .is_err());
+
+        let mut registry = Registry::default();
+        registry.register("custom", |_, _, _| Ok(Box::new(Custom)));
+        let g = GameParse::new_from_str(src)?.finalize_parsing_with(
+            Path::new(".trash"),
+            &IgnoreOps::Nothing,
+            &registry,
+        )?;
+        assert_eq!(
+            g.collectors.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            vec!["custom"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn convert_all_seasons() {
+        for entry in std::fs::read_dir("../data").unwrap() {
+            let dir = entry.unwrap().path();
+            let Some(stem) = dir.file_name() else {
+                continue;
+            };
+            let path = dir.join(stem).with_extension("yaml");
+            if !path.exists() {
+                continue;
+            }
+            let gp = GameParse::new_from_file(&path).unwrap();
+            // json has no stable key order => compare the values instead of the strings
+            let expected = serde_json::to_value(&gp).unwrap();
+            for format in [Format::Json, Format::Toml, Format::Yaml] {
+                let converted = gp
+                    .to_string(format)
+                    .unwrap_or_else(|e| panic!("{}: {:?}", path.display(), e));
+                let back: GameParse = format
+                    .parse(&converted)
+                    .unwrap_or_else(|e| panic!("{}: {:?}\n{}", path.display(), e, converted));
+                assert_eq!(serde_json::to_value(&back).unwrap(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,394 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module gathers statistics per person (the other tables are pair-centric): how often a
+//! person sat in a match box, in how many matching nights they sat with their match (if the
+//! solution is known), how much information on their match the events involving them produced,
+//! after which event their match was certain and how the probability of their match evolved over
+//! the season.
+//!
+//! The information is the decrease of the entropy of the distribution of the match of the person
+//! (their row/column in the tables), only events the person took part in are counted. The
+//! probability tracked over the season is the one of the true match if the solution is known,
+//! otherwise the one of the most likely match (which might be a different person after each
+//! event).
+
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, Table};
+
+use crate::collector::{PagePlot, THEME_DARK, THEME_LIGHT};
+use crate::comparison::plotly::scatter::build_line_plot;
+use crate::constraint::evaluate_predicates::ConstraintEval;
+use crate::constraint::ConstraintGetters;
+use crate::game::report_trail::Trail;
+use crate::game::Game;
+use crate::matching_repr::{IdBase, MaskedMatching};
+use crate::Rem;
+
+/// label of the state before the first event
+const INITIAL: &str = "initial";
+
+/// the set a person belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    /// set_a (rows of the tables)
+    A,
+    /// set_b (columns of the tables)
+    B,
+}
+
+/// statistics of a single person over the course of the season
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct PersonStats {
+    /// the name of the person
+    name: String,
+    /// in how many match boxes the person sat
+    boxes: usize,
+    /// in how many matching nights the person sat with their match (only if the solution is
+    /// known)
+    nights_matched: Option<usize>,
+    /// the information (in bits) on the match of the person produced by the events involving
+    /// them
+    information: f64,
+    /// after which event the match of the person was certain (if it is)
+    certain_after: Option<String>,
+    /// the probability of the true match of the person (the most likely match if the solution is
+    /// unknown) along with their name initially and after each event
+    trajectory: Vec<(f64, String)>,
+}
+
+impl Game {
+    /// Gather the statistics of each person of set_a and set_b based on the `trail`. `solution`
+    /// is the solution of the season if it is known.
+    pub(super) fn person_stats(
+        &self,
+        trail: &Trail,
+        solution: Option<&MaskedMatching>,
+    ) -> (Vec<PersonStats>, Vec<PersonStats>) {
+        let stats = |side: Side, names: &[String]| {
+            names
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| self.stats_of(side, idx as IdBase, name, trail, solution))
+                .collect::<Vec<_>>()
+        };
+        (stats(Side::A, &self.map_a), stats(Side::B, &self.map_b))
+    }
+
+    /// Gather the statistics of the person `idx` of `side`
+    fn stats_of(
+        &self,
+        side: Side,
+        idx: IdBase,
+        name: &str,
+        trail: &Trail,
+        solution: Option<&MaskedMatching>,
+    ) -> PersonStats {
+        let partner = solution.and_then(|sol| self.true_match(side, idx, sol));
+        let point = |rem: &Rem| match partner {
+            Some(p) => self.prob_of(side, idx, p, rem),
+            None => self.most_likely(side, idx, rem),
+        };
+        let certain = |rem: &Rem| self.most_likely(side, idx, rem).0 >= 1.0;
+
+        let mut stats = PersonStats {
+            name: name.to_string(),
+            boxes: 0,
+            nights_matched: solution.map(|_| 0),
+            information: 0.0,
+            certain_after: certain(&trail.0).then(|| INITIAL.to_string()),
+            trajectory: vec![point(&trail.0)],
+        };
+
+        let mut before = &trail.0;
+        for e in &trail.1 {
+            let c = e.constraint;
+            let pairs = c
+                .matching()
+                .iter_pairs()
+                .filter(|&(a, b)| match side {
+                    Side::A => a == idx,
+                    Side::B => b == idx,
+                })
+                .collect::<Vec<_>>();
+            if !pairs.is_empty() {
+                if c.is_mb() {
+                    stats.boxes += 1;
+                }
+                stats.information +=
+                    self.entropy(side, idx, before) - self.entropy(side, idx, &e.rem);
+                if let (Some(sol), Some(n)) = (solution, stats.nights_matched.as_mut()) {
+                    let matched = pairs.iter().any(|&(a, b)| {
+                        sol.slot_mask(a as usize).is_some_and(|m| m.contains_idx(b))
+                    });
+                    if c.is_mn() && matched {
+                        *n += 1;
+                    }
+                }
+            }
+
+            if stats.certain_after.is_none() && certain(&e.rem) {
+                stats.certain_after = Some(c.type_str());
+            }
+            stats.trajectory.push(point(&e.rem));
+            before = &e.rem;
+        }
+        stats
+    }
+
+    /// How often each possible partner is a match of the person `idx` of `side` given `rem`
+    /// (along with the index of the partner)
+    fn match_counts(&self, side: Side, idx: IdBase, rem: &Rem) -> Vec<(usize, u128)> {
+        let idx = idx as usize;
+        let len = match side {
+            Side::A => self.map_b.len(),
+            Side::B => self.map_a.len(),
+        };
+        (0..len)
+            .filter_map(|i| {
+                let (a, b) = match side {
+                    Side::A => (idx, i),
+                    Side::B => (i, idx),
+                };
+                (!self.rule_set.ignore_pairing(a, b)).then(|| (i, rem.0[a][b]))
+            })
+            .collect()
+    }
+
+    /// the name of the partner with the index `partner` of a person of `side`
+    fn partner_name(&self, side: Side, partner: usize) -> String {
+        match side {
+            Side::A => self.map_b[partner].clone(),
+            Side::B => self.map_a[partner].clone(),
+        }
+    }
+
+    /// The probability of the most likely match of the person `idx` of `side` given `rem` along
+    /// with the name of the match
+    fn most_likely(&self, side: Side, idx: IdBase, rem: &Rem) -> (f64, String) {
+        if rem.1 == 0 {
+            return (0.0, String::new());
+        }
+        self.match_counts(side, idx, rem)
+            .into_iter()
+            // prefer the first one in case of a tie
+            .rev()
+            .max_by_key(|(_, c)| *c)
+            .map_or((0.0, String::new()), |(i, c)| {
+                (c as f64 / rem.1 as f64, self.partner_name(side, i))
+            })
+    }
+
+    /// The probability that `partner` is a match of the person `idx` of `side` given `rem` along
+    /// with the name of the partner
+    fn prob_of(&self, side: Side, idx: IdBase, partner: usize, rem: &Rem) -> (f64, String) {
+        let (a, b) = match side {
+            Side::A => (idx as usize, partner),
+            Side::B => (partner, idx as usize),
+        };
+        let prob = if rem.1 == 0 {
+            0.0
+        } else {
+            rem.0[a][b] as f64 / rem.1 as f64
+        };
+        (prob, self.partner_name(side, partner))
+    }
+
+    /// The entropy (in bits) of the distribution of the match of the person `idx` of `side` given
+    /// `rem`
+    fn entropy(&self, side: Side, idx: IdBase, rem: &Rem) -> f64 {
+        let counts = self.match_counts(side, idx, rem);
+        let total = counts.iter().map(|(_, c)| c).sum::<u128>();
+        counts
+            .iter()
+            .filter(|(_, c)| *c > 0)
+            .map(|&(_, c)| {
+                let q = c as f64 / total as f64;
+                -q * q.log2()
+            })
+            .sum()
+    }
+
+    /// The true match of the person `idx` of `side` in the `solution` (the first one if they have
+    /// multiple matches)
+    fn true_match(&self, side: Side, idx: IdBase, solution: &MaskedMatching) -> Option<usize> {
+        match side {
+            Side::A => solution
+                .slot_mask(idx as usize)?
+                .iter()
+                .next()
+                .map(|b| b as usize),
+            Side::B => (0..self.map_a.len())
+                .find(|&a| solution.slot_mask(a).is_some_and(|m| m.contains_idx(idx))),
+        }
+    }
+
+    /// Print the statistics of the persons of both sets to stdout (each table followed by an
+    /// empty line). Returns the amount of tables printed.
+    pub(super) fn print_person_stats(&self, stats: &(Vec<PersonStats>, Vec<PersonStats>)) -> usize {
+        for s in [&stats.0, &stats.1] {
+            println!("{}", person_table(s));
+            println!();
+        }
+        2
+    }
+
+    /// Plot the probability of the match of each person over the season (a plot per person, each
+    /// is embedded in its own collapsed block on the page). `trail` provides the labels of the
+    /// events, `solved` whether the trajectories follow the true match.
+    pub(super) fn person_plots(
+        &self,
+        stats: &(Vec<PersonStats>, Vec<PersonStats>),
+        trail: &Trail,
+        solved: bool,
+    ) -> Vec<PagePlot> {
+        let labels = std::iter::once(INITIAL.to_string())
+            .chain(trail.1.iter().map(|e| e.constraint.type_str()))
+            .collect::<Vec<_>>();
+        stats
+            .0
+            .iter()
+            .chain(&stats.1)
+            .map(|s| {
+                let series = [(
+                    s.name.clone(),
+                    labels.clone(),
+                    s.trajectory.iter().map(|(p, _)| p * 100.0).collect(),
+                    s.trajectory.iter().map(|(_, n)| n.clone()).collect(),
+                )];
+                let title = if solved {
+                    format!("Probability of the match of {}", s.name)
+                } else {
+                    format!(
+                        "Probability of the most likely match of {} (the partner might change)",
+                        s.name
+                    )
+                };
+                let plot = |theme| build_line_plot(&series, theme, &title, "event", "P [%]");
+                PagePlot {
+                    light: plot(THEME_LIGHT),
+                    dark: plot(THEME_DARK),
+                    title,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Build the table showing the statistics of the persons `stats`
+fn person_table(stats: &[PersonStats]) -> Table {
+    let mut table = Table::new();
+    table
+        .force_no_tty()
+        .enforce_styling()
+        .load_preset(UTF8_FULL_CONDENSED)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_header(vec!["", "MB", "MN w/ match", "I [bit]", "certain", "P [%]"]);
+
+    for (i, s) in stats.iter().enumerate() {
+        let style = if i % 2 == 0 {
+            |cell: Cell| cell.bg(crate::COLOR_ALT_BG)
+        } else {
+            |cell: Cell| cell
+        };
+        let (prob, partner) = s.trajectory.last().cloned().unwrap_or_default();
+        table.add_row(
+            [
+                s.name.clone(),
+                s.boxes.to_string(),
+                s.nights_matched.map_or(String::new(), |n| n.to_string()),
+                format!("{:.2}", s.information),
+                s.certain_after.clone().unwrap_or_default(),
+                format!("{:.1} ({partner})", prob * 100.0),
+            ]
+            .into_iter()
+            .map(|x| style(Cell::new(x))),
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use anyhow::Result;
+
+    use crate::game::builder::GameBuilder;
+    use crate::game::eval_utils::merge_constraints;
+    use crate::game::report_trail::gen_report_data;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    #[test]
+    fn person_stats_simple() -> Result<()> {
+        let mut g = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+            .box_(1, [("A", "a")], 1)
+            // A-a is a match => B-c and C-b are no match
+            .night(1, [("A", "a"), ("B", "c"), ("C", "b")], 1)
+            .build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let mut constraints = merge_constraints(&is.constraints)?;
+        let trail = gen_report_data(&mut constraints, (is.each.clone(), is.total), &g.map_a)?;
+        assert_eq!(is.left_poss.len(), 1);
+        let solution = is.left_poss.iter().next().transpose()?;
+        let (a, b) = g.person_stats(&trail, solution.as_ref());
+
+        assert_eq!(a.len(), 3);
+        assert_eq!(b.len(), 3);
+        assert_eq!(a[0].name, "A");
+        assert_eq!(a[0].boxes, 1);
+        assert_eq!(a[0].nights_matched, Some(1));
+        assert_eq!(a[0].certain_after.as_deref(), Some("MB#1"));
+        assert_eq!(
+            a[0].trajectory,
+            vec![
+                (1.0 / 3.0, "a".to_string()),
+                (1.0, "a".to_string()),
+                (1.0, "a".to_string())
+            ]
+        );
+        // the box reveals the match of A, the night tells nothing about A
+        assert!((a[0].information - 3f64.log2()).abs() < 1e-9);
+
+        assert_eq!(a[1].boxes, 0);
+        assert_eq!(a[1].nights_matched, Some(0));
+        assert_eq!(a[1].certain_after.as_deref(), Some("MN#1"));
+        assert_eq!(a[1].trajectory.last(), Some(&(1.0, "b".to_string())));
+        // only the night is counted (B wasn't part of the box)
+        assert!((a[1].information - 1.0).abs() < 1e-9);
+
+        assert_eq!(b[0].boxes, 1);
+        assert!((b[0].information - 3f64.log2()).abs() < 1e-9);
+        assert_eq!(b[2].name, "c");
+        assert_eq!(b[2].trajectory[1].0, 0.5);
+        assert_eq!(b[2].trajectory.last(), Some(&(1.0, "C".to_string())));
+
+        let plots = g.person_plots(&(a, b), &trail, true);
+        // a plot per person of both sets
+        assert_eq!(plots.len(), 6);
+        assert_eq!(plots[0].title, "Probability of the match of A");
+        assert_eq!(plots[5].title, "Probability of the match of c");
+        Ok(())
+    }
+
+    #[test]
+    fn person_stats_unknown_solution() -> Result<()> {
+        let mut g = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+            .box_(1, [("A", "a")], 0)
+            .build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let mut constraints = merge_constraints(&is.constraints)?;
+        let trail = gen_report_data(&mut constraints, (is.each.clone(), is.total), &g.map_a)?;
+        let (a, _) = g.person_stats(&trail, None);
+        assert_eq!(a[0].nights_matched, None);
+        assert_eq!(a[0].certain_after, None);
+        assert_eq!(a[0].trajectory[1], (0.5, "b".to_string()));
+        // the box on A-a tells something about A but nothing about B
+        assert!((a[0].information - (3f64.log2() - 1.0)).abs() < 1e-9);
+        assert_eq!(a[1].information, 0.0);
+        Ok(())
+    }
+}
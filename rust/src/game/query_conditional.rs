@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module contains everything needed (in the game module) to query conditional
+//! probabilities, i.e. how likely each 1:1 matching is *given* that some pairs are a match
+//! (P(X–Y | A–B)). This includes finishing the parsing from the config, generating the report and
+//! the data stored for the comparison.
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Context, Result};
+
+use crate::constraint::compare::ConditionalStats;
+use crate::game::report_trail::MdTable;
+use crate::game::report_utils::print_rem_generic;
+use crate::game::Game;
+use crate::iterstate::QueryConditionalData;
+use crate::matching_repr::IdBase;
+use crate::ruleset::RuleSet;
+use crate::{Lut, Map, MapS};
+
+/// translate the conditions as parsed from config to ids used during simulation and later
+/// reporting
+pub(super) fn translate_query_conditional(
+    src: &[MapS],
+    lut_a: &Lut,
+    lut_b: &Lut,
+    rule_set: &RuleSet,
+) -> Result<Vec<Map>> {
+    let mut out = Vec::with_capacity(src.len());
+    for cond in src {
+        ensure!(!cond.is_empty(), "empty condition in queryConditional");
+        let mut map = Map::new();
+        for (a, b) in cond {
+            let mut a = *lut_a
+                .get(a)
+                .with_context(|| format!("{} not found in lut_a", a))?;
+            let mut b = *lut_b
+                .get(b)
+                .with_context(|| format!("{} not found in lut_b", b))?;
+            // the pair is only stored in one direction (e.g. NToN)
+            if rule_set.ignore_pairing(a, b) {
+                (a, b) = (b, a);
+            }
+            map.insert(a as IdBase, b as IdBase);
+        }
+        out.push(map);
+    }
+    Ok(out)
+}
+
+impl Game {
+    /// The condition `cond` with the names of the individuals, e.g. `A–a, B–b`
+    fn condition_str(&self, cond: &Map) -> String {
+        cond.iter()
+            .map(|(a, b)| (*a as usize, *b as usize))
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(a, b)| format!("{}–{}", self.map_a[a], self.map_b[b]))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Print a table with the conditional probabilities for each condition in `data`.
+    ///
+    /// Each table is a separate block in the output and gets its own markdown table (appended to
+    /// `md_tables`), starting with index `tab_idx`. Returns the next free index.
+    pub(super) fn report_conditional(
+        &self,
+        data: &QueryConditionalData,
+        print_transposed: bool,
+        mut tab_idx: usize,
+        md_tables: &mut Vec<MdTable>,
+    ) -> usize {
+        let (mv, mh) = if print_transposed {
+            (&self.map_b, &self.map_a)
+        } else {
+            (&self.map_a, &self.map_b)
+        };
+        let norm_idx = if print_transposed {
+            |v, h| (h, v)
+        } else {
+            |v, h| (v, h)
+        };
+        let ignore_pairing = |v, h| self.rule_set.ignore_pairing(v, h);
+
+        for (cond, rem) in data {
+            let cond = self.condition_str(cond);
+            if rem.1 == 0 {
+                println!("Given {cond}: no solution left\n");
+            } else {
+                println!("Given {cond}:");
+                println!(
+                    "{}\n",
+                    print_rem_generic(rem, mv, mh, norm_idx, ignore_pairing)
+                );
+            }
+            md_tables.push(MdTable {
+                name: format!("P(·|{cond})"),
+                idx: tab_idx,
+                trees: vec![],
+                detail: true,
+            });
+            tab_idx += 1;
+        }
+        tab_idx
+    }
+
+    /// The conditional probabilities for each condition in `data` as stored for the comparison
+    pub(super) fn conditional_stats(&self, data: &QueryConditionalData) -> Vec<ConditionalStats> {
+        data.iter()
+            .map(|(cond, rem)| ConditionalStats {
+                condition: cond
+                    .iter()
+                    .map(|(a, b)| {
+                        (
+                            self.map_a[*a as usize].clone(),
+                            self.map_b[*b as usize].clone(),
+                        )
+                    })
+                    .collect(),
+                total: rem.1,
+                probs: self
+                    .map_a
+                    .iter()
+                    .enumerate()
+                    .map(|(a, name_a)| {
+                        let row = self
+                            .map_b
+                            .iter()
+                            .enumerate()
+                            .filter(|(b, _)| !self.rule_set.ignore_pairing(a, *b) && rem.1 > 0)
+                            .map(|(b, name_b)| {
+                                let p = rem.0[a][b] as f64 / rem.1 as f64 * 100.0;
+                                (name_b.clone(), p)
+                            })
+                            .collect();
+                        (name_a.clone(), row)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::game::builder::GameBuilder;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+    use crate::ruleset::parse::RuleSetParse;
+
+    fn make_lut(keys: &[&str]) -> Lut {
+        keys.iter()
+            .enumerate()
+            .map(|(i, k)| (k.to_string(), i))
+            .collect()
+    }
+
+    #[test]
+    fn translate_query_conditional_simple() -> Result<()> {
+        let lut_a = make_lut(&["A", "B"]);
+        let lut_b = make_lut(&["a", "b"]);
+        let src = vec![MapS::from([
+            ("A".to_string(), "b".to_string()),
+            ("B".to_string(), "a".to_string()),
+        ])];
+        let out = translate_query_conditional(&src, &lut_a, &lut_b, &RuleSet::Eq)?;
+        assert_eq!(out, vec![Map::from([(0, 1), (1, 0)])]);
+
+        // unknown name
+        let src = vec![MapS::from([("A".to_string(), "x".to_string())])];
+        assert!(translate_query_conditional(&src, &lut_a, &lut_b, &RuleSet::Eq).is_err());
+        // empty condition
+        assert!(translate_query_conditional(&[MapS::new()], &lut_a, &lut_b, &RuleSet::Eq).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn translate_query_conditional_n_to_n() -> Result<()> {
+        // pairs are stored with the larger index as slot
+        let lut = make_lut(&["A", "B", "C", "D"]);
+        let src = vec![MapS::from([("A".to_string(), "C".to_string())])];
+        let out = translate_query_conditional(&src, &lut, &lut, &RuleSet::NToN)?;
+        assert_eq!(out, vec![Map::from([(2, 0)])]);
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_stats_simple() -> Result<()> {
+        let mut g = GameBuilder::new(["A", "B", "C"], ["a", "b", "c"])
+            .box_(1, [("C", "c")], 0)
+            .conditional([("A", "b")])
+            .conditional([("A", "a"), ("B", "b")])
+            .build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let stats = g.conditional_stats(&is.query_conditional);
+
+        // A-b leaves B-c/C-a only (B-a/C-c is eliminated by the box)
+        assert_eq!(
+            stats[0].condition,
+            BTreeMap::from([("A".into(), "b".into())])
+        );
+        assert_eq!(stats[0].total, 1);
+        assert_eq!(stats[0].probs["B"]["c"], 100.0);
+        assert_eq!(stats[0].probs["C"]["a"], 100.0);
+        assert_eq!(stats[0].probs["C"]["c"], 0.0);
+
+        // A-a and B-b requires C-c
+        assert_eq!(stats[1].total, 0);
+        assert!(stats[1].probs["A"].is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_stats_n_to_n() -> Result<()> {
+        let names = ["A", "B", "C", "D"];
+        let mut g = GameBuilder::new(names, names)
+            .rule_set(RuleSetParse::NToN)
+            .conditional([("A", "B")])
+            .build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let stats = g.conditional_stats(&is.query_conditional);
+        assert_eq!(stats[0].total, 1);
+        assert_eq!(stats[0].probs["D"]["C"], 100.0);
+        // only stored in one direction
+        assert!(!stats[0].probs["C"].contains_key("D"));
+        Ok(())
+    }
+
+    #[test]
+    fn condition_str_simple() -> Result<()> {
+        let g = GameBuilder::new(["A", "B"], ["a", "b"]).build()?;
+        assert_eq!(g.condition_str(&Map::from([(1, 0), (0, 1)])), "A–b, B–a");
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module estimates after each event how many further events are needed to solve the season
+//! (see [`crate::solver::remaining`]). The solutions left after each event are taken from the
+//! [`SurvivorTrail`] recorded during the simulation.
+
+use crate::constraint::evaluate_predicates::ConstraintEval;
+use crate::constraint::Constraint;
+use crate::game::eval_utils::merged_indices;
+use crate::game::Game;
+use crate::solver::mb::optimal::OptimalMbOptimizer;
+use crate::solver::mn::entropy_left::EntropyLeftMnOptimizer;
+use crate::solver::remaining::{estimate, RemainingEvents, SurvivorTrail};
+
+/// sampling threshold of the matching night strategy (same as used by the solver)
+pub(super) const MN_SAMPLE_THRESHOLD: usize = 5_000;
+
+/// how many matching nights a season has if not specified in the game-config
+const DEFAULT_NIGHTS: usize = 10;
+
+impl Game {
+    /// How many matching nights the season has (as specified in the game-config, 10 by default)
+    pub(super) fn nights(&self) -> usize {
+        self.nights.unwrap_or(DEFAULT_NIGHTS)
+    }
+
+    /// Estimate the remaining events after each of the `merged` constraints and store them in the
+    /// constraints. `raw` are the constraints before merging (as used in the simulation which
+    /// recorded the `trail`).
+    ///
+    /// The probability to win is based on the matching nights left after each constraint (see
+    /// [`Game::nights`]). Constraints after which the solutions left were not kept (too many or
+    /// covered by the cache used) are skipped.
+    pub(super) fn estimate_remaining_events(
+        &self,
+        merged: &mut [Constraint],
+        raw: &[Constraint],
+        trail: Option<&SurvivorTrail>,
+    ) {
+        let (Some(trail), Some(samples)) = (trail, self.remaining_events) else {
+            return;
+        };
+        let strategy = (
+            &OptimalMbOptimizer,
+            &EntropyLeftMnOptimizer::new(MN_SAMPLE_THRESHOLD),
+        );
+
+        let required_lights = self
+            .rule_set
+            .constr_map_len(self.lut_a.len(), self.lut_b.len());
+        let mut nights_done = 0;
+        let mut won = false;
+        for (c, idx) in merged.iter_mut().zip(merged_indices(raw)) {
+            if c.is_mn() {
+                nights_done += 1;
+            }
+            won |= c.won(required_lights);
+            let Some(left) = trail.left_after(idx) else {
+                continue;
+            };
+            let next_box = c.is_mn();
+            let rem = estimate(
+                &left,
+                self.map_b.len(),
+                strategy,
+                next_box,
+                self.nights().saturating_sub(nights_done),
+                samples,
+            );
+            // the solution was already seated => nothing left to win
+            c.set_remaining_events(rem.map(|r| {
+                if won {
+                    RemainingEvents { win: 1.0, ..r }
+                } else {
+                    r
+                }
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use std::io::Write;
+
+    use anyhow::Result;
+
+    use crate::constraint::compare::EvalEvent;
+    use crate::dump_mode::DumpMode;
+    use crate::game::builder::{box_night, events_of};
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    /// the remaining events estimated for each event of `g` (as stored for the comparison)
+    fn remaining(g: &mut Game) -> Result<Vec<Option<RemainingEvents>>> {
+        events_of(g, |e| match e {
+            EvalEvent::MB(e) => Some(e.remaining),
+            EvalEvent::MN(e) => Some(e.remaining),
+            EvalEvent::Initial(_) => None,
+        })
+    }
+
+    #[test]
+    fn estimate_remaining_events_simple() -> Result<()> {
+        let builder = || box_night().night(2, [("A", "b"), ("B", "a"), ("C", "d"), ("D", "c")], 4);
+
+        // not requested
+        let mut g = builder().build()?;
+        assert_eq!(remaining(&mut g)?, vec![None; 3]);
+
+        let mut g = builder().build()?;
+        g.set_remaining_events(Some(100));
+        let rem = remaining(&mut g)?;
+        // 18 solutions left after the first box, all of them are simulated
+        let first = rem[0].unwrap();
+        assert_eq!(first.sampled, 18);
+        assert!(first.expected >= 1.0 && first.expected <= first.worst as f64);
+        // enough nights left to find every solution
+        assert_eq!(first.win, 1.0);
+        // solved and won with the last event
+        assert_eq!(
+            rem[2],
+            Some(RemainingEvents {
+                expected: 0.0,
+                worst: 0,
+                sampled: 1,
+                win: 1.0,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_remaining_events_nights() -> Result<()> {
+        let mut g = box_night().nights(1).build()?;
+        assert_eq!(g.nights(), 1);
+        g.set_remaining_events(Some(100));
+        let rem = remaining(&mut g)?;
+        // only the single night is left -> won only by luck
+        assert!(rem[0].unwrap().win < 1.0);
+        // no night left
+        assert_eq!(rem[1].unwrap().win, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_remaining_events_cache() -> Result<()> {
+        let mut g = box_night().build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(Some(DumpMode::Full))?;
+
+        // a cache containing the solutions left after the night
+        let mut cache = tempfile::NamedTempFile::new()?;
+        for p in is.left_poss.iter() {
+            serde_json::to_writer(&mut cache, &p?)?;
+            writeln!(cache)?;
+        }
+
+        // the cache is no candidate of the game => it is unknown which events it covers (without
+        // this the box would be estimated based on the solutions left after the night), only the
+        // solutions left in the end are known
+        let mut g = box_night().build()?;
+        g.set_remaining_events(Some(100));
+        g.cache_file = Some(cache.path().to_path_buf());
+        let rem = remaining(&mut g)?;
+        assert_eq!(rem[0], None);
+        assert_eq!(rem[1].unwrap().sampled, 5);
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use comfy_table::{Cell, Table};
 
 use anyhow::Result;
 
-use crate::constraint::report_summary::SummaryRow;
+use crate::constraint::report_summary::{OptionalColumns, SummaryRow};
 use crate::constraint::Constraint;
 use crate::game::Game;
 
@@ -44,6 +44,15 @@ impl Game {
         hdr.push(Cell::new("#new").set_alignment(comfy_table::CellAlignment::Center));
         hdr.push(Cell::new("min dist").set_alignment(comfy_table::CellAlignment::Center));
 
+        // some columns are only shown if they were filled (e.g. the remaining events)
+        let rows = generate_data(merged_constraints, transpose, map_hor);
+        let cols = OptionalColumns::of(&rows);
+        hdr.extend(
+            cols.header()
+                .into_iter()
+                .map(|x| Cell::new(x).set_alignment(comfy_table::CellAlignment::Center)),
+        );
+
         let mut table = Table::new();
         table
             .force_no_tty()
@@ -52,16 +61,13 @@ impl Game {
             .apply_modifier(UTF8_ROUND_CORNERS)
             .set_header(hdr);
 
-        for (i, row) in generate_data(merged_constraints, transpose, map_hor)
-            .iter()
-            .enumerate()
-        {
+        for (i, row) in rows.iter().enumerate() {
             let style = if i % 2 == 0 {
                 |cell: Cell| cell.bg(crate::COLOR_ALT_BG)
             } else {
                 |cell: Cell| cell
             };
-            table.add_row(row.render(style));
+            table.add_row(row.render(style, cols));
         }
         Ok(table)
     }
@@ -11,16 +11,18 @@ use anyhow::{Context, Result};
 use crate::constraint::{report_hdr::ReportData, Constraint};
 use crate::game::report_utils::print_rem_generic;
 use crate::game::Game;
+use crate::iterstate::IterState;
+use crate::progressbar::ProgressBarTrait;
 use crate::Rem;
 
 /// event prepared for reporting
 pub(super) struct ReportEvent<'a> {
     /// the amount of 1:1 matches left after this event
-    rem: Rem,
+    pub(super) rem: Rem,
     /// the report prepared from the constraint
     constr_report: ReportData<'a>,
     /// the constraint on which this reports on
-    constraint: &'a Constraint,
+    pub(super) constraint: &'a Constraint,
 }
 
 /// descibres the trail which is reported later on
@@ -172,3 +174,74 @@ impl Game {
         Ok(tab_idx)
     }
 }
+
+impl Game {
+    /// The remaining amount of solutions (per 1:1 matching and in total) after each of the
+    /// constraints of the simulation `is`. The constraints are not merged, so the result contains
+    /// one entry per constraint in the order of the config.
+    pub fn rem_trail<T: ProgressBarTrait>(
+        &self,
+        is: &IterState<T, Constraint>,
+    ) -> Result<Vec<Rem>> {
+        let mut rem = (is.each.clone(), is.total);
+        let mut rems = Vec::with_capacity(is.constraints.len());
+        for c in &is.constraints {
+            rem = c.clone().apply_to_rem(rem).context("Apply to rem failed")?;
+            rems.push(rem.clone());
+        }
+        Ok(rems)
+    }
+
+    /// The probability (in percent) that `a` from set_a and `b` from set_b are a match according to
+    /// `rem`. The names are the ones used in the config (not the renamed ones).
+    pub fn pair_prob(&self, rem: &Rem, a: &str, b: &str) -> Option<f64> {
+        let (mut a, mut b) = (*self.lut_a.get(a)?, *self.lut_b.get(b)?);
+        if self.rule_set.ignore_pairing(a, b) {
+            (a, b) = (b, a);
+        }
+        let cnt = *rem.0.get(a)?.get(b)?;
+        Some(cnt as f64 / rem.1 as f64 * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    use crate::game::parse::GameParse;
+    use crate::ignore_ops::IgnoreOps;
+    use crate::progressbar::MockProgressBar;
+
+    #[test]
+    fn rem_trail_simple() -> Result<()> {
+        let src = r#"
+rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+constraints:
+  - type: !Box {num: 1, comment: ""}
+    check: !Lights [0]
+    map:
+      A: a
+  - type: !Night {num: 1, comment: ""}
+    check: !Lights [3]
+    map:
+      A: b
+      B: a
+      C: c
+"#;
+        let mut g = GameParse::new_from_str(src)?
+            .finalize_parsing(Path::new(".trash/test"), &IgnoreOps::Nothing)?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let rems = g.rem_trail(&is)?;
+
+        assert_eq!(rems.iter().map(|r| r.1).collect::<Vec<_>>(), vec![4, 1]);
+        assert_eq!(g.pair_prob(&rems[0], "A", "b"), Some(50.0));
+        assert_eq!(g.pair_prob(&rems[1], "A", "b"), Some(100.0));
+        assert_eq!(g.pair_prob(&rems[1], "A", "x"), None);
+        Ok(())
+    }
+}
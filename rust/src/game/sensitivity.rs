@@ -0,0 +1,489 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a sensitivity analysis of the light counts. As the amount of lights is
+//! transcribed from TV, it is worth knowing how robust the results are against a count being off by
+//! one.
+//!
+//! For every constraint checked via lights, the season is evaluated with the count shifted by -1
+//! and +1 (all other constraints stay untouched). This is done in a single pass over all
+//! permutations: a matching survives a variant iff the shifted constraint is the *only* one
+//! eliminating it and the shifted constraint admits it.
+//!
+//! Note: caches are never used here, since they already lack the matchings which were eliminated by
+//! the constraints that shall be shifted.
+
+use std::fmt;
+
+use anyhow::Result;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, Color, Table};
+use indicatif::ProgressStyle;
+
+use crate::constraint::{Constraint, ConstraintGetters};
+use crate::game::Game;
+use crate::iterstate::IterStateTrait;
+use crate::matching_repr::MaskedMatching;
+use crate::progressbar::ProgressBarTrait;
+use crate::ruleset::RuleSet;
+use crate::LightCnt;
+
+/// the shifts applied to the amount of lights of every constraint
+const SHIFTS: [i16; 2] = [-1, 1];
+
+/// the stats collected for one variant of the season
+#[derive(Debug)]
+struct Variant {
+    /// the constraint with the shifted amount of lights
+    constraint: Constraint,
+    /// the amount of solutions left with this variant
+    survivors: u128,
+    /// how often each 1:1 matching was observed in the solutions left with this variant
+    each: Vec<Vec<u128>>,
+}
+
+/// A consumer of the emitted matchings which collects the stats of the unmodified season as well
+/// as of all the variants with shifted light counts.
+struct SensitivityState<T: ProgressBarTrait> {
+    /// the constraints of the season (unmodified)
+    constraints: Vec<Constraint>,
+    /// the variants of each constraint (same index as `constraints`)
+    variants: Vec<Vec<Variant>>,
+    /// the amount of solutions left in the unmodified season
+    survivors: u128,
+    /// how often each 1:1 matching was observed in the solutions left of the unmodified season
+    each: Vec<Vec<u128>>,
+
+    // progressbar related
+    /// after how many permutations to step/update the progressbar
+    cnt_update: usize,
+    /// the progressbar for displaying progress
+    progress: T,
+}
+
+impl<T: ProgressBarTrait> SensitivityState<T> {
+    /// Create a new [`SensitivityState`] with the variants derived from `constraints`.
+    ///
+    /// - `perm_amount`: total number of permutations expected (for showing progress).
+    /// - `map_lens`: the lengths of the two maps/sets (`set_a` and `set_b`)
+    fn new(constraints: Vec<Constraint>, perm_amount: usize, map_lens: (usize, usize)) -> Self {
+        let variants = constraints
+            .iter()
+            .map(|c| {
+                SHIFTS
+                    .iter()
+                    .filter_map(|d| c.with_shifted_lights(*d))
+                    .map(|constraint| Variant {
+                        constraint,
+                        survivors: 0,
+                        each: vec![vec![0; map_lens.1]; map_lens.0],
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let s = Self {
+            constraints,
+            variants,
+            survivors: 0,
+            each: vec![vec![0; map_lens.1]; map_lens.0],
+            cnt_update: std::cmp::max(perm_amount / 50, 1),
+            progress: T::new(100),
+        };
+        s.progress.set_style(
+            ProgressStyle::with_template(
+                "[{elapsed_precise}] [{wide_bar}] {pos:>3}/{len:3} (ETA: {eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+        );
+        s
+    }
+}
+
+impl<T: ProgressBarTrait> IterStateTrait for SensitivityState<T> {
+    fn start(&mut self) {
+        self.progress.inc(0)
+    }
+
+    fn finish(&mut self) {
+        self.progress.finish()
+    }
+
+    fn step(&mut self, i: usize, p: &MaskedMatching) -> Result<()> {
+        if i.is_multiple_of(self.cnt_update) {
+            self.progress.inc(2);
+        }
+
+        // search for the constraint(s) eliminating `p`. As soon as two constraints eliminate `p`
+        // shifting one of them cannot make `p` a solution anymore.
+        let mut failed = None;
+        for (idx, c) in self.constraints.iter_mut().enumerate() {
+            if !c.admits(p) {
+                if failed.is_some() {
+                    return Ok(());
+                }
+                failed = Some(idx);
+            }
+        }
+
+        match failed {
+            None => {
+                // the variants can't admit `p` as well, the amount of lights differs
+                self.survivors += 1;
+                p.count_pairs(&mut self.each);
+            }
+            Some(idx) => {
+                for v in &mut self.variants[idx] {
+                    if v.constraint.admits(p) {
+                        v.survivors += 1;
+                        p.count_pairs(&mut v.each);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// how certain it is that a pair is a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Certainty {
+    /// the pair is a match in all solutions left
+    Match,
+    /// the pair is no match in all solutions left
+    NoMatch,
+    /// the pair is still open
+    Open,
+}
+
+impl Certainty {
+    /// derive the certainty from how often the pair occurs (`cnt`) in all solutions left (`total`)
+    fn from_cnt(cnt: u128, total: u128) -> Self {
+        if cnt == 0 {
+            Certainty::NoMatch
+        } else if cnt == total {
+            Certainty::Match
+        } else {
+            Certainty::Open
+        }
+    }
+}
+
+impl fmt::Display for Certainty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Certainty::Match => write!(f, "match"),
+            Certainty::NoMatch => write!(f, "no match"),
+            Certainty::Open => write!(f, "open"),
+        }
+    }
+}
+
+/// a pair whose certainty changes with a variant of the season
+#[derive(Debug, PartialEq)]
+struct PairChange {
+    /// name of the individual from set_a
+    a: String,
+    /// name of the individual from set_b
+    b: String,
+    /// certainty in the unmodified season
+    before: Certainty,
+    /// certainty in the variant of the season
+    after: Certainty,
+}
+
+impl fmt::Display for PairChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}+{}: {} -> {}",
+            self.a, self.b, self.before, self.after
+        )
+    }
+}
+
+/// one row of the sensitivity report, represents one variant of the season
+#[derive(Debug, PartialEq)]
+struct SensitivityRow {
+    /// label of the event which was shifted
+    event: String,
+    /// the amount of lights which was recorded
+    recorded: LightCnt,
+    /// the amount of lights in this variant
+    shifted: LightCnt,
+    /// the amount of solutions left in this variant
+    survivors: u128,
+    /// pairs which change their certainty in this variant
+    changed: Vec<PairChange>,
+}
+
+/// represents the report immediately before displaying it
+#[derive(Debug, PartialEq)]
+struct SensitivityReport {
+    /// the amount of solutions left in the unmodified season
+    survivors: u128,
+    /// one row for every variant of the season
+    rows: Vec<SensitivityRow>,
+}
+
+impl SensitivityReport {
+    /// evaluate the stats collected in `state` and produce a report which can be displayed later
+    fn new<T: ProgressBarTrait>(
+        state: &SensitivityState<T>,
+        rule_set: &RuleSet,
+        map_a: &[String],
+        map_b: &[String],
+    ) -> Self {
+        let certainties = |each: &[Vec<u128>], total: u128| {
+            each.iter()
+                .enumerate()
+                .flat_map(|(a, bs)| {
+                    bs.iter()
+                        .enumerate()
+                        .filter(move |(b, _)| !rule_set.ignore_pairing(a, *b))
+                        .map(move |(b, cnt)| ((a, b), Certainty::from_cnt(*cnt, total)))
+                })
+                .collect::<Vec<_>>()
+        };
+        let base = certainties(&state.each, state.survivors);
+
+        let rows = state
+            .constraints
+            .iter()
+            .zip(&state.variants)
+            .flat_map(|(c, vs)| vs.iter().map(move |v| (c, v)))
+            .map(|(c, v)| {
+                // an inconsistent variant has no certainties which could be compared
+                let changed = if v.survivors == 0 {
+                    vec![]
+                } else {
+                    base.iter()
+                        .zip(certainties(&v.each, v.survivors))
+                        .filter(|((_, before), (_, after))| before != after)
+                        .map(|(((a, b), before), (_, after))| PairChange {
+                            a: map_a.get(*a).cloned().unwrap_or_default(),
+                            b: map_b.get(*b).cloned().unwrap_or_default(),
+                            before: *before,
+                            after,
+                        })
+                        .collect()
+                };
+                SensitivityRow {
+                    event: format!("{} {}", c.type_str(), c.comment()),
+                    recorded: c.check.as_lights().unwrap_or_default(),
+                    shifted: v.constraint.check.as_lights().unwrap_or_default(),
+                    survivors: v.survivors,
+                    changed,
+                }
+            })
+            .collect();
+
+        Self {
+            survivors: state.survivors,
+            rows,
+        }
+    }
+}
+
+impl fmt::Display for SensitivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new();
+        table
+            .force_no_tty()
+            .enforce_styling()
+            .load_preset(UTF8_FULL_CONDENSED)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec!["", "L", "shifted", "consistent", "#left", "changed"]);
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let style = if i % 2 == 0 {
+                |cell: Cell| cell.bg(crate::COLOR_ALT_BG)
+            } else {
+                |cell: Cell| cell
+            };
+            let consistent = if row.survivors == 0 {
+                // strong evidence the recorded amount of lights is correct
+                Cell::new("no").fg(Color::Green)
+            } else {
+                // conclusions drawn from this event are fragile
+                Cell::new("yes").fg(Color::Yellow)
+            };
+            table.add_row(
+                vec![
+                    Cell::new(&row.event),
+                    Cell::new(row.recorded),
+                    Cell::new(row.shifted),
+                    consistent,
+                    Cell::new(row.survivors),
+                    Cell::new(
+                        row.changed
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                ]
+                .into_iter()
+                .map(style)
+                .collect::<Vec<_>>(),
+            );
+        }
+        writeln!(f, "{table}")?;
+        write!(f, "{} left without shifting any lights", self.survivors)
+    }
+}
+
+impl Game {
+    /// Run the sensitivity analysis of the light counts and print the report.
+    ///
+    /// For every constraint checked via lights, the season is evaluated with the amount of lights
+    /// shifted by -1 and +1. The report tells whether the season becomes inconsistent, how many
+    /// solutions are left and which pairs change their certainty.
+    pub fn sensitivity<T: ProgressBarTrait>(&self) -> Result<()> {
+        let perm_amount =
+            self.rule_set
+                .get_perms_amount(self.map_a.len(), self.map_b.len(), &None)?;
+
+        let mut state: SensitivityState<T> = SensitivityState::new(
+            self.constraints_orig.clone(),
+            perm_amount,
+            (self.map_a.len(), self.map_b.len()),
+        );
+        self.rule_set
+            .iter_perms(&self.lut_a, &self.lut_b, &mut state, &None)?;
+
+        let report = SensitivityReport::new(&state, &self.rule_set, &self.map_a, &self.map_b);
+        println!("{report}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rust_decimal::dec;
+
+    use crate::constraint::check_type::CheckType;
+    use crate::constraint::ConstraintType;
+    use crate::progressbar::MockProgressBar;
+    use crate::ruleset_data::dummy::DummyData;
+    use crate::Lut;
+
+    fn lut(n: usize) -> Lut {
+        (0..n).map(|i| (i.to_string(), i)).collect()
+    }
+
+    fn names(n: usize) -> Vec<String> {
+        (0..n).map(|i| i.to_string()).collect()
+    }
+
+    fn night(lights: LightCnt) -> Constraint {
+        Constraint::new_with_defaults(
+            ConstraintType::Night {
+                num: dec![1],
+                comment: "c".to_string(),
+                offer: None,
+            },
+            CheckType::Lights(lights, Default::default()),
+            MaskedMatching::from_matching_ref(&[vec![0], vec![1], vec![2]]),
+            Box::new(DummyData::default()),
+            3,
+            3,
+            0,
+        )
+    }
+
+    #[test]
+    fn step_collects_variants() -> Result<()> {
+        let mut state: SensitivityState<MockProgressBar> =
+            SensitivityState::new(vec![night(1)], 6, (3, 3));
+        RuleSet::Eq.iter_perms(&lut(3), &lut(3), &mut state, &None)?;
+
+        // 3 lights: 1 permutation, 1 light: 3 permutations, 0 lights: 2 permutations
+        assert_eq!(state.survivors, 3);
+        assert_eq!(
+            state.variants[0]
+                .iter()
+                .map(|v| (v.constraint.check.as_lights(), v.survivors))
+                .collect::<Vec<_>>(),
+            vec![(Some(0), 2), (Some(2), 0)]
+        );
+        assert_eq!(state.variants[0][0].each[0], vec![0, 1, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn step_multiple_failures() -> Result<()> {
+        // the second constraint eliminates everything -> no variant of the first one survives
+        let mut state: SensitivityState<MockProgressBar> =
+            SensitivityState::new(vec![night(1), night(2)], 6, (3, 3));
+        RuleSet::Eq.iter_perms(&lut(3), &lut(3), &mut state, &None)?;
+
+        assert_eq!(state.survivors, 0);
+        assert_eq!(
+            state.variants[0]
+                .iter()
+                .map(|v| v.survivors)
+                .collect::<Vec<_>>(),
+            vec![0, 0]
+        );
+        // shifting the second constraint to 1 light yields the solutions of the first one only
+        assert_eq!(
+            state.variants[1]
+                .iter()
+                .map(|v| (v.constraint.check.as_lights(), v.survivors))
+                .collect::<Vec<_>>(),
+            vec![(Some(1), 3), (Some(3), 0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn report_simple() -> Result<()> {
+        let mut state: SensitivityState<MockProgressBar> =
+            SensitivityState::new(vec![night(3)], 6, (3, 3));
+        RuleSet::Eq.iter_perms(&lut(3), &lut(3), &mut state, &None)?;
+
+        let report = SensitivityReport::new(&state, &RuleSet::Eq, &names(3), &names(3));
+        assert_eq!(report.survivors, 1);
+        assert_eq!(report.rows.len(), 1);
+
+        let row = &report.rows[0];
+        assert_eq!(row.event, "MN#1 c");
+        assert_eq!((row.recorded, row.shifted, row.survivors), (3, 2, 0));
+        // inconsistent -> nothing to compare
+        assert_eq!(row.changed, vec![]);
+
+        let mut state: SensitivityState<MockProgressBar> =
+            SensitivityState::new(vec![night(1)], 6, (3, 3));
+        RuleSet::Eq.iter_perms(&lut(3), &lut(3), &mut state, &None)?;
+        let report = SensitivityReport::new(&state, &RuleSet::Eq, &names(3), &names(3));
+
+        // with 0 lights the pairs of the night are certainly no match anymore
+        let row = &report.rows[0];
+        assert_eq!((row.recorded, row.shifted, row.survivors), (1, 0, 2));
+        assert_eq!(
+            row.changed,
+            (0..3)
+                .map(|i| PairChange {
+                    a: i.to_string(),
+                    b: i.to_string(),
+                    before: Certainty::Open,
+                    after: Certainty::NoMatch,
+                })
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn certainty_from_cnt_simple() {
+        assert_eq!(Certainty::from_cnt(0, 5), Certainty::NoMatch);
+        assert_eq!(Certainty::from_cnt(5, 5), Certainty::Match);
+        assert_eq!(Certainty::from_cnt(3, 5), Certainty::Open);
+    }
+}
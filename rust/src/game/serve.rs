@@ -0,0 +1,451 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a small local HTTP/JSON API to the simulation results of the seasons in
+//! the data directory. This allows other tools (e.g. a web frontend) to use the results without
+//! parsing the generated reports.
+//!
+//! Endpoints:
+//! - `GET /seasons` lists the seasons
+//! - `GET /seasons/<season>/events` the probability matrix after each event
+//! - `GET /seasons/<season>/solutions?offset=<n>&limit=<n>` the remaining solutions (paginated)
+//! - `POST /seasons/<season>/whatif` the probability matrix if the constraint in the body (json,
+//!   same structure as in the game-config) would be added
+//! - `GET /seasons/<season>/comparison` the data used when comparing the seasons
+//!
+//! A season is simulated (starting from the most recent cache if available) when it is requested
+//! for the first time. The results are kept in memory afterwards.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::constraint::parse::ConstraintParse;
+use crate::constraint::{Constraint, ConstraintGetters};
+use crate::game::cache::{CacheMode, CacheSpec};
+use crate::game::parse::GameParse;
+use crate::game::Game;
+use crate::ignore_ops::IgnoreOps;
+use crate::iterstate::IterState;
+use crate::progressbar::{ProgressBar, ProgressBarTrait};
+use crate::Rem;
+
+/// the amount of solutions returned if no limit is requested
+const DEFAULT_LIMIT: usize = 100;
+/// the maximum amount of solutions returned at once
+const MAX_LIMIT: usize = 1000;
+
+/// An error which is answered with the HTTP status code `.0`
+#[derive(Debug)]
+struct ApiError(u16, anyhow::Error);
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError(500, e)
+    }
+}
+
+/// the resource requested does not exist
+fn not_found(what: String) -> ApiError {
+    ApiError(404, anyhow!("{} not found", what))
+}
+
+/// the request is malformed
+fn bad_request(e: anyhow::Error) -> ApiError {
+    ApiError(400, e)
+}
+
+/// A season which was already simulated
+struct Season<T: ProgressBarTrait> {
+    /// where the game-config is stored
+    path: PathBuf,
+    /// the game built from the game-config
+    game: Game,
+    /// the result of the simulation (including the remaining solutions)
+    is: IterState<T, Constraint>,
+}
+
+impl<T: ProgressBarTrait> Season<T> {
+    /// Load the game-config at `path` and simulate it
+    fn new(path: PathBuf) -> Result<Self> {
+        let mut game =
+            GameParse::new_from_file(&path)?.finalize_parsing(&path, &IgnoreOps::Nothing)?;
+        let cs: Vec<CacheSpec> = game.get_cache_candidates();
+        // simulating from scratch is fine as well, it just takes longer
+        let _ = game.select_cache(&cs, CacheMode::MostRecent, &None, true);
+        game.keep_rem = true;
+        let is = game.sim(None)?;
+        Ok(Season { path, game, is })
+    }
+
+    /// The probabilities (in percent) of each pair of set_a and set_b according to `rem`
+    fn probs(&self, rem: &Rem) -> Vec<Vec<f64>> {
+        let (each, total) = rem;
+        (0..self.game.map_a.len())
+            .map(|a| {
+                (0..self.game.map_b.len())
+                    .map(|b| {
+                        let (a, b) = if self.game.rule_set.ignore_pairing(a, b) {
+                            (b, a)
+                        } else {
+                            (a, b)
+                        };
+                        let cnt = each.get(a).and_then(|r| r.get(b)).copied().unwrap_or(0);
+                        cnt as f64 / *total as f64 * 100.0
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The state described by `rem` serialized as json
+    fn state(&self, label: &str, comment: &str, rem: &Rem) -> Value {
+        json!({
+            "label": label,
+            "comment": comment,
+            "total": rem.1,
+            "probs": self.probs(rem),
+        })
+    }
+
+    /// The initial state and the state after each event
+    fn events(&self) -> Result<Value> {
+        let initial = (self.is.each.clone(), self.is.total);
+        let mut events = vec![self.state("initial", "", &initial)];
+        for (c, rem) in self
+            .is
+            .constraints
+            .iter()
+            .zip(self.game.rem_trail(&self.is)?)
+        {
+            events.push(self.state(&c.type_str(), c.comment(), &rem));
+        }
+        Ok(json!({
+            "setA": self.game.map_a,
+            "setB": self.game.map_b,
+            "events": events,
+        }))
+    }
+
+    /// The remaining solutions starting from `offset`, at most `limit` many
+    fn solutions(&self, offset: usize, limit: usize) -> Result<Value> {
+        let solutions = self
+            .is
+            .left_poss
+            .iter_from(offset)
+            .take(limit)
+            .map(|m| {
+                let pairs = m?.prepare_debug_print_names(&self.game.map_a, &self.game.map_b)?;
+                Ok(pairs.into_iter().collect::<HashMap<_, _>>())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(json!({
+            "total": self.is.left_poss.len(),
+            "offset": offset,
+            "limit": limit,
+            "solutions": solutions,
+        }))
+    }
+
+    /// The state if `constraint` would be added to the game-config
+    fn what_if(&self, constraint: ConstraintParse) -> Result<Value, ApiError> {
+        let mut gp = GameParse::new_from_file(&self.path)?;
+        gp.constraints_orig.push(constraint);
+        let game = gp
+            .finalize_parsing(&self.path, &IgnoreOps::Nothing)
+            .map_err(bad_request)?;
+        let mut c = game
+            .constraints_orig
+            .last()
+            .context("the constraint was not added")?
+            .clone();
+
+        let mut each = vec![vec![0; self.game.map_b.len()]; self.game.map_a.len()];
+        let mut left = 0;
+        for m in self.is.left_poss.iter() {
+            let m = m?;
+            if c.admits(&m) {
+                m.count_pairs(&mut each);
+                left += 1;
+            }
+        }
+        Ok(self.state(&c.type_str(), c.comment(), &(each, left)))
+    }
+}
+
+/// The state of the API, the seasons are simulated lazily
+pub struct Api<T: ProgressBarTrait> {
+    /// the directory containing the seasons (one directory per season)
+    data: PathBuf,
+    /// the seasons which were already simulated
+    seasons: HashMap<String, Season<T>>,
+}
+
+impl<T: ProgressBarTrait> Api<T> {
+    /// Create the API for the seasons stored in `data`
+    pub fn new(data: &Path) -> Self {
+        Api {
+            data: data.to_path_buf(),
+            seasons: HashMap::new(),
+        }
+    }
+
+    /// The names of the seasons in the data directory. A season is a directory `<name>` containing
+    /// the game-config `<name>.yaml`.
+    fn season_names(&self) -> Result<Vec<String>> {
+        let mut names = fs::read_dir(&self.data)
+            .with_context(|| format!("failed to read {}", self.data.display()))?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|n| self.data.join(n).join(n).with_extension("yaml").is_file())
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Get the season `name`, simulate it if this did not happen yet
+    fn season(&mut self, name: &str) -> Result<&Season<T>, ApiError> {
+        if !self.seasons.contains_key(name) {
+            if !self.season_names()?.iter().any(|n| n == name) {
+                return Err(not_found(format!("season '{}'", name)));
+            }
+            let path = self.data.join(name).join(name).with_extension("yaml");
+            let season = Season::new(path)?;
+            self.seasons.insert(name.to_string(), season);
+        }
+        self.seasons
+            .get(name)
+            .ok_or_else(|| not_found(format!("season '{}'", name)))
+    }
+
+    /// Answer the request for `url` with the `body` sent
+    fn handle(&mut self, method: &Method, url: &str, body: &str) -> Result<Value, ApiError> {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let query = query
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .collect::<HashMap<_, _>>();
+        // parse a numeric query parameter
+        let param = |key: &str, default: usize| -> Result<usize, ApiError> {
+            query.get(key).map_or(Ok(default), |v| {
+                v.parse()
+                    .with_context(|| format!("invalid {} '{}'", key, v))
+                    .map_err(bad_request)
+            })
+        };
+
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        match (method, segments.as_slice()) {
+            (Method::Get, ["seasons"]) => Ok(json!(self.season_names()?)),
+            (Method::Get, ["seasons", name, "events"]) => Ok(self.season(name)?.events()?),
+            (Method::Get, ["seasons", name, "solutions"]) => {
+                let offset = param("offset", 0)?;
+                let limit = param("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT);
+                Ok(self.season(name)?.solutions(offset, limit)?)
+            }
+            (Method::Post, ["seasons", name, "whatif"]) => {
+                let constraint = serde_json::from_str(body)
+                    .context("invalid constraint")
+                    .map_err(bad_request)?;
+                self.season(name)?.what_if(constraint)
+            }
+            (Method::Get, ["seasons", name, "comparison"]) => {
+                let season = self.season(name)?;
+                let data = season.game.comparison_data(&season.is)?;
+                Ok(serde_json::to_value(data).context("serializing failed")?)
+            }
+            _ => Err(not_found(format!("{} {}", method, path))),
+        }
+    }
+
+    /// Answer `req` with json
+    fn respond(&mut self, mut req: Request) -> Result<()> {
+        let mut body = String::new();
+        let res = match req.as_reader().read_to_string(&mut body) {
+            Ok(_) => self.handle(req.method(), req.url(), &body),
+            Err(e) => Err(bad_request(e.into())),
+        };
+        let (status, value) = match res {
+            Ok(v) => (200, v),
+            Err(ApiError(status, e)) => (status, json!({"error": format!("{:#}", e)})),
+        };
+        let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .map_err(|_| anyhow!("invalid header"))?;
+        req.respond(
+            Response::from_string(value.to_string())
+                .with_status_code(status)
+                .with_header(header),
+        )?;
+        Ok(())
+    }
+
+    /// Answer the requests to `server` until it is shut down
+    fn run(&mut self, server: &Server) {
+        for req in server.incoming_requests() {
+            if let Err(e) = self.respond(req) {
+                eprintln!("error: {:#}", e);
+            }
+        }
+    }
+}
+
+/// Serve the API for the seasons in `data` on `addr` (e.g. `127.0.0.1:8080`)
+pub fn serve(data: &Path, addr: &str) -> Result<()> {
+    let server = Server::http(addr).map_err(|e| anyhow!("failed to listen on {}: {}", addr, e))?;
+    println!("Listening on http://{}", server.server_addr());
+    Api::<ProgressBar>::new(data).run(&server);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use crate::progressbar::MockProgressBar;
+
+    const SRC: &str = "rule_set: !Eq
+frontmatter: {}
+setA: [A, B, C]
+setB: [a, b, c]
+constraints:
+  - type: !Box {num: 1, comment: \"E01\"}
+    check: !Lights [0]
+    map:
+      A: a
+";
+
+    /// a data directory containing the season `s01`
+    fn data_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("s01")).unwrap();
+        fs::write(dir.path().join("s01/s01.yaml"), SRC).unwrap();
+        // not a season
+        fs::create_dir(dir.path().join("other")).unwrap();
+        dir
+    }
+
+    /// send a request to `api` and return the status and the json answered
+    fn req(api: &mut Api<MockProgressBar>, method: Method, url: &str, body: &str) -> (u16, Value) {
+        match api.handle(&method, url, body) {
+            Ok(v) => (200, v),
+            Err(ApiError(status, e)) => (status, json!(e.to_string())),
+        }
+    }
+
+    #[test]
+    fn api_seasons() {
+        let dir = data_dir();
+        let mut api = Api::new(dir.path());
+        assert_eq!(
+            req(&mut api, Method::Get, "/seasons", ""),
+            (200, json!(["s01"]))
+        );
+        assert_eq!(
+            req(&mut api, Method::Get, "/seasons/other/events", "").0,
+            404
+        );
+        assert_eq!(req(&mut api, Method::Get, "/seasons/s01/x", "").0, 404);
+        assert_eq!(
+            req(&mut api, Method::Post, "/seasons/s01/events", "").0,
+            404
+        );
+    }
+
+    #[test]
+    fn api_events() {
+        let dir = data_dir();
+        let mut api = Api::new(dir.path());
+        let (status, v) = req(&mut api, Method::Get, "/seasons/s01/events", "");
+        assert_eq!(status, 200);
+        assert_eq!(v["setA"], json!(["A", "B", "C"]));
+        assert_eq!(v["events"][0]["total"], json!(6));
+        assert_eq!(v["events"][1]["label"], json!("MB#1"));
+        assert_eq!(v["events"][1]["total"], json!(4));
+        assert_eq!(v["events"][1]["probs"][0], json!([0.0, 50.0, 50.0]));
+    }
+
+    #[test]
+    fn api_solutions() {
+        let dir = data_dir();
+        let mut api = Api::new(dir.path());
+        let (status, v) = req(
+            &mut api,
+            Method::Get,
+            "/seasons/s01/solutions?offset=1&limit=2",
+            "",
+        );
+        assert_eq!(status, 200);
+        assert_eq!(v["total"], json!(4));
+        assert_eq!(v["solutions"].as_array().unwrap().len(), 2);
+        assert_eq!(v["solutions"][0].as_object().unwrap().len(), 3);
+
+        let (_, v) = req(&mut api, Method::Get, "/seasons/s01/solutions?offset=3", "");
+        assert_eq!(v["solutions"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            req(&mut api, Method::Get, "/seasons/s01/solutions?limit=x", "").0,
+            400
+        );
+    }
+
+    #[test]
+    fn api_what_if() {
+        let dir = data_dir();
+        let mut api = Api::new(dir.path());
+        let body = r#"{"type": {"Box": {"num": 2, "comment": ""}}, "check": {"Lights": [1]}, "map": {"B": "b"}}"#;
+        let (status, v) = req(&mut api, Method::Post, "/seasons/s01/whatif", body);
+        assert_eq!(status, 200);
+        assert_eq!(v["label"], json!("MB#2"));
+        assert_eq!(v["total"], json!(1));
+
+        assert_eq!(
+            req(&mut api, Method::Post, "/seasons/s01/whatif", "{}").0,
+            400
+        );
+        let body = body.replace("\"B\"", "\"X\"");
+        assert_eq!(
+            req(&mut api, Method::Post, "/seasons/s01/whatif", &body).0,
+            400
+        );
+    }
+
+    #[test]
+    fn api_comparison() {
+        let dir = data_dir();
+        let mut api = Api::new(dir.path());
+        let (status, v) = req(&mut api, Method::Get, "/seasons/s01/comparison", "");
+        assert_eq!(status, 200);
+        // the initial state and the box
+        assert_eq!(v["events"].as_array().unwrap().len(), 2);
+        assert_eq!(v["events"][1]["type"], json!("MB"));
+    }
+
+    #[test]
+    fn serve_localhost() -> Result<()> {
+        let dir = data_dir();
+        let server = Server::http("127.0.0.1:0").map_err(|e| anyhow!(e))?;
+        let addr = server.server_addr().to_ip().context("no ip address")?;
+        let data = dir.path().to_path_buf();
+        std::thread::spawn(move || Api::<MockProgressBar>::new(&data).run(&server));
+
+        let mut stream = TcpStream::connect(addr)?;
+        stream
+            .write_all(b"GET /seasons HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")?;
+        let mut res = String::new();
+        stream.read_to_string(&mut res)?;
+        assert!(res.starts_with("HTTP/1.1 200"));
+        assert!(res.contains("Content-Type: application/json"));
+        assert!(res.ends_with("[\"s01\"]"));
+        Ok(())
+    }
+}
@@ -15,10 +15,15 @@ use anyhow::Result;
 use indicatif::ProgressStyle;
 use serde_json::to_writer;
 
+use crate::collector::Collector;
 use crate::constraint::{ConstraintGetters, ConstraintSim};
+use crate::matching_repr::rank::RankCodec;
 use crate::matching_repr::IdBase;
 use crate::matching_repr::{bitset::Bitset, MaskedMatching};
 use crate::progressbar::ProgressBarTrait;
+use crate::solution_store::SolutionStore;
+use crate::solver::remaining::SurvivorTrail;
+use crate::{Map, Rem};
 
 /// A type to query which individual matches to who else and how often
 ///
@@ -31,6 +36,14 @@ pub(super) type QueryPairData = (
     HashMap<IdBase, HashMap<IdBase, u64>>,
 );
 
+/// A type to query the 1:1 matchings in the remaining solutions which fulfill a condition
+///
+/// For each condition:
+/// - `.0` the pairs (set_a -> set_b) a remaining solution must contain to be counted
+/// - `.1` how often each 1:1 matching was observed in the remaining solutions fulfilling the
+///   condition (and how many of these solutions there are)
+pub(super) type QueryConditionalData = Vec<(Map, Rem)>;
+
 /// Trait describing a consumer of emitted matchings during iteration.
 ///
 /// Implementers receive lifecycle calls (`start`, `finish`) and `step` calls for
@@ -72,13 +85,19 @@ pub struct IterState<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters>
     pub total: u128,
     /// the amount of possible solutions left (`left_poss.len()`)
     pub survivors: u128,
-    /// all possible solutions left
-    pub left_poss: Vec<MaskedMatching>,
+    /// all possible solutions left (held in memory as long as the budget allows to)
+    pub left_poss: SolutionStore,
     /// allows to query when a Matching was eliminated (by which "comment")
     pub query_matchings: Vec<(MaskedMatching, Option<String>)>,
     /// allows to query in which combinations and how often an individual is matched
     #[allow(clippy::type_complexity)]
     pub query_pair: QueryPairData,
+    /// allows to query the 1:1 matchings in the remaining solutions given some pairs are a match
+    pub query_conditional: QueryConditionalData,
+    /// user-defined statistics collected on the possible solutions left
+    pub collectors: Vec<Box<dyn Collector>>,
+    /// keeps the solutions left after the last constraints (if set)
+    pub survivor_trail: Option<SurvivorTrail>,
 
     // progressbar related
     /// after how many permutations to step/update the progressbar
@@ -88,6 +107,8 @@ pub struct IterState<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters>
 
     /// whether and if so, where to write the cache with all the possible solutions left
     cache_file: Option<BufWriter<File>>,
+    /// encodes the solutions written to the cache compactly
+    codec: RankCodec,
 }
 
 impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> PartialEq for IterState<T, S> {
@@ -103,6 +124,8 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> PartialEq for It
             && self.left_poss == other.left_poss
             && self.query_matchings == other.query_matchings
             && self.query_pair == other.query_pair
+            && self.query_conditional == other.query_conditional
+            && self.survivor_trail == other.survivor_trail
             && self.cnt_update == other.cnt_update
     }
 }
@@ -118,9 +141,13 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> Default for Iter
             left_poss: Default::default(),
             query_matchings: Default::default(),
             query_pair: Default::default(),
+            query_conditional: Default::default(),
+            collectors: Default::default(),
+            survivor_trail: Default::default(),
             cnt_update: Default::default(),
             progress: T::new(100),
             cache_file: Default::default(),
+            codec: Default::default(),
         }
     }
 }
@@ -153,18 +180,31 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> IterStateTrait f
         // permutation still works?
         if left {
             self.step_collect_query_pair(p);
+            self.step_collect_query_conditional(p);
+            for c in &mut self.collectors {
+                c.push(p)?;
+            }
+            if let Some(t) = &mut self.survivor_trail {
+                t.push(self.constraints.len(), p);
+            }
 
-            // write permutation to cache file
+            // write permutation to cache file (as its rank if possible, this is much more
+            // compact)
             if let Some(fs) = &mut self.cache_file {
-                to_writer(&mut *fs, p)?;
-                writeln!(fs)?;
+                match self.codec.encode(p) {
+                    Some(code) => writeln!(fs, "{code}")?,
+                    None => {
+                        to_writer(&mut *fs, p)?;
+                        writeln!(fs)?;
+                    }
+                }
             }
 
             self.survivors += 1;
 
             // store the permutation as still possible solution
             if self.keep_rem {
-                self.left_poss.push(p.clone());
+                self.left_poss.push(p)?;
             }
         }
         Ok(())
@@ -214,10 +254,14 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> IterState<T, S>
             each: vec![vec![0; map_lens.1]; map_lens.0],
             total: 0,
             survivors: 0,
-            left_poss: vec![],
+            left_poss: Default::default(),
+            query_conditional: vec![],
+            collectors: vec![],
+            survivor_trail: None,
             progress: T::new(100),
             cnt_update: std::cmp::max(perm_amount / 50, 1),
             cache_file: file,
+            codec: RankCodec::new(map_lens.0, map_lens.1),
         };
         is.progress.set_style(
             ProgressStyle::with_template(
@@ -235,13 +279,7 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> IterState<T, S>
         // - necessary to be able to work with caching
         // - important to generate the "base-table" from which to calculate how much a constraint
         //   has filtered out / is left after (in percentage)
-        for (k, v) in p.iter_pairs() {
-            if let Some(x) = self.each.get_mut(k as usize) {
-                if let Some(x_val) = x.get_mut(v as usize) {
-                    *x_val += 1;
-                }
-            }
-        }
+        p.count_pairs(&mut self.each);
         // aggregate to check the (mathematically) calculated total permutations count
         self.total += 1;
     }
@@ -251,8 +289,11 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> IterState<T, S>
     /// Returns `Ok(true)` if the permutation survives all constraints, or `Ok(false)`
     /// if eliminated by any constraint.
     fn step_process(&mut self, p: &MaskedMatching) -> Result<bool> {
-        for c in &mut self.constraints {
+        for (idx, c) in self.constraints.iter_mut().enumerate() {
             if !c.process(p)? {
+                if let Some(t) = &mut self.survivor_trail {
+                    t.push(idx, p);
+                }
                 // check if this permutation was queried.
                 // If so store by which constraint it was eliminated
                 for (q, id) in &mut self.query_matchings {
@@ -288,6 +329,22 @@ impl<T: ProgressBarTrait, S: ConstraintSim + ConstraintGetters> IterState<T, S>
             }
         }
     }
+
+    /// Update the conditional query statistics for permutation `p`
+    ///
+    /// For each condition `p` fulfills, the 1:1 matchings of `p` are counted.
+    fn step_collect_query_conditional(&mut self, p: &MaskedMatching) {
+        for (cond, rem) in &mut self.query_conditional {
+            let fulfilled = cond
+                .iter()
+                .all(|(a, b)| p.slot_mask(*a as usize).is_some_and(|m| m.contains_idx(*b)));
+            if !fulfilled {
+                continue;
+            }
+            p.count_pairs(&mut rem.0);
+            rem.1 += 1;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -405,7 +462,7 @@ mod tests {
         let mut state: IterState<MockProgressBar, MockConstraint> = IterState {
             keep_rem: false,
             survivors: 0,
-            left_poss: Vec::new(),
+            left_poss: Default::default(),
             constraints: vec![], // ensures permutation survives
             ..Default::default()
         };
@@ -414,12 +471,12 @@ mod tests {
         state.step(1, &sample_matching())?;
 
         assert_eq!(state.survivors, 2);
-        assert_eq!(state.left_poss, vec![]);
+        assert!(state.left_poss.is_empty());
 
         let mut state: IterState<MockProgressBar, MockConstraint> = IterState {
             keep_rem: true,
             survivors: 0,
-            left_poss: Vec::new(),
+            left_poss: Default::default(),
             constraints: vec![], // ensures permutation survives
             ..Default::default()
         };
@@ -428,7 +485,10 @@ mod tests {
         state.step(1, &sample_matching())?;
 
         assert_eq!(state.survivors, 2);
-        assert_eq!(state.left_poss, vec![sample_matching(), sample_matching(),]);
+        assert_eq!(
+            state.left_poss.to_vec()?,
+            vec![sample_matching(), sample_matching(),]
+        );
 
         Ok(())
     }
@@ -5,17 +5,23 @@
 //! This is the crate's root.
 //! It also defines some widely used type aliases and constants.
 
+pub mod collector;
 pub mod comparison;
 pub mod constraint;
 pub mod dump_mode;
 pub mod game;
 pub mod ignore_ops;
 pub mod iterstate;
+pub mod lint;
 pub mod matching_repr;
 pub mod progressbar;
 pub mod ruleset;
 pub mod ruleset_data;
+pub mod solution_store;
+pub mod solver;
+pub mod survivors;
 pub mod tree;
+pub mod watch;
 
 use std::collections::HashMap;
 
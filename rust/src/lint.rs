@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a linter for game-configs. Instead of stopping at the first problem (as
+//! [`crate::game::parse::GameParse::finalize_parsing`] does), all problems which can be found are
+//! collected as [`Diagnostic`]s which point to the location in the yaml file.
+//!
+//! The checks themselves are implemented next to the data they check (see `game/lint.rs` and
+//! `constraint/lint.rs`).
+
+pub mod spans;
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::game::format::Format;
+use crate::game::parse::GameParse;
+use crate::ignore_ops::IgnoreOps;
+use crate::lint::spans::SpanIndex;
+
+/// How severe a finding of the linter is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// the game-config can be used, but most likely does not do what was intended
+    Warning,
+    /// the game-config can't be used
+    Error,
+}
+
+/// A location in the yaml file (both 1-indexed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Position {
+    /// the line
+    pub line: usize,
+    /// the column
+    pub column: usize,
+}
+
+/// A single finding of the linter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// how severe the finding is
+    pub severity: Severity,
+    /// description of the problem
+    pub message: String,
+    /// where the problem is located (if known)
+    pub position: Option<Position>,
+    /// a name which was probably meant instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a new error
+    pub fn error(message: String, position: Option<Position>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            position,
+            suggestion: None,
+        }
+    }
+
+    /// Create a new warning
+    pub fn warning(message: String, position: Option<Position>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message,
+            position,
+            suggestion: None,
+        }
+    }
+
+    /// Attach a suggestion to the diagnostic
+    pub fn with_suggestion(mut self, suggestion: Option<String>) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(p) = self.position {
+            write!(f, "{}:{}: ", p.line, p.column)?;
+        }
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}", severity, self.message)?;
+        if let Some(s) = &self.suggestion {
+            write!(f, " (did you mean '{}'?)", s)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the name in `candidates` which is most similar to `name`. Only returns a candidate if it
+/// is similar enough to be a plausible typo.
+pub(crate) fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a String>,
+) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|c| (strsim::jaro_winkler(name, c), c))
+        .filter(|(score, _)| *score > 0.8)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, c)| c.clone())
+}
+
+/// Lint the game-config given as yaml string `src`.
+///
+/// The returned diagnostics are sorted by their position in the file.
+pub fn lint_str(src: &str) -> Vec<Diagnostic> {
+    let (spans, mut diags) = match SpanIndex::new(src) {
+        Ok(x) => x,
+        // not even valid yaml => there is nothing more to check
+        Err(d) => return vec![d],
+    };
+
+    match serde_yaml::from_str::<GameParse>(src) {
+        Ok(gp) => lint_parsed(gp, &spans, &mut diags),
+        Err(e) => diags.push(Diagnostic::error(
+            e.to_string(),
+            e.location().map(|l| Position {
+                line: l.line(),
+                column: l.column(),
+            }),
+        )),
+    }
+
+    diags.sort_by_key(|d| d.position);
+    diags
+}
+
+/// Run the checks on the already parsed game-config `gp`. The findings are added to `diags`.
+fn lint_parsed(gp: GameParse, spans: &SpanIndex, diags: &mut Vec<Diagnostic>) {
+    diags.extend(gp.lint(spans));
+
+    // only if the linter didn't find anything severe, try to actually build the game
+    // this way problems not covered by the linter are reported as well
+    if !diags.iter().any(|d| d.severity == Severity::Error) {
+        if let Err(e) = gp.finalize_parsing(Path::new(".trash/lint"), &IgnoreOps::Nothing) {
+            diags.push(Diagnostic::error(format!("{:#}", e), None));
+        }
+    }
+}
+
+/// Lint the game-config stored at `path`. The format is detected based on the extension, only for
+/// yaml files the positions of the findings are known.
+pub fn lint_file(path: &Path) -> Result<Vec<Diagnostic>> {
+    let src = std::fs::read_to_string(path)?;
+    let format = Format::from_path(path)?;
+    if format == Format::Yaml {
+        return Ok(lint_str(&src));
+    }
+
+    let mut diags = vec![];
+    match format.parse::<GameParse>(&src) {
+        Ok(gp) => lint_parsed(gp, &SpanIndex::default(), &mut diags),
+        Err(e) => diags.push(Diagnostic::error(format!("{:#}", e), None)),
+    }
+    Ok(diags)
+}
+
+/// How the findings of the linter are printed
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum LintFormat {
+    /// one finding per line, prefixed with the location
+    #[default]
+    Human,
+    /// all findings as a json array
+    Json,
+}
+
+/// Print the `diags` found in the file at `path` in the given `format`.
+pub fn print_diagnostics(path: &Path, diags: &[Diagnostic], format: LintFormat) -> Result<()> {
+    match format {
+        LintFormat::Human => {
+            for d in diags {
+                println!("{}:{}", path.display(), d);
+            }
+            let errors = diags
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            println!("{} error(s), {} warning(s)", errors, diags.len() - errors);
+        }
+        LintFormat::Json => println!("{}", serde_json::to_string_pretty(diags)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn did_you_mean_simple() {
+        let names = ["Caro".to_string(), "Carina".to_string(), "Max".to_string()];
+        assert_eq!(did_you_mean("Carro", &names), Some("Caro".to_string()));
+        assert_eq!(did_you_mean("Xaver", &names), None);
+    }
+
+    #[test]
+    fn display_simple() {
+        let d = Diagnostic::warning(
+            "unknown name 'Carro'".to_string(),
+            Some(Position { line: 3, column: 5 }),
+        )
+        .with_suggestion(Some("Caro".to_string()));
+        assert_eq!(
+            d.to_string(),
+            "3:5: warning: unknown name 'Carro' (did you mean 'Caro'?)"
+        );
+    }
+
+    #[test]
+    fn lint_str_syntax_error() {
+        let diags = lint_str("setA: [A, B\n");
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn lint_str_serde_error() {
+        let diags = lint_str("setA:\n  - A\nsetB: 5\n");
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert!(diags[0].position.is_some());
+    }
+}
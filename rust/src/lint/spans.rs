@@ -0,0 +1,348 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module records where the nodes of a yaml document are located. serde does not retain any
+//! positions once the document was deserialized, so the document is scanned a second time with an
+//! event based parser to be able to point the user to the right line/column.
+
+use std::collections::HashMap;
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+use crate::lint::{Diagnostic, Position};
+
+/// One step of the path which leads from the root of the document to a node
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSeg {
+    /// a key of a mapping
+    Key(String),
+    /// an index of a sequence
+    Idx(usize),
+}
+
+/// Convenience macro to build a path (array of [`PathSeg`]) from keys (strings) and indices
+/// (usize)
+#[macro_export]
+macro_rules! yaml_path {
+    ($($seg:expr),* $(,)?) => {
+        [$($crate::lint::spans::PathSeg::from($seg)),*]
+    };
+}
+
+impl From<&str> for PathSeg {
+    fn from(value: &str) -> Self {
+        PathSeg::Key(value.to_string())
+    }
+}
+
+impl From<&String> for PathSeg {
+    fn from(value: &String) -> Self {
+        PathSeg::Key(value.clone())
+    }
+}
+
+impl From<usize> for PathSeg {
+    fn from(value: usize) -> Self {
+        PathSeg::Idx(value)
+    }
+}
+
+/// Where a node is located in the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// position of the key in case the node is the value of a mapping
+    pub key: Option<Position>,
+    /// position of the node itself
+    pub value: Position,
+}
+
+/// Index of the positions of all nodes in a yaml document
+#[derive(Debug, Default)]
+pub struct SpanIndex {
+    /// path of the node -> where it is located
+    spans: HashMap<Vec<PathSeg>, Span>,
+    /// path of a mapping/sequence -> where it ends
+    ends: HashMap<Vec<PathSeg>, Position>,
+}
+
+impl SpanIndex {
+    /// Scan `src` and build the index.
+    ///
+    /// Keys which occur multiple times in the same mapping are reported as errors in the returned
+    /// diagnostics. If the document is no valid yaml at all, the error is returned instead.
+    pub fn new(src: &str) -> Result<(SpanIndex, Vec<Diagnostic>), Diagnostic> {
+        let mut recv = SpanCollector::default();
+        let mut parser = Parser::new_from_str(src);
+        parser.load(&mut recv, false).map_err(|e| {
+            Diagnostic::error(
+                format!("invalid yaml: {}", e.info()),
+                Some(Position::from(*e.marker())),
+            )
+        })?;
+        Ok((
+            SpanIndex {
+                spans: recv.spans,
+                ends: recv.ends,
+            },
+            recv.diagnostics,
+        ))
+    }
+
+    /// Get the span of the node at `path`
+    pub fn get(&self, path: &[PathSeg]) -> Option<&Span> {
+        self.spans.get(path)
+    }
+
+    /// Position of the node at `path` itself (if it exists)
+    pub fn value(&self, path: &[PathSeg]) -> Option<Position> {
+        self.get(path).map(|s| s.value)
+    }
+
+    /// Position of the key of the node at `path`. Falls back to the position of the node itself
+    /// if the node is no value of a mapping.
+    pub fn key(&self, path: &[PathSeg]) -> Option<Position> {
+        self.get(path).map(|s| s.key.unwrap_or(s.value))
+    }
+
+    /// Position where the mapping/sequence at `path` ends. For block collections this is the
+    /// start of whatever follows the collection.
+    pub fn end(&self, path: &[PathSeg]) -> Option<Position> {
+        self.ends.get(path).copied()
+    }
+
+    /// The last line in which a node below `path` (or the node itself) starts
+    pub fn last_line(&self, path: &[PathSeg]) -> Option<usize> {
+        self.spans
+            .iter()
+            .filter(|(p, _)| p.starts_with(path))
+            .map(|(_, s)| s.value.line.max(s.key.map_or(0, |k| k.line)))
+            .max()
+    }
+
+    /// The most specific node which starts (with its key or itself) in `line`
+    pub fn path_at(&self, line: usize) -> Option<&[PathSeg]> {
+        self.spans
+            .iter()
+            .filter(|(_, s)| s.value.line == line || s.key.is_some_and(|k| k.line == line))
+            .map(|(p, _)| p.as_slice())
+            .max_by_key(|p| p.len())
+    }
+}
+
+impl From<Marker> for Position {
+    fn from(value: Marker) -> Self {
+        Position {
+            line: value.line(),
+            column: value.col() + 1,
+        }
+    }
+}
+
+/// The containers which are currently open while walking through the events
+#[derive(Debug)]
+enum Frame {
+    /// an open mapping
+    Map {
+        /// path of the mapping itself
+        path: Vec<PathSeg>,
+        /// the current key and where it is located (`None` while waiting for the next key)
+        key: Option<(String, Marker)>,
+        /// keys which were already seen in this mapping with their value (if it was a scalar)
+        seen: HashMap<String, Option<String>>,
+    },
+    /// an open sequence
+    Seq {
+        /// path of the sequence itself
+        path: Vec<PathSeg>,
+        /// index of the next element
+        idx: usize,
+    },
+}
+
+/// Receives the events of the parser and records the positions of the nodes
+#[derive(Debug, Default)]
+struct SpanCollector {
+    /// the containers which are currently open
+    stack: Vec<Frame>,
+    /// the positions collected so far
+    spans: HashMap<Vec<PathSeg>, Span>,
+    /// where the mappings/sequences end
+    ends: HashMap<Vec<PathSeg>, Position>,
+    /// problems found while collecting (e.g. duplicate keys)
+    diagnostics: Vec<Diagnostic>,
+    /// depth of complex (non-scalar) keys currently being skipped
+    skip: usize,
+}
+
+impl SpanCollector {
+    /// Handle the start of a new node. Returns the path of the node if it is a value (as opposed
+    /// to being the key of a mapping).
+    fn node(&mut self, scalar: Option<&str>, mark: Marker) -> Option<Vec<PathSeg>> {
+        match self.stack.last_mut() {
+            None => Some(vec![]),
+            Some(Frame::Seq { path, idx }) => {
+                let mut p = path.clone();
+                p.push(PathSeg::Idx(*idx));
+                *idx += 1;
+                self.spans.insert(
+                    p.clone(),
+                    Span {
+                        key: None,
+                        value: mark.into(),
+                    },
+                );
+                Some(p)
+            }
+            Some(Frame::Map { path, key, seen }) => match key.take() {
+                None => {
+                    *key = Some((scalar.unwrap_or_default().to_string(), mark));
+                    None
+                }
+                Some((k, key_mark)) => {
+                    // serde silently keeps the last value of duplicate keys. This is only harmless
+                    // if both values are the same.
+                    let value = scalar.map(|s| s.to_string());
+                    if let Some(prev) = seen.insert(k.clone(), value.clone()) {
+                        let msg = format!("duplicate key '{k}'");
+                        self.diagnostics.push(if prev.is_some() && prev == value {
+                            Diagnostic::warning(
+                                msg + " (with the same value)",
+                                Some(key_mark.into()),
+                            )
+                        } else {
+                            Diagnostic::error(msg, Some(key_mark.into()))
+                        });
+                    }
+                    let mut p = path.clone();
+                    p.push(PathSeg::Key(k));
+                    self.spans.insert(
+                        p.clone(),
+                        Span {
+                            key: Some(key_mark.into()),
+                            value: mark.into(),
+                        },
+                    );
+                    Some(p)
+                }
+            },
+        }
+    }
+}
+
+impl MarkedEventReceiver for SpanCollector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::MappingStart(..) | Event::SequenceStart(..) if self.skip > 0 => self.skip += 1,
+            Event::MappingEnd | Event::SequenceEnd if self.skip > 0 => self.skip -= 1,
+            _ if self.skip > 0 => {}
+            Event::Scalar(s, ..) => {
+                self.node(Some(&s), mark);
+            }
+            Event::Alias(_) => {
+                self.node(None, mark);
+            }
+            Event::MappingStart(..) | Event::SequenceStart(..) => match self.node(None, mark) {
+                Some(path) => self.stack.push(if matches!(ev, Event::MappingStart(..)) {
+                    Frame::Map {
+                        path,
+                        key: None,
+                        seen: HashMap::new(),
+                    }
+                } else {
+                    Frame::Seq { path, idx: 0 }
+                }),
+                // complex keys are not supported by the game-config => just skip them
+                None => self.skip += 1,
+            },
+            Event::MappingEnd | Event::SequenceEnd => {
+                if let Some(Frame::Map { path, .. } | Frame::Seq { path, .. }) = self.stack.pop() {
+                    self.ends.insert(path, mark.into());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SRC: &str = "setA:
+  - A
+  - B
+constraints:
+  - type: !Night {num: 1, comment: \"\"}
+    map:
+      A: b
+      B: a
+";
+
+    #[test]
+    fn new_positions() {
+        let (spans, diags) = SpanIndex::new(SRC).unwrap();
+        assert!(diags.is_empty());
+
+        assert_eq!(
+            spans.value(&yaml_path!["setA", 1]),
+            Some(Position { line: 3, column: 5 })
+        );
+        assert_eq!(
+            spans.key(&yaml_path!["constraints", 0, "map", "B"]),
+            Some(Position { line: 8, column: 7 })
+        );
+        assert_eq!(
+            spans.value(&yaml_path!["constraints", 0, "map", "B"]),
+            Some(Position {
+                line: 8,
+                column: 10
+            })
+        );
+        assert_eq!(
+            spans.value(&yaml_path!["constraints", 0, "type", "num"]),
+            Some(Position {
+                line: 5,
+                column: 24
+            })
+        );
+        assert!(spans.get(&yaml_path!["constraints", 1]).is_none());
+        assert_eq!(
+            spans.end(&yaml_path!["constraints"]),
+            Some(Position { line: 9, column: 1 })
+        );
+        assert_eq!(
+            spans.path_at(7),
+            Some(&yaml_path!["constraints", 0, "map", "A"][..])
+        );
+        assert_eq!(spans.path_at(4), Some(&yaml_path!["constraints"][..]));
+        assert_eq!(spans.last_line(&yaml_path!["constraints", 0]), Some(8));
+        assert_eq!(spans.last_line(&yaml_path!["constraints", 1]), None);
+    }
+
+    #[test]
+    fn new_duplicate_key() {
+        let (_, diags) = SpanIndex::new("map:\n  A: b\n  A: c\n  B: b\n  B: b\n").unwrap();
+        assert_eq!(
+            diags,
+            vec![
+                Diagnostic::error(
+                    "duplicate key 'A'".to_string(),
+                    Some(Position { line: 3, column: 3 })
+                ),
+                Diagnostic::warning(
+                    "duplicate key 'B' (with the same value)".to_string(),
+                    Some(Position { line: 5, column: 3 })
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn new_invalid_yaml() {
+        let d = SpanIndex::new("map: [a, b\n").unwrap_err();
+        assert_eq!(d.severity, crate::lint::Severity::Error);
+    }
+}
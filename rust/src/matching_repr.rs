@@ -7,6 +7,7 @@
 pub mod bitset;
 mod conversions;
 mod iter;
+pub mod rank;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -4,6 +4,8 @@
 
 //! This module allows to iterate in various different ways over a MaskedMatching.
 
+use std::ops::AddAssign;
+
 use smallvec::SmallVec;
 
 use crate::matching_repr::bitset::{BitIter, Bitset};
@@ -26,6 +28,16 @@ impl MaskedMatching {
         }
     }
 
+    /// Count the 1:1 matchings contained in this matching in the table `each` (indexed by
+    /// `[slot][value]`). Pairs outside of the table are ignored.
+    pub fn count_pairs<T: AddAssign + From<u8>>(&self, each: &mut [Vec<T>]) {
+        for (k, v) in self.iter_pairs() {
+            if let Some(x) = each.get_mut(k as usize).and_then(|x| x.get_mut(v as usize)) {
+                *x += T::from(1);
+            }
+        }
+    }
+
     /// Helper function for producing an iterator for the unwrapped matchings
     ///
     /// Builds an bitset-iterator for every slot
@@ -226,6 +238,15 @@ mod tests {
         assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 0)]);
     }
 
+    #[test]
+    fn count_pairs_table() {
+        let mut each = vec![vec![0u128; 3]; 2];
+        MaskedMatching::from_matching_ref(&[vec![1, 2], vec![0]]).count_pairs(&mut each);
+        // pairs outside of the table are skipped
+        MaskedMatching::from_matching_ref(&[vec![1], vec![0], vec![0, 5]]).count_pairs(&mut each);
+        assert_eq!(each, vec![vec![0, 2, 1], vec![2, 0, 0]]);
+    }
+
     #[test]
     fn iter_unwrapped_cartesian_product() {
         let mm = MaskedMatching::from_matching_ref(&[vec![0, 1], vec![2, 3]]);
@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements compact encodings of a [`MaskedMatching`] as a single integer.
+//!
+//! - For matchings which are permutations (like all matchings of [`crate::ruleset::RuleSet::Eq`])
+//!   the *Lehmer rank* is used. For 10 individuals this fits into 22 bits.
+//! - All other matchings (e.g. with dups/trips) are encoded by their *assignment rank*: for each
+//!   individual of `set_b` the slot it is assigned to (or none) is stored as a digit of a
+//!   mixed-radix number.
+//!
+//! The [`RankCodec`] combines both encodings, the lowest bit of the code tells which one was used.
+
+use anyhow::{bail, ensure, Result};
+use smallvec::SmallVec;
+
+use crate::matching_repr::bitset::Bitset;
+use crate::matching_repr::{IdBase, MaskedMatching, MATCH_MAX_LEN};
+
+/// the maximal amount of slots a permutation may have to be ranked (20! < 2^63)
+const LEHMER_MAX_LEN: usize = 20;
+
+/// `n!` (only valid for `n <= LEHMER_MAX_LEN`)
+fn factorial(n: usize) -> u64 {
+    (1..=n as u64).product()
+}
+
+impl MaskedMatching {
+    /// The Lehmer rank of this matching if it is a permutation of `0..self.len()` (every slot
+    /// contains exactly one distinct element). Returns `None` otherwise or if the matching has more
+    /// than 20 slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ayto::matching_repr::MaskedMatching;
+    /// let m = MaskedMatching::from_matching_ref(&[vec![1], vec![0], vec![2]]);
+    /// assert_eq!(m.lehmer_rank(), Some(2));
+    /// assert_eq!(MaskedMatching::from_lehmer_rank(2, 3).unwrap(), m);
+    /// ```
+    pub fn lehmer_rank(&self) -> Option<u64> {
+        let n = self.len();
+        if n > LEHMER_MAX_LEN {
+            return None;
+        }
+        let mut used = Bitset::empty();
+        let mut rank = 0;
+        for (i, b) in self.iter().enumerate() {
+            let v = b.single_idx()?;
+            if v as usize >= n || used.contains_idx(v) {
+                return None;
+            }
+            // amount of elements which are smaller than v and still available
+            let smaller_used = Bitset::from_word(used.as_word() & ((1 << v) - 1)).count();
+            let digit = v as usize - smaller_used;
+            rank += digit as u64 * factorial(n - 1 - i);
+            used.insert(v);
+        }
+        Some(rank)
+    }
+
+    /// Reconstruct the permutation with `len` slots from its Lehmer `rank` (see
+    /// [`MaskedMatching::lehmer_rank`])
+    pub fn from_lehmer_rank(rank: u64, len: usize) -> Result<Self> {
+        ensure!(
+            len <= LEHMER_MAX_LEN,
+            "permutations with {} slots can't be ranked",
+            len
+        );
+        ensure!(
+            rank < factorial(len),
+            "rank {} out of range for permutations with {} slots",
+            rank,
+            len
+        );
+        let mut avail = (0..len as IdBase).collect::<Vec<_>>();
+        let mut rank = rank;
+        let mut masks = SmallVec::with_capacity(len);
+        for i in 0..len {
+            let f = factorial(len - 1 - i);
+            let v = avail.remove((rank / f) as usize);
+            rank %= f;
+            masks.push(Bitset::from_idxs(&[v]));
+        }
+        Ok(MaskedMatching::from(masks))
+    }
+
+    /// The assignment rank of this matching where the elements are in `0..len_b`. Each element
+    /// may be in at most one slot. Returns `None` if this is not the case or if the rank does not
+    /// fit into 63 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ayto::matching_repr::MaskedMatching;
+    /// let m = MaskedMatching::from_matching_ref(&[vec![1, 2], vec![0]]);
+    /// let rank = m.assignment_rank(3).unwrap();
+    /// assert_eq!(MaskedMatching::from_assignment_rank(rank, 2, 3).unwrap(), m);
+    /// ```
+    pub fn assignment_rank(&self, len_b: usize) -> Option<u64> {
+        // the digit of each element: the slot it is assigned to + 1 or 0 if not assigned
+        let mut digits = vec![0u64; len_b];
+        for (slot, b) in self.iter().enumerate() {
+            for v in b.iter() {
+                let d = digits.get_mut(v as usize)?;
+                if *d != 0 {
+                    return None;
+                }
+                *d = slot as u64 + 1;
+            }
+        }
+        let base = self.len() as u64 + 1;
+        let rank = digits
+            .iter()
+            .rev()
+            .try_fold(0u64, |acc, d| acc.checked_mul(base)?.checked_add(*d))?;
+        (rank <= u64::MAX >> 1).then_some(rank)
+    }
+
+    /// Reconstruct the matching with `len_a` slots and elements in `0..len_b` from its assignment
+    /// `rank` (see [`MaskedMatching::assignment_rank`])
+    pub fn from_assignment_rank(rank: u64, len_a: usize, len_b: usize) -> Result<Self> {
+        let base = len_a as u64 + 1;
+        let mut rank = rank;
+        let mut masks: SmallVec<[Bitset; MATCH_MAX_LEN]> =
+            SmallVec::from_elem(Bitset::empty(), len_a);
+        for v in 0..len_b {
+            let d = rank % base;
+            rank /= base;
+            if d > 0 {
+                masks[d as usize - 1].insert(v as IdBase);
+            }
+        }
+        if rank != 0 {
+            bail!(
+                "rank out of range for matchings with {} slots and {} elements",
+                len_a,
+                len_b
+            );
+        }
+        Ok(MaskedMatching::from(masks))
+    }
+}
+
+/// Encodes matchings with `len_a` slots and elements in `0..len_b` as a single `u64`.
+///
+/// Permutations are encoded via their Lehmer rank, all other matchings via their assignment rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RankCodec {
+    /// the amount of slots of the matchings
+    len_a: usize,
+    /// the amount of elements which can be in the slots
+    len_b: usize,
+}
+
+impl RankCodec {
+    /// A codec for matchings with `len_a` slots and elements in `0..len_b`
+    pub fn new(len_a: usize, len_b: usize) -> Self {
+        RankCodec { len_a, len_b }
+    }
+
+    /// Encode `m`. Returns `None` if `m` can't be encoded by this codec (see
+    /// [`MaskedMatching::assignment_rank`]).
+    pub fn encode(&self, m: &MaskedMatching) -> Option<u64> {
+        if m.len() != self.len_a {
+            return None;
+        }
+        if let Some(r) = m.lehmer_rank() {
+            return Some(r << 1);
+        }
+        m.assignment_rank(self.len_b).map(|r| (r << 1) | 1)
+    }
+
+    /// Decode a `code` produced by [`RankCodec::encode`]
+    pub fn decode(&self, code: u64) -> Result<MaskedMatching> {
+        if code & 1 == 0 {
+            MaskedMatching::from_lehmer_rank(code >> 1, self.len_a)
+        } else {
+            MaskedMatching::from_assignment_rank(code >> 1, self.len_a, self.len_b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn lehmer_rank_all() {
+        let mut seen = HashSet::new();
+        for r in 0..120 {
+            let m = MaskedMatching::from_lehmer_rank(r, 5).unwrap();
+            assert_eq!(m.lehmer_rank(), Some(r));
+            assert!(seen.insert(m));
+        }
+
+        // identity and reversed are first and last
+        let m = MaskedMatching::from(&[0, 1, 2, 3][..]);
+        assert_eq!(m.lehmer_rank(), Some(0));
+        let m = MaskedMatching::from(&[3, 2, 1, 0][..]);
+        assert_eq!(m.lehmer_rank(), Some(23));
+    }
+
+    #[test]
+    fn lehmer_rank_invalid() {
+        // not a singleton
+        let m = MaskedMatching::from_matching_ref(&[vec![0, 1], vec![2]]);
+        assert_eq!(m.lehmer_rank(), None);
+        // element out of range
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![2]]);
+        assert_eq!(m.lehmer_rank(), None);
+        // element used twice
+        let m = MaskedMatching::from_matching_ref(&[vec![1], vec![1]]);
+        assert_eq!(m.lehmer_rank(), None);
+
+        assert!(MaskedMatching::from_lehmer_rank(6, 3).is_err());
+        assert!(MaskedMatching::from_lehmer_rank(0, 21).is_err());
+    }
+
+    #[test]
+    fn assignment_rank_simple() {
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![1, 3], vec![2]]);
+        let r = m.assignment_rank(5).unwrap();
+        // digits (element 0..5): 1, 2, 3, 2, 0 with base 4
+        assert_eq!(r, 1 + 2 * 4 + 3 * 16 + 2 * 64);
+        assert_eq!(MaskedMatching::from_assignment_rank(r, 3, 5).unwrap(), m);
+
+        // element in two slots
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![0]]);
+        assert_eq!(m.assignment_rank(2), None);
+        // element out of range
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![4]]);
+        assert_eq!(m.assignment_rank(2), None);
+        // too large
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![63]]);
+        assert_eq!(m.assignment_rank(64), None);
+
+        assert!(MaskedMatching::from_assignment_rank(9, 2, 2).is_err());
+    }
+
+    #[test]
+    fn codec_roundtrip() {
+        let c = RankCodec::new(3, 4);
+        for m in [
+            MaskedMatching::from_matching_ref(&[vec![2], vec![0], vec![1]]),
+            MaskedMatching::from_matching_ref(&[vec![3], vec![0], vec![1, 2]]),
+            MaskedMatching::from_matching_ref(&[vec![3], vec![0], vec![]]),
+        ] {
+            let code = c.encode(&m).unwrap();
+            assert_eq!(c.decode(code).unwrap(), m);
+        }
+        // permutations use the lehmer rank
+        let m = MaskedMatching::from_matching_ref(&[vec![2], vec![1], vec![0]]);
+        assert_eq!(c.encode(&m), Some(5 << 1));
+        // wrong amount of slots
+        let m = MaskedMatching::from_matching_ref(&[vec![0], vec![1]]);
+        assert_eq!(c.encode(&m), None);
+    }
+}
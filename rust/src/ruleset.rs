@@ -12,6 +12,7 @@ mod permutators;
 mod utils;
 
 use crate::matching_repr::bitset::Bitset;
+use crate::matching_repr::rank::RankCodec;
 use crate::matching_repr::{IdBase, MaskedMatching};
 use crate::ruleset::permutators::{
     dup::add_x_dups_inplace, dup::someone_is_dup_inplace, heaps_permute, n_to_n::n_to_n_inplace,
@@ -19,6 +20,7 @@ use crate::ruleset::permutators::{
 };
 
 use anyhow::{ensure, Context, Result};
+use num_bigint::BigUint;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
@@ -67,12 +69,18 @@ impl RuleSet {
 
         // If a cache of serialized MaskedMatching objects exists, prefer streaming that
         // (we deserialize MaskedMatching directly and pass a reference to is.step).
+        // Each line either contains the rank of the matching or the matching serialized as json.
         if let Some(c) = cache {
             let file = File::open(c)
                 .with_context(|| format!("Cache path ({:?}) is not a readable file", c))?;
             let reader = BufReader::new(file);
+            let codec = RankCodec::new(lut_a.len(), lut_b.len());
             for (i, line) in reader.lines().enumerate() {
-                let p = serde_json::from_str::<MaskedMatching>(&line?)?;
+                let line = line?;
+                let p = match line.parse::<u64>() {
+                    Ok(code) => codec.decode(code)?,
+                    Err(_) => serde_json::from_str::<MaskedMatching>(&line)?,
+                };
                 is.step(i, &p)?;
             }
             is.finish();
@@ -210,6 +218,10 @@ impl RuleSet {
     }
 
     /// get the amount of permutations which is to be expected with this ruleset
+    ///
+    /// If `cache` is set, the amount of lines in the cache is returned. Otherwise the amount is
+    /// calculated via [`RuleSet::count_perms`], amounts not fitting into `usize` saturate (this is
+    /// only used to show the progress).
     pub fn get_perms_amount(
         &self,
         size_map_a: usize,
@@ -222,6 +234,24 @@ impl RuleSet {
             let line_count = reader.lines().count();
             return Ok(line_count);
         }
+        let cnt = self.count_perms(size_map_a, size_map_b)?;
+        Ok(usize::try_from(&cnt).unwrap_or(usize::MAX))
+    }
+
+    /// the exact amount of permutations which are generated with this ruleset for `size_map_a`
+    /// and `size_map_b` individuals (the size of the search space)
+    ///
+    /// In contrast to `usize` this does not overflow for larger casts (e.g. 20! already exceeds
+    /// `u64`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ayto::ruleset::RuleSet;
+    /// let cnt = RuleSet::Eq.count_perms(25, 25).unwrap();
+    /// assert_eq!(cnt.to_string(), "15511210043330985984000000");
+    /// ```
+    pub fn count_perms(&self, size_map_a: usize, size_map_b: usize) -> Result<BigUint> {
         Ok(match self {
             RuleSet::XTimesDup((unkown_cnt, fixed)) => {
                 // number of buckets / each permutation
@@ -232,41 +262,56 @@ impl RuleSet {
                 let f = fixed.len();
                 // number of additional "items" which are placed double buckets
                 let s = unkown_cnt;
+                ensure!(
+                    s + f <= a && a - s <= b && f <= b,
+                    "{} dups (thereof {} fixed) are not possible with {}/{} individuals",
+                    s + f,
+                    f,
+                    a,
+                    b
+                );
 
                 // function foo(a,b,s,f) return (math.factorial(a)*math.factorial(b-f)*math.factorial(2*s+2*f))/(math.factorial(s+f)*math.factorial(a-s-f)*math.factorial(b-a+s)*2^(s+f)) end
 
                 // choose which buckets should be double-buckets
                 //   => choose (s+f) positions out of a positions
-                let f_a = permutator::divide_factorial(a, a - (s + f));
+                let f_a = divide_factorial(a, a - (s + f));
                 // choose which "items" to place in the single-buckets
                 //   => choose a-(s+f) items from b-f available items
                 //   (simplified the denominator)
-                let f_b = permutator::divide_factorial(b - f, b - (a - s));
+                let f_b = divide_factorial(b - f, b - (a - s));
                 // "items" left to distribute: 2l = b-(a-s-f)
                 //   -> make l pairs out of them
                 //   -> order all items (1), then remove duplicates (just swapped) (2), then ignore oder of pairs (3)
                 //   => (2l)! / 2^l / l!
                 //   -> assign pairs to double-bucket position
                 //   => l!
-                let f_c = permutator::divide_factorial(b - (a - s - f), s + f)
-                    / (2_usize).pow((s + f) as u32);
+                let f_c = divide_factorial(b - (a - s - f), s + f) >> (s + f);
                 f_a * f_b * f_c
             }
             // choose one of setA to have the triple (a) and distribute the remaining ones (b!/3!)
-            RuleSet::SomeoneIsTrip => size_map_a * permutator::factorial(size_map_b) / 6,
+            RuleSet::SomeoneIsTrip => size_map_a * factorial(size_map_b) / 6u32,
             // chose one of setA to have the triple (a) and distribute the remaining ones without
             // the fixed one ((b-1)!/2!)
-            RuleSet::FixedTrip(_) => size_map_a * permutator::factorial(size_map_b - 1) / 2,
-            RuleSet::Eq => permutator::factorial(size_map_a),
+            RuleSet::FixedTrip(_) => size_map_a * factorial(size_map_b.saturating_sub(1)) / 2u32,
+            RuleSet::Eq => factorial(size_map_a),
             // first choose the items for the first set, then distribute the rest. Avoid double
             // counting. binom(X,2X) * X! / 2
-            RuleSet::NToN => {
-                permutator::divide_factorial(size_map_a, size_map_a / 2) / (1 << (size_map_a / 2))
-            }
+            RuleSet::NToN => divide_factorial(size_map_a, size_map_a / 2) >> (size_map_a / 2),
         })
     }
 }
 
+/// `n!` as arbitrary-precision integer
+fn factorial(n: usize) -> BigUint {
+    divide_factorial(n, 0)
+}
+
+/// `n! / k!` as arbitrary-precision integer (`1` if `k >= n`)
+fn divide_factorial(n: usize, k: usize) -> BigUint {
+    (k + 1..=n).fold(BigUint::from(1u8), |acc, i| acc * i)
+}
+
 /// Copy `slice` into the provided [`crate::matching_repr::MaskedMatching`] and forward it to the iterator-state.
 ///
 /// When re-using the same MaskedMatching over and over again this avoids having to allocate a
@@ -443,6 +488,26 @@ mod tests {
         assert_eq!(col.outputs[0], mm);
     }
 
+    #[test]
+    fn iter_perms_cache_ranks() {
+        // ranks and json can be mixed
+        let tmp = NamedTempFile::new().unwrap();
+        let mm = MaskedMatching::from_matching_ref(&[vec![1], vec![0]]);
+        let mm_dup = MaskedMatching::from_matching_ref(&[vec![1, 2], vec![0]]);
+        let codec = RankCodec::new(2, 3);
+        writeln!(tmp.as_file(), "{}", codec.encode(&mm).unwrap()).unwrap();
+        writeln!(tmp.as_file(), "{}", codec.encode(&mm_dup).unwrap()).unwrap();
+        writeln!(tmp.as_file(), "{}", serde_json::to_string(&mm).unwrap()).unwrap();
+
+        let lut_a = make_lut(&["a", "b"]);
+        let lut_b = make_lut(&["A", "B", "C"]);
+        let mut col = Collector::new();
+        RuleSet::Eq
+            .iter_perms(&lut_a, &lut_b, &mut col, &Some(PathBuf::from(tmp.path())))
+            .unwrap();
+        assert_eq!(col.outputs, vec![mm.clone(), mm_dup, mm]);
+    }
+
     #[test]
     fn get_perms_amount_eq_simple() {
         let amt = RuleSet::Eq.get_perms_amount(3, 3, &None).unwrap();
@@ -480,6 +545,24 @@ mod tests {
         assert_eq!(amt, 3);
     }
 
+    #[test]
+    fn count_perms_large() {
+        // 21! exceeds u64
+        let cnt = RuleSet::Eq.count_perms(21, 21).unwrap();
+        assert_eq!(cnt.to_string(), "51090942171709440000");
+        assert_eq!(
+            RuleSet::Eq.get_perms_amount(21, 21, &None).unwrap(),
+            usize::MAX
+        );
+        // 41!! for 42 individuals
+        let cnt = RuleSet::NToN.count_perms(42, 42).unwrap();
+        assert_eq!(cnt.to_string(), "13113070457687988603440625");
+
+        // impossible amount of dups
+        let cfg = (3usize, vec!["a".to_string()]);
+        assert!(RuleSet::XTimesDup(cfg).count_perms(3, 4).is_err());
+    }
+
     #[test]
     fn get_perms_amount_cache_simple() {
         let tmp = NamedTempFile::new().unwrap();
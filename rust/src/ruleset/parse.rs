@@ -5,16 +5,18 @@
 //! This module parses a ruleset. It transfers a RuleSetParse to a ready to use Ruleset.
 
 use crate::ruleset::RuleSet;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// An enum defining all the different rulesets which can be applied to the game.
 /// This enum is only for parsing such a ruleset from file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
 pub enum RuleSetParse {
     /// A ruleset where X duplicates exist. One of the two individuals forming the dup might be
     /// known (`Some(name)`) or not (`None`).
     /// The dups have to exist on the set_b side.
-    XTimesDup(Vec<Option<String>>),
+    /// In formats without null (toml) an unknown individual can be given as `""` as well.
+    XTimesDup(#[serde(deserialize_with = "unknown_as_none")] Vec<Option<String>>),
     /// A ruleset where exactly one triple exists. None of the individuals of the triple is known.
     /// The triple has to exist on the set_b side.
     SomeoneIsTrip,
@@ -29,6 +31,15 @@ pub enum RuleSetParse {
     Eq,
 }
 
+/// Deserialize a list of individuals where unknown ones are given as `null` or `""`
+fn unknown_as_none<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Option<String>>, D::Error> {
+    let names: Vec<Option<String>> = Vec::deserialize(d)?;
+    Ok(names
+        .into_iter()
+        .map(|n| n.filter(|n| !n.is_empty()))
+        .collect())
+}
+
 impl RuleSetParse {
     /// finalizes the parsing by consuming the [`RuleSetParse`] and producing the final [`super::RuleSet`]
     pub fn finalize_parsing(self) -> RuleSet {
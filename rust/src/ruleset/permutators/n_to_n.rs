@@ -5,24 +5,24 @@
 //! This module implements a generator for n-to-n matchings
 
 use anyhow::ensure;
-use permutator::{Combination, Permutation};
 
 use crate::matching_repr::{bitset::Bitset, IdBase};
 
-/// In-place generator for N-to-N assignments.
+/// In-place generator for N-to-N assignments (perfect matchings of the complete graph).
 ///
 /// Semantics:
-/// - `slots` must be even, `k = slots / 2`.
-/// - For every choice `ks` of `k` slot indices (combination) select a permutation of the remaining
-///   values `vs` and map it to the chosen `ks`
-/// - This emitter produces `&[Bitset]` buffers where only the `ks` positions are singletons; other
-///   positions are unset/empty [`crate::matching_repr::bitset::Bitset::empty`].
+/// - `slots` must be even.
+/// - The lowest unmatched index `i` is paired with every other unmatched index `j > i` in turn,
+///   then the remaining indices are paired recursively. This way each perfect matching is emitted
+///   exactly once (no symmetric duplicates have to be filtered).
+/// - This emitter produces `&[Bitset]` buffers where a pair `(i, j)` with `i < j` is stored as the
+///   singleton `i` at position `j`; the position `i` is unset/empty
+///   [`crate::matching_repr::bitset::Bitset::empty`].
 ///
 /// Notes:
-/// - To avoid allocations, permutations of `vs` are generated in-place using `heaps_permute`.
-/// - The function uses a reusable `c` output buffer which is overwritten for each emission.
-/// - Reject assignments where the slot index is less than or equal to the value index, because
-///   such a pairing would be symmetric with another generated permutation
+/// - The function uses a reusable `c` output buffer which is modified in-place and restored after
+///   each emission.
+/// - The recursion depth is `slots / 2`.
 #[inline]
 pub(crate) fn n_to_n_inplace<F>(slots: usize, mut emit: F) -> anyhow::Result<()>
 where
@@ -33,43 +33,28 @@ where
     }
     ensure!(slots.is_multiple_of(2));
 
-    let len = slots / 2;
-
     // Reusable output buffer: all empty initially.
     let mut c = vec![Bitset::empty(); slots];
+    let unmatched = Bitset::from_idxs(&(0..slots as IdBase).collect::<Vec<_>>());
+    pair_lowest(&mut c, unmatched, &mut emit)
+}
 
-    // Precompute full index set as u8 vector for perm/combo helpers
-    let full_indices: Vec<IdBase> = (0..slots as IdBase).collect();
-
-    // Iterate combinations of indices (ks)
-    for ks in full_indices.combination(len) {
-        // produce the list of remaining values (vs)
-        let mut vs = (0..slots as IdBase)
-            .filter(|x| !ks.contains(&x))
-            .collect::<Vec<_>>();
-
-        // For every permutation of remaining values
-        for perm_vs in vs.permutation() {
-            // reset buffer c to empty
-            for elem in &mut c {
-                *elem = Bitset::empty();
-            }
-
-            // put assigned values into ks positions (zip ks and perm_vs)
-            let mut ok = true;
-            for (k_u8, v_u8) in ks.iter().zip(perm_vs.iter()) {
-                let k = **k_u8 as usize;
-                let v = *v_u8;
-                if (k as IdBase) <= v {
-                    ok = false;
-                    break;
-                }
-                c[k] = Bitset::from_idxs(&[v]);
-            }
-            if ok {
-                emit(&c)?;
-            }
-        }
+/// Pair the lowest index of `unmatched` with each of the other ones and recurse on the rest.
+/// Emits `c` once `unmatched` is empty.
+fn pair_lowest<F>(c: &mut [Bitset], mut unmatched: Bitset, emit: &mut F) -> anyhow::Result<()>
+where
+    F: FnMut(&[Bitset]) -> anyhow::Result<()>,
+{
+    let Some(i) = unmatched.iter().next() else {
+        return emit(c);
+    };
+    unmatched.clear_bit(i);
+    for j in unmatched.iter() {
+        let mut rest = unmatched;
+        rest.clear_bit(j);
+        c[j as usize] = Bitset::from_idxs(&[i]);
+        pair_lowest(c, rest, emit)?;
+        c[j as usize] = Bitset::empty();
     }
     Ok(())
 }
@@ -217,50 +202,50 @@ mod tests {
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[1]),
                 Bitset::from_idxs(&[0]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[4]),
+                Bitset::from_idxs(&[1]),
+                Bitset::from_idxs(&[3]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[0]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[1]),
                 Bitset::from_idxs(&[3]),
+                Bitset::from_idxs(&[1]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[1]),
-                Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[0]),
-                Bitset::from_idxs(&[3]),
+                Bitset::from_idxs(&[]),
+                Bitset::from_idxs(&[4]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[0]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[3]),
+                Bitset::from_idxs(&[0]),
                 Bitset::from_idxs(&[1]),
+                Bitset::from_idxs(&[2]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[1]),
                 Bitset::from_idxs(&[]),
-                Bitset::from_idxs(&[3]),
                 Bitset::from_idxs(&[0]),
+                Bitset::from_idxs(&[2]),
+                Bitset::from_idxs(&[1]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
+                Bitset::from_idxs(&[1]),
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[0]),
-                Bitset::from_idxs(&[1]),
-                Bitset::from_idxs(&[2]),
+                Bitset::from_idxs(&[3]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
@@ -281,10 +266,10 @@ mod tests {
             vec![
                 Bitset::from_idxs(&[]),
                 Bitset::from_idxs(&[]),
+                Bitset::from_idxs(&[1]),
                 Bitset::from_idxs(&[]),
+                Bitset::from_idxs(&[3]),
                 Bitset::from_idxs(&[0]),
-                Bitset::from_idxs(&[2]),
-                Bitset::from_idxs(&[1]),
             ],
             vec![
                 Bitset::from_idxs(&[]),
@@ -309,4 +294,24 @@ mod tests {
         assert!(out.iter().all(|i| check_invariance_singleton(i)));
         Ok(())
     }
+
+    #[test]
+    fn n_to_n_inplace_count() -> anyhow::Result<()> {
+        // each perfect matching is emitted exactly once
+        for slots in (2..=12).step_by(2) {
+            let mut out = std::collections::HashSet::new();
+            let mut cnt = 0;
+            n_to_n_inplace(slots, |s| {
+                assert!(check_invariance_ordering(s));
+                assert!(check_invariance_singleton(s));
+                out.insert(s.to_vec());
+                cnt += 1;
+                Ok(())
+            })?;
+            let expected = crate::ruleset::RuleSet::NToN.get_perms_amount(slots, slots, &None)?;
+            assert_eq!(cnt, expected);
+            assert_eq!(out.len(), expected);
+        }
+        Ok(())
+    }
 }
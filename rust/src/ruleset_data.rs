@@ -10,7 +10,7 @@
 pub mod dummy;
 pub mod dup;
 pub mod dup_x;
-mod utils;
+pub(crate) mod utils;
 
 use anyhow::Result;
 
@@ -40,7 +40,7 @@ where
 ///
 /// Implementations may collect statistics (e.g. duplicate/trip counts) while
 /// the simulation runs, then render human-readable output via `print`.
-pub trait RuleSetData: std::fmt::Debug + RuleSetDataClone {
+pub trait RuleSetData: std::fmt::Debug + RuleSetDataClone + Send {
     /// Called for each solution matching encountered
     fn push(&mut self, m: &MaskedMatching) -> Result<()>;
 
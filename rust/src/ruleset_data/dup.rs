@@ -202,6 +202,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_sort_by)]
     fn new_simple() -> Result<()> {
         let dup = DupData {
             cnt: HashMap::from_iter([
@@ -342,6 +342,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_sort_by)]
     fn new_simple() -> Result<()> {
         let dupx = DupXData {
             cnt: HashMap::from_iter([
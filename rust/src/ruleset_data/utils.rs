@@ -46,7 +46,6 @@ where
         }
     }
     let mut vec: Vec<_> = agg.into_iter().collect();
-    vec.sort_by(|a, b| b.1.cmp(&a.1));
     vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
     vec
 }
@@ -78,7 +77,7 @@ where
 /// `entries` provides data and a count. The count will be converted to percentage values by the
 /// use of `total.`
 #[allow(clippy::too_many_arguments)]
-pub(super) fn print_stats<T, W>(
+pub(crate) fn print_stats<T, W>(
     mut f: W,
     title: &str,
     suffix: &str,
@@ -189,6 +188,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_conversion)]
     fn print_stats_full_simple() -> Result<()> {
         let entries = vec![(("key1".to_string(), 2), 5), (("key2".to_string(), 6), 10)];
         let mut buf = Vec::new();
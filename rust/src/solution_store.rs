@@ -0,0 +1,662 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module implements a memory-bounded storage for the solutions which are left after some
+//! constraints.
+//!
+//! The [`SolutionStore`] keeps solutions in memory as long as the shared [`MemBudget`] allows to.
+//! Once the budget is exhausted, all further solutions are spilled to a temporary file in a
+//! compact binary representation. An index of the offsets of the spilled solutions is kept in
+//! memory so reading can start at an arbitrary solution.
+//!
+//! If the store is given a [`RankCodec`], the solutions are stored as their rank (8 bytes instead
+//! of a full [`MaskedMatching`]), both in memory and on disk. Solutions which can't be encoded are
+//! stored as they are.
+//!
+//! Reading the solutions works via [`SolutionStore::iter`]. As reading from disk might fail, the
+//! iterator yields [`Result`]s.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use smallvec::SmallVec;
+use tempfile::NamedTempFile;
+
+use crate::matching_repr::bitset::Bitset;
+use crate::matching_repr::rank::RankCodec;
+use crate::matching_repr::{MaskedMatching, Word};
+
+/// the amount of encoded bytes buffered before they are written to the spill-file
+const WRITE_BUF_SIZE: usize = 64 * 1024;
+
+/// the tag of a spilled solution which is stored as it is
+const TAG_PLAIN: u8 = 0;
+/// the tag of a spilled solution which is stored as its rank
+const TAG_RANK: u8 = 1;
+
+/// How many bytes the solutions held in memory may occupy. The budget is shared by all stores it
+/// is passed to (clones refer to the same budget). The bytes a store reserved are given back once
+/// the store is dropped.
+#[derive(Debug, Clone)]
+pub struct MemBudget(Arc<AtomicUsize>);
+
+impl MemBudget {
+    /// A budget allowing to hold `mib` MiB of solutions in memory
+    pub fn from_mib(mib: usize) -> Self {
+        Self::from_bytes(mib * 1024 * 1024)
+    }
+
+    /// A budget allowing to hold `bytes` bytes of solutions in memory
+    pub fn from_bytes(bytes: usize) -> Self {
+        MemBudget(Arc::new(AtomicUsize::new(bytes)))
+    }
+
+    /// Try to reserve `bytes` bytes. Returns whether this was successful.
+    fn take(&self, bytes: usize) -> bool {
+        self.0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |r| {
+                r.checked_sub(bytes)
+            })
+            .is_ok()
+    }
+
+    /// Give back `bytes` bytes which were reserved before
+    fn give(&self, bytes: usize) {
+        self.0.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// the amount of bytes which are still available
+    #[cfg(test)]
+    fn available(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The part of a [`SolutionStore`] which was spilled to disk
+#[derive(Debug, Clone)]
+struct Spill {
+    /// the file the solutions are written to (shared between clones of the store)
+    file: Arc<NamedTempFile>,
+    /// the offset of each spilled solution (the offset is relative to the start of the file, also
+    /// for the solutions which are still buffered)
+    index: Vec<u64>,
+    /// the amount of bytes which were written to `file` already
+    written: u64,
+    /// encoded solutions not yet written to `file`
+    buf: Vec<u8>,
+}
+
+impl Spill {
+    /// create a new (empty) temporary spill-file
+    fn new() -> Result<Self> {
+        Ok(Spill {
+            file: Arc::new(NamedTempFile::new().context("creating the spill-file failed")?),
+            index: vec![],
+            written: 0,
+            buf: Vec::with_capacity(WRITE_BUF_SIZE),
+        })
+    }
+
+    /// append `m` to the spilled solutions, encoded via `codec` if possible
+    fn push(&mut self, m: &MaskedMatching, codec: Option<RankCodec>) -> Result<()> {
+        self.index.push(self.written + self.buf.len() as u64);
+        if let Some(code) = codec.and_then(|c| c.encode(m)) {
+            self.buf.push(TAG_RANK);
+            self.buf.extend_from_slice(&code.to_le_bytes());
+        } else {
+            self.buf.push(TAG_PLAIN);
+            self.buf.push(m.len() as u8);
+            for b in m.iter() {
+                self.buf.extend_from_slice(&b.as_word().to_le_bytes());
+            }
+        }
+        if self.buf.len() >= WRITE_BUF_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// write the buffered solutions to the file
+    fn flush(&mut self) -> Result<()> {
+        // clones of the store share the file => copy it before writing to it
+        if Arc::get_mut(&mut self.file).is_none() {
+            let mut copy = NamedTempFile::new().context("creating the spill-file failed")?;
+            std::io::copy(
+                &mut File::open(self.file.path())?.take(self.written),
+                &mut copy,
+            )?;
+            self.file = Arc::new(copy);
+        }
+        let file = Arc::get_mut(&mut self.file).expect("the spill-file is not shared");
+        file.write_all(&self.buf)
+            .context("writing to the spill-file failed")?;
+        self.written += self.buf.len() as u64;
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// a reader for the encoded solutions starting at the spilled solution with index `start`
+    fn reader(&self, start: usize) -> Result<Box<dyn Read + '_>> {
+        let Some(&offset) = self.index.get(start) else {
+            return Ok(Box::new(std::io::empty()));
+        };
+        if offset >= self.written {
+            return Ok(Box::new(&self.buf[(offset - self.written) as usize..]));
+        }
+        let mut file = File::open(self.file.path()).context("opening the spill-file failed")?;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(Box::new(
+            BufReader::new(file.take(self.written - offset)).chain(&self.buf[..]),
+        ))
+    }
+}
+
+/// read one encoded solution from `r`, ranked solutions are decoded via `codec`
+fn read_solution(r: &mut dyn Read, codec: Option<RankCodec>) -> Result<MaskedMatching> {
+    let mut tag = [0u8; 1];
+    r.read_exact(&mut tag)?;
+    if tag[0] == TAG_RANK {
+        let mut code = [0u8; 8];
+        r.read_exact(&mut code)?;
+        return codec
+            .context("ranked solution without a codec")?
+            .decode(u64::from_le_bytes(code));
+    }
+    let mut len = [0u8; 1];
+    r.read_exact(&mut len)?;
+    let mut word = [0u8; std::mem::size_of::<Word>()];
+    let mut masks = SmallVec::with_capacity(len[0] as usize);
+    for _ in 0..len[0] {
+        r.read_exact(&mut word)?;
+        masks.push(Bitset::from_word(Word::from_le_bytes(word)));
+    }
+    Ok(MaskedMatching::from(masks))
+}
+
+/// The solutions held in memory
+#[derive(Debug, Clone)]
+enum Mem {
+    /// the solutions as they are
+    Plain(Vec<MaskedMatching>),
+    /// the ranks of the solutions (see [`RankCodec`])
+    Ranked(Vec<u64>),
+}
+
+impl Mem {
+    /// the amount of solutions held in memory
+    fn len(&self) -> usize {
+        match self {
+            Mem::Plain(ms) => ms.len(),
+            Mem::Ranked(rs) => rs.len(),
+        }
+    }
+}
+
+/// Stores solutions, in memory as long as the [`MemBudget`] allows to, otherwise on disk.
+///
+/// The order in which the solutions were pushed is kept.
+#[derive(Debug)]
+pub struct SolutionStore {
+    /// the solutions held in memory (these are the first ones pushed)
+    mem: Mem,
+    /// the budget limiting how many solutions may be held in memory (unbounded if not set)
+    budget: Option<MemBudget>,
+    /// the amount of bytes this store reserved from `budget` (given back on drop)
+    taken: usize,
+    /// how to encode the solutions compactly (if set)
+    codec: Option<RankCodec>,
+    /// the solutions which were spilled to disk
+    spill: Option<Spill>,
+}
+
+impl Default for SolutionStore {
+    fn default() -> Self {
+        SolutionStore {
+            mem: Mem::Plain(vec![]),
+            budget: None,
+            taken: 0,
+            codec: None,
+            spill: None,
+        }
+    }
+}
+
+impl Clone for SolutionStore {
+    /// The clone holds its own copy of the solutions in memory, so it reserves the bytes again.
+    /// If the budget doesn't allow to, the copy is held in memory nevertheless (like
+    /// [`SolutionStore::to_vec`] does).
+    fn clone(&self) -> Self {
+        let taken = match &self.budget {
+            Some(b) if b.take(self.taken) => self.taken,
+            _ => 0,
+        };
+        SolutionStore {
+            mem: self.mem.clone(),
+            budget: self.budget.clone(),
+            taken,
+            codec: self.codec,
+            spill: self.spill.clone(),
+        }
+    }
+}
+
+impl Drop for SolutionStore {
+    fn drop(&mut self) {
+        if let Some(b) = &self.budget {
+            b.give(self.taken);
+        }
+    }
+}
+
+impl SolutionStore {
+    /// An empty store which holds solutions in memory only as long as `budget` allows to
+    pub fn with_budget(budget: Option<MemBudget>) -> Self {
+        SolutionStore {
+            mem: Mem::Plain(vec![]),
+            budget,
+            taken: 0,
+            codec: None,
+            spill: None,
+        }
+    }
+
+    /// Store the solutions pushed from now on encoded via `codec`. Has to be called on an empty
+    /// store.
+    pub fn compact(mut self, codec: RankCodec) -> Self {
+        debug_assert!(self.is_empty(), "compact called on a non-empty store");
+        self.mem = Mem::Ranked(vec![]);
+        self.codec = Some(codec);
+        self
+    }
+
+    /// Append `m` to the store
+    pub fn push(&mut self, m: &MaskedMatching) -> Result<()> {
+        if let Some(spill) = self.spill.as_mut() {
+            return spill.push(m, self.codec);
+        }
+        let code = self.codec.and_then(|c| c.encode(m));
+        let fits = (code.is_some() || self.make_plain()?) && self.reserve(self.entry_size());
+        if fits {
+            match (&mut self.mem, code) {
+                (Mem::Ranked(rs), Some(code)) => rs.push(code),
+                (Mem::Plain(ms), _) => ms.push(m.clone()),
+                (Mem::Ranked(_), None) => unreachable!("the memory was converted to plain"),
+            }
+            return Ok(());
+        }
+        let mut spill = Spill::new()?;
+        spill.push(m, self.codec)?;
+        self.spill = Some(spill);
+        Ok(())
+    }
+
+    /// the amount of bytes a single solution held in memory occupies
+    fn entry_size(&self) -> usize {
+        match self.mem {
+            Mem::Plain(_) => std::mem::size_of::<MaskedMatching>(),
+            Mem::Ranked(_) => std::mem::size_of::<u64>(),
+        }
+    }
+
+    /// Take `bytes` from the budget (if there is one), returns whether they were available
+    fn reserve(&mut self, bytes: usize) -> bool {
+        match &self.budget {
+            None => true,
+            Some(b) if b.take(bytes) => {
+                self.taken += bytes;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    /// Store the solutions held in memory as they are (needed if a solution can't be encoded).
+    /// The additional memory is taken from the budget, returns false (and leaves the store as it
+    /// is) if it doesn't fit.
+    fn make_plain(&mut self) -> Result<bool> {
+        let n = match &self.mem {
+            Mem::Ranked(rs) => rs.len(),
+            Mem::Plain(_) => return Ok(true),
+        };
+        let grow = std::mem::size_of::<MaskedMatching>() - std::mem::size_of::<u64>();
+        if !self.reserve(n * grow) {
+            return Ok(false);
+        }
+        if let Mem::Ranked(rs) = &self.mem {
+            let codec = self.codec.context("ranked solutions without a codec")?;
+            let ms = rs
+                .iter()
+                .map(|&r| codec.decode(r))
+                .collect::<Result<Vec<_>>>()?;
+            self.mem = Mem::Plain(ms);
+        }
+        Ok(true)
+    }
+
+    /// the amount of solutions stored
+    pub fn len(&self) -> usize {
+        self.mem.len() + self.spill.as_ref().map_or(0, |s| s.index.len())
+    }
+
+    /// whether no solutions are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// whether some of the solutions were spilled to disk
+    pub fn spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Iterate over all solutions in the order they were pushed
+    pub fn iter(&self) -> Iter<'_> {
+        self.iter_from(0)
+    }
+
+    /// Iterate over the solutions starting with the solution at index `start`. Uses the index to
+    /// avoid reading the skipped solutions.
+    pub fn iter_from(&self, start: usize) -> Iter<'_> {
+        Iter {
+            store: self,
+            mem_pos: start,
+            spill_start: start.saturating_sub(self.mem.len()),
+            reader: None,
+            remaining: self.len().saturating_sub(start),
+        }
+    }
+
+    /// Load all solutions into memory (ignoring the budget)
+    pub fn to_vec(&self) -> Result<Vec<MaskedMatching>> {
+        self.iter().collect()
+    }
+}
+
+impl From<Vec<MaskedMatching>> for SolutionStore {
+    fn from(ms: Vec<MaskedMatching>) -> Self {
+        SolutionStore {
+            mem: Mem::Plain(ms),
+            budget: None,
+            taken: 0,
+            codec: None,
+            spill: None,
+        }
+    }
+}
+
+impl PartialEq for SolutionStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| matches!((a, b), (Ok(a), Ok(b)) if a == b))
+    }
+}
+
+/// Iterator over the solutions of a [`SolutionStore`]
+pub struct Iter<'a> {
+    /// the store which is iterated
+    store: &'a SolutionStore,
+    /// the position of the next solution held in memory to yield
+    mem_pos: usize,
+    /// the index of the spilled solution to start reading at
+    spill_start: usize,
+    /// reads the spilled solutions (opened lazily once the in-memory ones are done)
+    reader: Option<Box<dyn Read + 'a>>,
+    /// the amount of solutions not yielded yet
+    remaining: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<MaskedMatching>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let pos = self.mem_pos;
+        self.mem_pos += 1;
+        match &self.store.mem {
+            Mem::Plain(ms) if pos < ms.len() => return Some(Ok(ms[pos].clone())),
+            Mem::Ranked(rs) if pos < rs.len() => {
+                let codec = self.store.codec?;
+                return Some(codec.decode(rs[pos]));
+            }
+            _ => {}
+        }
+        let reader = match self.reader.as_mut() {
+            Some(r) => r,
+            None => {
+                let spill = self.store.spill.as_ref()?;
+                match spill.reader(self.spill_start) {
+                    Ok(r) => self.reader.insert(r),
+                    Err(e) => {
+                        // don't try again
+                        self.remaining = 0;
+                        return Some(Err(e));
+                    }
+                }
+            }
+        };
+        let ret =
+            read_solution(reader, self.store.codec).context("reading the spilled solutions failed");
+        if ret.is_err() {
+            self.remaining = 0;
+        }
+        Some(ret)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::game::builder::box_night;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    /// `n` distinct matchings with 3 slots
+    fn matchings(n: u8) -> Vec<MaskedMatching> {
+        (0..n)
+            .map(|i| MaskedMatching::from_matching_ref(&[vec![i % 3], vec![i / 3, 40], vec![i]]))
+            .collect()
+    }
+
+    #[test]
+    fn store_in_memory() -> Result<()> {
+        let mut s = SolutionStore::default();
+        for m in &matchings(10) {
+            s.push(m)?;
+        }
+        assert!(!s.spilled());
+        assert_eq!(s.len(), 10);
+        assert_eq!(s.to_vec()?, matchings(10));
+        Ok(())
+    }
+
+    #[test]
+    fn store_spilled() -> Result<()> {
+        let budget = MemBudget::from_bytes(4 * std::mem::size_of::<MaskedMatching>());
+        let mut s = SolutionStore::with_budget(Some(budget.clone()));
+        let mut t = SolutionStore::with_budget(Some(budget));
+        for m in &matchings(3) {
+            s.push(m)?;
+        }
+        // only one solution is left in the shared budget
+        for m in &matchings(50) {
+            t.push(m)?;
+        }
+        assert!(!s.spilled());
+        assert!(t.spilled());
+        assert_eq!(t.mem.len(), 1);
+        assert_eq!(t.len(), 50);
+        assert_eq!(t.to_vec()?, matchings(50));
+        assert_eq!(
+            t.iter_from(20).collect::<Result<Vec<_>>>()?,
+            matchings(50)[20..]
+        );
+        assert_eq!(t.iter_from(60).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn store_budget_given_back() -> Result<()> {
+        let size = std::mem::size_of::<MaskedMatching>();
+        let budget = MemBudget::from_bytes(4 * size);
+        let mut s = SolutionStore::with_budget(Some(budget.clone()));
+        for m in &matchings(3) {
+            s.push(m)?;
+        }
+        assert_eq!(budget.available(), size);
+
+        // the clone doesn't fit into the budget
+        let c = s.clone();
+        assert_eq!(budget.available(), size);
+        drop(c);
+        assert_eq!(budget.available(), size);
+
+        drop(s);
+        assert_eq!(budget.available(), 4 * size);
+
+        // a store which spilled gives back what it held in memory
+        let mut t = SolutionStore::with_budget(Some(budget.clone()));
+        for m in &matchings(10) {
+            t.push(m)?;
+        }
+        assert!(t.spilled());
+        assert_eq!(budget.available(), 0);
+        let c = t.clone();
+        drop(t);
+        assert_eq!(budget.available(), 4 * size);
+        drop(c);
+        assert_eq!(budget.available(), 4 * size);
+        Ok(())
+    }
+
+    #[test]
+    fn store_spilled_flushed() -> Result<()> {
+        // enough solutions so the buffer is written to the file a few times
+        let ms = (0..20_000u32)
+            .map(|i| {
+                MaskedMatching::from_matching_ref(&[
+                    vec![(i % 64) as u8],
+                    vec![(i / 64 % 64) as u8],
+                ])
+            })
+            .collect::<Vec<_>>();
+        let mut s = SolutionStore::with_budget(Some(MemBudget::from_bytes(0)));
+        for m in &ms {
+            s.push(m)?;
+        }
+        assert!(s.spill.as_ref().unwrap().written > 0);
+        assert_eq!(s.to_vec()?, ms);
+        assert_eq!(
+            s.iter_from(12_345).next().transpose()?,
+            Some(ms[12_345].clone())
+        );
+
+        // pushing to a clone must not affect the original
+        let mut c = s.clone();
+        for m in &ms {
+            c.push(m)?;
+        }
+        assert_eq!(c.len(), 2 * ms.len());
+        assert_eq!(s.to_vec()?, ms);
+        assert_eq!(c.iter_from(ms.len()).collect::<Result<Vec<_>>>()?, ms);
+        assert_eq!(s, SolutionStore::from(ms));
+        Ok(())
+    }
+
+    #[test]
+    fn store_compact() -> Result<()> {
+        let ms = (0..24)
+            .map(|r| MaskedMatching::from_lehmer_rank(r, 4))
+            .collect::<Result<Vec<_>>>()?;
+        let budget = MemBudget::from_bytes(10 * std::mem::size_of::<u64>());
+        let mut s = SolutionStore::with_budget(Some(budget)).compact(RankCodec::new(4, 4));
+        for m in &ms {
+            s.push(m)?;
+        }
+        assert!(matches!(&s.mem, Mem::Ranked(rs) if rs.len() == 10));
+        assert!(s.spilled());
+        assert_eq!(s.to_vec()?, ms);
+
+        // a solution which can't be ranked
+        let mut s = SolutionStore::default().compact(RankCodec::new(4, 4));
+        let odd = MaskedMatching::from_matching_ref(&[vec![0], vec![0], vec![1], vec![2]]);
+        s.push(&ms[3])?;
+        s.push(&odd)?;
+        s.push(&ms[5])?;
+        assert!(matches!(&s.mem, Mem::Plain(_)));
+        assert_eq!(s.to_vec()?, vec![ms[3].clone(), odd, ms[5].clone()]);
+        Ok(())
+    }
+
+    #[test]
+    fn store_compact_plain_budget() -> Result<()> {
+        let (rank, plain) = (
+            std::mem::size_of::<u64>(),
+            std::mem::size_of::<MaskedMatching>(),
+        );
+        let ms = (0..24)
+            .map(|r| MaskedMatching::from_lehmer_rank(r, 4))
+            .collect::<Result<Vec<_>>>()?;
+        let odd = MaskedMatching::from_matching_ref(&[vec![0], vec![0], vec![1], vec![2]]);
+
+        // converting to plain takes the difference from the budget, later pushes the plain size
+        let budget = MemBudget::from_bytes(4 * plain);
+        let mut s = SolutionStore::with_budget(Some(budget.clone())).compact(RankCodec::new(4, 4));
+        s.push(&ms[0])?;
+        s.push(&ms[1])?;
+        assert_eq!(budget.available(), 4 * plain - 2 * rank);
+        s.push(&odd)?;
+        assert_eq!(budget.available(), plain);
+        s.push(&ms[2])?;
+        assert_eq!(budget.available(), 0);
+        assert!(!s.spilled());
+        s.push(&ms[3])?;
+        assert!(s.spilled());
+        drop(s);
+        assert_eq!(budget.available(), 4 * plain);
+
+        // the conversion doesn't fit => spill instead
+        let budget = MemBudget::from_bytes(plain);
+        let mut s = SolutionStore::with_budget(Some(budget.clone())).compact(RankCodec::new(4, 4));
+        for m in &ms[..3] {
+            s.push(m)?;
+        }
+        s.push(&odd)?;
+        assert!(s.spilled());
+        assert!(matches!(&s.mem, Mem::Ranked(rs) if rs.len() == 3));
+        assert_eq!(budget.available(), plain - 3 * rank);
+        assert_eq!(
+            s.to_vec()?,
+            vec![ms[0].clone(), ms[1].clone(), ms[2].clone(), odd]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sim_spilled() -> Result<()> {
+        let mut g = box_night().build()?;
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        assert!(!is.left_poss.spilled());
+
+        let mut g = box_night().build()?;
+        g.set_mem_budget(Some(0));
+        let is_spilled: IterState<MockProgressBar, _> = g.sim(None)?;
+        assert!(is_spilled.left_poss.spilled());
+        assert_eq!(is_spilled.left_poss, is.left_poss);
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module rates the decisions made for an event by comparing the expected information of the
+//! chosen match box / seating with the one the strategies of the solver would have chosen.
+//!
+//! - match boxes are compared with the pair chosen by the optimal strategy (the pair with a
+//!   probability of about 50%). This only needs the probabilities of the pairs.
+//! - matching nights are compared with the best seating found by the entropy strategy and with a
+//!   random seating. This needs the solutions left, without them only the expected information
+//!   of the chosen seating is known.
+
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::matching_repr::{IdBase, MaskedMatching};
+use crate::solver::calc_entropy;
+use crate::solver::mb::MbOptimizer;
+use crate::solver::mn::MnOptimizer;
+use crate::Rem;
+
+/// how many random seatings are averaged for the expected information of a random seating
+const RANDOM_SEATINGS: usize = 16;
+
+/// The expected information (in bits) of the decision made for an event compared to alternatives
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DecisionRating {
+    /// the expected information of the chosen match box / seating
+    pub chosen: f64,
+    /// the expected information of the match box / seating the strategies would have chosen (if
+    /// known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best: Option<f64>,
+    /// the average expected information of a random seating (only for matching nights)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random: Option<f64>,
+}
+
+impl DecisionRating {
+    /// The share of the best expected information which was achieved by the decision (if the best
+    /// one is known). Might exceed `1` if the decision was better than the one of the strategies.
+    pub fn efficiency(&self) -> Option<f64> {
+        let best = self.best?;
+        Some(if best > 0.0 {
+            self.chosen / best
+        } else {
+            // nothing to learn anymore -> every decision is as good as the best one
+            1.0
+        })
+    }
+}
+
+impl fmt::Display for DecisionRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.efficiency() {
+            Some(e) => write!(f, "{:.0}%", e * 100.0),
+            // at least show the expected information of the decision
+            None => write!(f, "{:.2} bit", self.chosen),
+        }
+    }
+}
+
+/// Rate a match box with the expected information `chosen` given the table `rem` of the solutions
+/// left before it against the box chosen by `mb`
+pub fn rate_box<S: MbOptimizer>(
+    chosen: f64,
+    rem: &Rem,
+    mb: &S,
+    rng: &mut dyn Rng,
+) -> DecisionRating {
+    let best = mb.choose_mb(&rem.0, rem.1, rng);
+    // a box with a single pair lights up with the probability of the pair
+    let best = best.iter_pairs().next().and_then(|(a, b)| {
+        let cnt = *rem.0.get(a as usize)?.get(b as usize)?;
+        Some(binary_entropy(cnt as f64 / rem.1 as f64))
+    });
+    DecisionRating {
+        chosen,
+        best,
+        random: None,
+    }
+}
+
+/// Rate a seating with the expected information `chosen` given the solutions `left` before it (the
+/// elements of set_b are in `0..len_b`) against the seating chosen by `mn` and against random
+/// seatings. If the solutions left are unknown, only the expected information of the seating is
+/// known.
+pub fn rate_night<T: MnOptimizer>(
+    chosen: f64,
+    left: Option<&[MaskedMatching]>,
+    len_a: usize,
+    len_b: usize,
+    mn: &T,
+    rng: &mut dyn Rng,
+) -> DecisionRating {
+    let Some(left) = left.filter(|l| !l.is_empty()) else {
+        return DecisionRating {
+            chosen,
+            best: None,
+            random: None,
+        };
+    };
+    let (best, _) = mn.choose_mn(left, rng);
+    let random = (0..RANDOM_SEATINGS)
+        .map(|_| calc_entropy(&random_seating(len_a, len_b, rng), left))
+        .sum::<f64>()
+        / RANDOM_SEATINGS as f64;
+    DecisionRating {
+        chosen,
+        best: Some(best),
+        random: Some(random),
+    }
+}
+
+/// The entropy (in bits) of an event happening with the probability `p`
+fn binary_entropy(p: f64) -> f64 {
+    [p, 1.0 - p]
+        .into_iter()
+        .filter(|&p| p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+/// A random seating of `len_a` individuals with distinct partners from `0..len_b`
+fn random_seating(len_a: usize, len_b: usize, rng: &mut dyn Rng) -> MaskedMatching {
+    let mut bs = (0..len_b as IdBase).collect::<Vec<_>>();
+    bs.shuffle(rng);
+    bs.truncate(len_a);
+    MaskedMatching::from(&bs[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::solver::mb::optimal::OptimalMbOptimizer;
+    use crate::solver::mn::entropy_left::EntropyLeftMnOptimizer;
+
+    #[test]
+    fn rate_box_simple() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // A-a in 3 of 4 solutions, A-b in 1 of 4 and B-b in 2 of 4
+        let left = vec![
+            MaskedMatching::from(&[0, 1, 2][..]),
+            MaskedMatching::from(&[0, 2, 1][..]),
+            MaskedMatching::from(&[0, 1, 2][..]),
+            MaskedMatching::from(&[1, 0, 2][..]),
+        ];
+        let mut each = vec![vec![0; 3]; 3];
+        for p in &left {
+            p.count_pairs(&mut each);
+        }
+        let a_a = MaskedMatching::from_matching_ref(&[vec![0], vec![], vec![]]);
+        let chosen = calc_entropy(&a_a, &left);
+        let r = rate_box(chosen, &(each, 4), &OptimalMbOptimizer, &mut rng);
+        // the best box has a 50% chance
+        assert_eq!(r.best, Some(1.0));
+        assert!((r.chosen - 0.8112781244591328).abs() < 1e-9);
+        assert!((r.efficiency().unwrap() - 0.8112781244591328).abs() < 1e-9);
+        assert_eq!(r.random, None);
+        assert_eq!(r.to_string(), "81%");
+    }
+
+    #[test]
+    fn rate_night_simple() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let left = vec![
+            MaskedMatching::from(&[0, 1, 2][..]),
+            MaskedMatching::from(&[1, 2, 0][..]),
+            MaskedMatching::from(&[2, 0, 1][..]),
+        ];
+        // distinguishes all solutions
+        let mn = EntropyLeftMnOptimizer::new(10);
+        let m = MaskedMatching::from(&[0, 1, 2][..]);
+        let chosen = calc_entropy(&m, &left);
+        let r = rate_night(chosen, Some(&left), 3, 3, &mn, &mut rng);
+        assert_eq!(r.efficiency(), Some(1.0));
+        assert!(r.random.unwrap() <= r.best.unwrap());
+
+        // doesn't tell anything
+        let m = MaskedMatching::from(&[0, 2, 1][..]);
+        let chosen = calc_entropy(&m, &left);
+        let r = rate_night(chosen, Some(&left), 3, 3, &mn, &mut rng);
+        assert_eq!(r.chosen, 0.0);
+        assert_eq!(r.efficiency(), Some(0.0));
+
+        // the solutions left are unknown
+        let r = rate_night(0.5, None, 3, 3, &mn, &mut rng);
+        assert_eq!(
+            r,
+            DecisionRating {
+                chosen: 0.5,
+                best: None,
+                random: None,
+            }
+        );
+        assert_eq!(r.efficiency(), None);
+        assert_eq!(r.to_string(), "0.50 bit");
+    }
+
+    #[test]
+    fn efficiency_nothing_to_learn() {
+        let r = DecisionRating {
+            chosen: 0.0,
+            best: Some(0.0),
+            random: None,
+        };
+        assert_eq!(r.efficiency(), Some(1.0));
+    }
+}
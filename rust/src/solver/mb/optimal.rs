@@ -6,14 +6,14 @@
 //! Selects the optimal match to place in the Match-Box. The optimum is the match which is closest
 //! to 50% probability.
 
-use ayto::matching_repr::{IdBase, MaskedMatching};
 use rand::Rng;
 
-use crate::strategies::mb::MbOptimizer;
+use crate::matching_repr::{IdBase, MaskedMatching};
+use crate::solver::mb::MbOptimizer;
 
 /// Selects the optimal match to place in the Match-Box. The optimum is the match which is closest
 /// to 50% probability.
-pub(crate) struct OptimalMbOptimizer;
+pub struct OptimalMbOptimizer;
 
 impl MbOptimizer for OptimalMbOptimizer {
     fn choose_mb(&self, data: &[Vec<u128>], total: u128, _rng: &mut dyn Rng) -> MaskedMatching {
@@ -37,7 +37,7 @@ impl MbOptimizer for OptimalMbOptimizer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ayto::matching_repr::MaskedMatching;
+    use crate::matching_repr::MaskedMatching;
     use pretty_assertions::assert_eq;
     use rand::{rngs::StdRng, SeedableRng};
 
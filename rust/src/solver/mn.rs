@@ -4,13 +4,14 @@
 
 //! Optimize/Select a full matching to seat at the Matching-Night
 
-pub(crate) mod entropy_left;
+pub mod entropy_left;
 
-use ayto::matching_repr::MaskedMatching;
 use rand::Rng;
 
+use crate::matching_repr::MaskedMatching;
+
 /// Chooses an MN
-pub(crate) trait MnOptimizer: Send + Sync {
+pub trait MnOptimizer: Send + Sync {
     /// Come up with a full-matching for a matching-night according to the strategy
     /// also return the H (entropy)
     fn choose_mn(&self, left_poss: &[MaskedMatching], rng: &mut dyn Rng) -> (f64, MaskedMatching);
@@ -10,15 +10,15 @@
 //! But at least in the beginning even this is too expensive to calculate, so this list ist sampled
 //! randomly in case it exceeds a certain threshold (set when creating the stragety).
 
-use ayto::matching_repr::MaskedMatching;
 use rand::prelude::IndexedRandom;
 use rand::Rng;
 
-use crate::strategies::mn::MnOptimizer;
-use crate::utils::calc_entropy;
+use crate::matching_repr::MaskedMatching;
+use crate::solver::calc_entropy;
+use crate::solver::mn::MnOptimizer;
 
 /// Entropy (over left_poss) MN optimizer that picks the candidate maximizing entropy.
-pub(crate) struct EntropyLeftMnOptimizer {
+pub struct EntropyLeftMnOptimizer {
     /// sampling threshold for performance
     /// in case there are many possibilities left, don't use them all. Instead sample them randomly
     /// down to a threshold
@@ -30,7 +30,7 @@ impl EntropyLeftMnOptimizer {
     ///
     /// - if the amount of left possibilities exceeds `sample_threshold`, the list of left
     ///   possibilities will be sampled randomly (with `sample_threshold` as size of the sample)
-    pub(crate) fn new(sample_threshold: usize) -> Self {
+    pub fn new(sample_threshold: usize) -> Self {
         Self { sample_threshold }
     }
 }
@@ -61,7 +61,7 @@ impl MnOptimizer for EntropyLeftMnOptimizer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ayto::matching_repr::{IdBase, MaskedMatching};
+    use crate::matching_repr::{IdBase, MaskedMatching};
     use pretty_assertions::assert_eq;
     use rand::rngs::StdRng;
     use rand::SeedableRng;
@@ -0,0 +1,362 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module estimates how many further events (match boxes and matching nights) are needed to
+//! solve a season, i.e. until only a single solution is left.
+//!
+//! For this the play of the solver is simulated starting with the solutions left: each of these
+//! solutions (or a random sample of them) is assumed to be the true solution and the strategies
+//! choose the events until it is found. The average and the maximal amount of events needed are
+//! reported. Additionally the share of solutions which are seated in a matching night before the
+//! nights run out is reported as the probability to win the game.
+//!
+//! To know the solutions left after each event of a season, the permutations eliminated by the
+//! last events are kept during the simulation of the season (see [`SurvivorTrail`]).
+
+use std::fmt;
+
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::matching_repr::MaskedMatching;
+use crate::solver::mb::MbOptimizer;
+use crate::solver::mn::MnOptimizer;
+
+/// the maximal amount of solutions left for which the remaining events are estimated (the
+/// strategies get too slow with more solutions)
+pub const MAX_LEFT: usize = 20_000;
+
+/// the maximal amount of events simulated for a single solution (the strategies might not make
+/// progress in some cases, e.g. with dups)
+const MAX_EVENTS: usize = 64;
+
+/// The amount of events still needed to solve a season
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RemainingEvents {
+    /// the average amount of events needed
+    pub expected: f64,
+    /// the maximal amount of events needed
+    pub worst: usize,
+    /// for how many solutions the events were simulated
+    pub sampled: usize,
+    /// the share of the solutions which were seated in one of the nights left (the probability to
+    /// win the game)
+    pub win: f64,
+}
+
+impl fmt::Display for RemainingEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}/{}", self.expected, self.worst)
+    }
+}
+
+/// The course of a simulated play for a single solution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Play {
+    /// after how many events only the solution was left
+    pub events: usize,
+    /// in which matching night the solution was seated (`None` if this did not happen)
+    pub nights: Option<usize>,
+}
+
+/// Simulate how the strategies `mb` and `mn` find `solution` among the solutions `left` (the
+/// elements of set_b are in `0..len_b`). The first event is a match box if `next_box` is set,
+/// afterwards matching nights and match boxes alternate. The play ends once the solution is seated
+/// in a matching night.
+pub fn play<S: MbOptimizer, T: MnOptimizer>(
+    left: &[MaskedMatching],
+    solution: &MaskedMatching,
+    len_b: usize,
+    (mb, mn): (&S, &T),
+    next_box: bool,
+    rng: &mut dyn Rng,
+) -> Play {
+    let mut poss = left.to_vec();
+    let mut is_box = next_box;
+    let mut events = 0;
+    let mut nights = 0;
+    let mut solved = (poss.len() <= 1).then_some(0);
+    // the lights of a night in which the solution is seated
+    let win_lights = solution.calculate_lights(solution);
+    while events < MAX_EVENTS && !poss.is_empty() {
+        let m = if is_box {
+            let mut each = vec![vec![0; len_b]; solution.len()];
+            for p in &poss {
+                p.count_pairs(&mut each);
+            }
+            mb.choose_mb(&each, poss.len() as u128, rng)
+        } else {
+            mn.choose_mn(&poss, rng).1
+        };
+        let lights = m.calculate_lights(solution);
+        poss.retain(|p| m.calculate_lights(p) == lights);
+
+        events += 1;
+        if poss.len() <= 1 {
+            solved.get_or_insert(events);
+        }
+        if !is_box {
+            nights += 1;
+            if lights == win_lights {
+                return Play {
+                    events: solved.unwrap_or(events),
+                    nights: Some(nights),
+                };
+            }
+        }
+        is_box = !is_box;
+    }
+    Play {
+        events: solved.unwrap_or(events),
+        nights: None,
+    }
+}
+
+/// Estimate how many events the strategies `mb` and `mn` need to solve a season with the solutions
+/// `left` and how likely the game is won within `nights_left` matching nights (see [`play`]). At
+/// most `samples` solutions are simulated, they are sampled reproducibly. Returns `None` if no
+/// solution is left.
+pub fn estimate<S: MbOptimizer, T: MnOptimizer>(
+    left: &[MaskedMatching],
+    len_b: usize,
+    strategy: (&S, &T),
+    next_box: bool,
+    nights_left: usize,
+    samples: usize,
+) -> Option<RemainingEvents> {
+    let solutions = if left.len() > samples {
+        left.sample(&mut StdRng::seed_from_u64(0), samples)
+            .collect::<Vec<_>>()
+    } else {
+        left.iter().collect::<Vec<_>>()
+    };
+    if solutions.is_empty() {
+        return None;
+    }
+
+    let plays = solutions
+        .par_iter()
+        .enumerate()
+        .map(|(i, sol)| {
+            let mut rng = StdRng::seed_from_u64(i as u64);
+            play(left, sol, len_b, strategy, next_box, &mut rng)
+        })
+        .collect::<Vec<_>>();
+    let won = plays
+        .iter()
+        .filter(|p| p.nights.is_some_and(|n| n <= nights_left))
+        .count();
+    Some(RemainingEvents {
+        expected: plays.iter().map(|p| p.events).sum::<usize>() as f64 / plays.len() as f64,
+        worst: plays.iter().map(|p| p.events).max().unwrap_or(0),
+        sampled: plays.len(),
+        win: won as f64 / plays.len() as f64,
+    })
+}
+
+/// Keeps the permutations eliminated by the last constraints of a simulation, so the solutions left
+/// after each of these constraints can be reconstructed.
+///
+/// The solutions left after the constraint `i` are the permutations which are eliminated first by
+/// a later constraint plus the ones which survive all constraints. At most `cap` permutations are
+/// kept, if there are more the ones eliminated by the earliest constraints are dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurvivorTrail {
+    /// the maximal amount of permutations kept
+    cap: usize,
+    /// the permutations by the index of the constraint which eliminated them first (the last
+    /// bucket contains the permutations which survived all constraints)
+    buckets: Vec<Vec<MaskedMatching>>,
+    /// the buckets with a lower index were dropped
+    first_kept: usize,
+    /// the amount of permutations kept
+    len: usize,
+}
+
+impl SurvivorTrail {
+    /// A trail for a simulation with `constraints` constraints keeping at most `cap` permutations
+    pub fn new(constraints: usize, cap: usize) -> Self {
+        SurvivorTrail {
+            cap,
+            buckets: vec![vec![]; constraints + 1],
+            first_kept: 0,
+            len: 0,
+        }
+    }
+
+    /// Record that `p` was eliminated first by the constraint with the index `idx` (use the amount
+    /// of constraints if `p` survived all constraints)
+    pub fn push(&mut self, idx: usize, p: &MaskedMatching) {
+        if idx < self.first_kept || idx >= self.buckets.len() {
+            return;
+        }
+        self.buckets[idx].push(p.clone());
+        self.len += 1;
+        while self.len > self.cap && self.first_kept < self.buckets.len() {
+            self.len -= std::mem::take(&mut self.buckets[self.first_kept]).len();
+            self.first_kept += 1;
+        }
+    }
+
+    /// Drop the permutations eliminated by the constraints before the index `idx` and don't keep
+    /// any further ones. Use this if the permutations of the simulation were read from a cache
+    /// which already contains the effect of these constraints, so the solutions left before them
+    /// are unknown.
+    pub fn forget_before(&mut self, idx: usize) {
+        let idx = idx.min(self.buckets.len());
+        while self.first_kept < idx {
+            self.len -= std::mem::take(&mut self.buckets[self.first_kept]).len();
+            self.first_kept += 1;
+        }
+    }
+
+    /// The solutions left before the constraint with the index `idx`. `None` if they were not kept.
+    pub fn left_before(&self, idx: usize) -> Option<Vec<MaskedMatching>> {
+        if idx < self.first_kept || idx >= self.buckets.len() {
+            return None;
+        }
+        Some(self.buckets[idx..].iter().flatten().cloned().collect())
+    }
+
+    /// The solutions left after the constraint with the index `idx`. `None` if they were not kept.
+    pub fn left_after(&self, idx: usize) -> Option<Vec<MaskedMatching>> {
+        self.left_before(idx + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::matching_repr::IdBase;
+    use crate::solver::mb::optimal::OptimalMbOptimizer;
+    use crate::solver::mn::entropy_left::EntropyLeftMnOptimizer;
+
+    /// all permutations of `0..n`
+    fn perms(n: IdBase) -> Vec<MaskedMatching> {
+        if n == 0 {
+            return vec![MaskedMatching::from(&[][..])];
+        }
+        let mut out = vec![];
+        for p in perms(n - 1) {
+            let p = p
+                .iter()
+                .map(|b| b.iter().next().unwrap())
+                .collect::<Vec<_>>();
+            for i in 0..=p.len() {
+                let mut q = p.clone();
+                q.insert(i, n - 1);
+                out.push(MaskedMatching::from(&q[..]));
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn play_simple() {
+        let strategy = (&OptimalMbOptimizer, &EntropyLeftMnOptimizer::new(100));
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // already solved, only needs to be seated
+        let left = perms(3)[..1].to_vec();
+        assert_eq!(
+            play(&left, &left[0], 3, strategy, true, &mut rng),
+            Play {
+                events: 0,
+                nights: Some(1)
+            }
+        );
+
+        // every solution is found and seated eventually
+        let left = perms(4);
+        for sol in &left {
+            let p = play(&left, sol, 4, strategy, false, &mut rng);
+            assert!((1..=6).contains(&p.events), "{p:?}");
+            assert!(p.nights.is_some_and(|n| n <= p.events), "{p:?}");
+        }
+    }
+
+    #[test]
+    fn estimate_simple() {
+        let strategy = (&OptimalMbOptimizer, &EntropyLeftMnOptimizer::new(100));
+        assert_eq!(estimate(&[], 4, strategy, true, 10, 10), None);
+
+        let left = perms(4);
+        let all = estimate(&left, 4, strategy, true, 10, 100).unwrap();
+        assert_eq!(all.sampled, 24);
+        assert!(all.expected > 1.0 && all.expected <= all.worst as f64);
+        assert_eq!(all.win, 1.0);
+        // no night left -> can't be won
+        assert_eq!(estimate(&left, 4, strategy, true, 0, 100).unwrap().win, 0.0);
+        // a single night -> only won by luck
+        let one = estimate(&left, 4, strategy, false, 1, 100).unwrap();
+        assert_eq!(one.win, 1.0 / 24.0);
+
+        let some = estimate(&left, 4, strategy, true, 10, 5).unwrap();
+        assert_eq!(some.sampled, 5);
+        // reproducible
+        assert_eq!(estimate(&left, 4, strategy, true, 10, 5).unwrap(), some);
+
+        assert_eq!(
+            RemainingEvents {
+                expected: 2.25,
+                worst: 4,
+                sampled: 4,
+                win: 0.5,
+            }
+            .to_string(),
+            "2.2/4"
+        );
+    }
+
+    #[test]
+    fn survivor_trail_simple() {
+        let m = |x: IdBase| MaskedMatching::from(&[x][..]);
+        let mut t = SurvivorTrail::new(3, 3);
+        t.push(2, &m(2));
+        t.push(3, &m(3));
+        t.push(1, &m(1));
+        assert_eq!(t.left_after(0), Some(vec![m(1), m(2), m(3)]));
+        assert_eq!(t.left_after(1), Some(vec![m(2), m(3)]));
+        assert_eq!(t.left_after(2), Some(vec![m(3)]));
+        assert_eq!(t.left_after(3), None);
+        assert_eq!(t.left_before(0), Some(vec![m(1), m(2), m(3)]));
+        assert_eq!(t.left_before(3), Some(vec![m(3)]));
+
+        // exceeds the cap -> the buckets of the first constraints are dropped
+        t.push(0, &m(0));
+        assert_eq!(t.left_after(0), Some(vec![m(1), m(2), m(3)]));
+        t.push(2, &m(4));
+        assert_eq!(t.left_after(0), None);
+        assert_eq!(t.left_before(1), None);
+        assert_eq!(t.left_after(1), Some(vec![m(2), m(4), m(3)]));
+        // buckets already dropped stay dropped
+        t.push(1, &m(1));
+        assert_eq!(t.left_after(0), None);
+        assert_eq!(t.left_after(1), Some(vec![m(2), m(4), m(3)]));
+    }
+
+    #[test]
+    fn survivor_trail_forget() {
+        let m = |x: IdBase| MaskedMatching::from(&[x][..]);
+        let mut t = SurvivorTrail::new(3, 10);
+        t.push(0, &m(0));
+        t.push(2, &m(2));
+        t.push(3, &m(3));
+        t.forget_before(2);
+        assert_eq!(t.left_before(1), None);
+        assert_eq!(t.left_after(0), None);
+        assert_eq!(t.left_after(1), Some(vec![m(2), m(3)]));
+        // not kept anymore
+        t.push(1, &m(1));
+        assert_eq!(t.left_before(1), None);
+        // forgetting more than all constraints leaves nothing
+        t.forget_before(10);
+        assert_eq!(t.left_before(3), None);
+    }
+}
@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module offers a pull-based alternative to [`crate::iterstate::IterState`]: the matchings
+//! which survive a set of constraints are yielded lazily by an [`Iterator`].
+//!
+//! The permutations are still generated by [`RuleSet::iter_perms`] (or read from a cache), but on
+//! a background thread. The survivors are passed over a bounded channel in chunks, so only a few
+//! chunks are held in memory at any time no matter how many matchings survive. If the iterator is
+//! dropped early, the generation is stopped (even if no further matchings survive) and the
+//! background thread is joined.
+//!
+//! As [`Survivors`] is [`Send`], it can be turned into a parallel iterator via rayon's
+//! `par_bridge`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::constraint::Constraint;
+use crate::iterstate::IterStateTrait;
+use crate::matching_repr::MaskedMatching;
+use crate::ruleset::RuleSet;
+use crate::Lut;
+
+/// the amount of survivors sent over the channel at once
+const CHUNK_SIZE: usize = 1024;
+/// the amount of chunks which might be buffered in the channel
+const CHUNKS_BUFFERED: usize = 16;
+
+/// Collects the survivors on the generating side and sends them over the channel in chunks
+struct ChannelSink {
+    /// the constraints a matching has to fulfill to survive
+    constraints: Vec<Constraint>,
+    /// the survivors which were not sent yet
+    buf: Vec<MaskedMatching>,
+    /// where to send the survivors to
+    tx: SyncSender<Result<Vec<MaskedMatching>>>,
+    /// set once the survivors are not needed anymore
+    stop: Arc<AtomicBool>,
+}
+
+impl ChannelSink {
+    /// send the buffered survivors, fails if the receiving side was dropped
+    fn flush(&mut self) -> Result<()> {
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        self.tx
+            .send(Ok(chunk))
+            .map_err(|_| anyhow!("the receiver of the survivors was dropped"))
+    }
+}
+
+impl IterStateTrait for ChannelSink {
+    fn start(&mut self) {}
+
+    fn finish(&mut self) {
+        // if the receiver is gone nobody is interested in the rest anyways
+        let _ = self.flush();
+    }
+
+    fn step(&mut self, _i: usize, p: &MaskedMatching) -> Result<()> {
+        if self.stop.load(Ordering::Relaxed) {
+            bail!("the generation of the survivors was stopped");
+        }
+        if self.constraints.iter_mut().all(|c| c.admits(p)) {
+            self.buf.push(p.clone());
+            if self.buf.len() >= CHUNK_SIZE {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A lazy iterator over the matchings which survive a set of constraints.
+///
+/// Errors during the generation (e.g. an unreadable cache) end the iteration early. Use
+/// [`Survivors::finish`] to check whether all survivors were yielded.
+pub struct Survivors {
+    /// where the chunks of survivors are received from
+    rx: Receiver<Result<Vec<MaskedMatching>>>,
+    /// the chunk which is currently yielded
+    chunk: std::vec::IntoIter<MaskedMatching>,
+    /// the thread generating the survivors
+    handle: Option<JoinHandle<()>>,
+    /// tells the generating thread to stop
+    stop: Arc<AtomicBool>,
+    /// the error which ended the generation (if any)
+    err: Option<anyhow::Error>,
+}
+
+impl Survivors {
+    /// Start generating the permutations of `rule_set` (or reading them from `cache` if set) and
+    /// yield the ones fulfilling all `constraints`
+    pub fn new(
+        rule_set: RuleSet,
+        (lut_a, lut_b): (Lut, Lut),
+        constraints: Vec<Constraint>,
+        cache: Option<PathBuf>,
+    ) -> Self {
+        let (tx, rx) = sync_channel(CHUNKS_BUFFERED);
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_sink = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut sink = ChannelSink {
+                constraints,
+                buf: Vec::with_capacity(CHUNK_SIZE),
+                tx: tx.clone(),
+                stop: stop_sink,
+            };
+            if let Err(e) = rule_set.iter_perms(&lut_a, &lut_b, &mut sink, &cache) {
+                // fails only if the receiver was dropped, then there is no one to tell anyways
+                let _ = tx.send(Err(e));
+            }
+        });
+        Survivors {
+            rx,
+            chunk: Vec::new().into_iter(),
+            handle: Some(handle),
+            stop,
+            err: None,
+        }
+    }
+
+    /// Stop the iteration and check whether the generation was successful. Returns the error
+    /// which ended the iteration early (if any), also if it was already sent but not yet reached
+    /// by the iteration.
+    ///
+    /// Note that `Ok` after the iteration was stopped early does not mean all survivors were
+    /// yielded, the rest of them is simply not generated anymore.
+    pub fn finish(mut self) -> Result<()> {
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        // an error might be waiting behind the chunks which were not consumed (only look at what
+        // is pending, the generation is not waited for)
+        for item in self.rx.try_iter().take(CHUNKS_BUFFERED + 1) {
+            item?;
+        }
+        self.join()
+    }
+
+    /// stop the generation and wait for the generating thread to end
+    fn join(&mut self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        // a generation blocked on sending notices that the receiver is gone
+        let rx = std::mem::replace(&mut self.rx, sync_channel(0).1);
+        drop(rx);
+        match self.handle.take().map(|h| h.join()) {
+            Some(Err(_)) => Err(anyhow!("generating the survivors panicked")),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for Survivors {
+    fn drop(&mut self) {
+        // nobody is interested in the result anymore
+        let _ = self.join();
+    }
+}
+
+impl Iterator for Survivors {
+    type Item = MaskedMatching;
+
+    fn next(&mut self) -> Option<MaskedMatching> {
+        loop {
+            if let Some(m) = self.chunk.next() {
+                return Some(m);
+            }
+            match self.rx.recv() {
+                Ok(Ok(chunk)) => self.chunk = chunk.into_iter(),
+                Ok(Err(e)) => {
+                    self.err = Some(e);
+                    return None;
+                }
+                // the generation is done
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rayon::prelude::*;
+
+    use std::io::Write;
+
+    use crate::game::builder::{box_night, GameBuilder};
+    use crate::game::Game;
+    use crate::iterstate::IterState;
+    use crate::progressbar::MockProgressBar;
+
+    /// a game with 5 survivors out of 24 permutations
+    fn game() -> Game {
+        box_night().build().unwrap()
+    }
+
+    #[test]
+    fn survivors_same_as_sim() -> Result<()> {
+        let mut g = game();
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+
+        let mut s = g.survivors();
+        let survivors = s.by_ref().collect::<Vec<_>>();
+        s.finish()?;
+        assert_eq!(survivors, is.left_poss.to_vec()?);
+        assert_eq!(survivors.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn survivors_parallel() {
+        let cnt = game()
+            .survivors()
+            .par_bridge()
+            .filter(|m| m.slot_mask(0).is_some())
+            .count();
+        assert_eq!(cnt, 5);
+    }
+
+    #[test]
+    fn survivors_many() -> Result<()> {
+        // more survivors than fit into a single chunk, stop early
+        let names = (0..8).map(|i| i.to_string()).collect::<Vec<_>>();
+        let g = GameBuilder::new(names.clone(), names).build()?;
+        let mut s = g.survivors();
+        assert_eq!(s.by_ref().take(CHUNK_SIZE + 5).count(), CHUNK_SIZE + 5);
+        s.finish()?;
+
+        assert_eq!(g.survivors().count(), 40320);
+        Ok(())
+    }
+
+    #[test]
+    fn survivors_dropped() -> Result<()> {
+        // no matching survives, without stopping all 12! permutations would be generated
+        let names = (0..12).map(|i| i.to_string()).collect::<Vec<_>>();
+        let g = GameBuilder::new(names.clone(), names)
+            .box_(1, [("0", "0")], 1)
+            .box_(2, [("0", "0")], 0)
+            .build()?;
+        let s = g.survivors();
+        let stop = Arc::clone(&s.stop);
+        let start = std::time::Instant::now();
+        drop(s);
+        assert!(stop.load(Ordering::Relaxed));
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+        Ok(())
+    }
+
+    #[test]
+    fn survivors_cache() -> Result<()> {
+        let mut g = game();
+        let is: IterState<MockProgressBar, _> = g.sim(None)?;
+        let (lut_a, lut_b) = g.luts();
+        let luts = (lut_a.clone(), lut_b.clone());
+
+        let mut cache = tempfile::NamedTempFile::new()?;
+        for m in is.left_poss.iter() {
+            writeln!(cache, "{}", serde_json::to_string(&m?)?)?;
+        }
+        let s = Survivors::new(
+            g.rule_set.clone(),
+            luts.clone(),
+            g.constraints_orig.clone(),
+            Some(cache.path().to_path_buf()),
+        );
+        assert_eq!(s.collect::<Vec<_>>(), is.left_poss.to_vec()?);
+
+        // unreadable cache
+        let mut s = Survivors::new(
+            g.rule_set.clone(),
+            luts.clone(),
+            g.constraints_orig.clone(),
+            Some(PathBuf::from("/nonexistent/cache")),
+        );
+        assert_eq!(s.next(), None);
+        assert!(s.finish().is_err());
+
+        // the error is reported even if the iteration never reached it
+        let mut cache = tempfile::NamedTempFile::new()?;
+        writeln!(cache, "no matching")?;
+        let s = Survivors::new(
+            g.rule_set.clone(),
+            luts,
+            g.constraints_orig.clone(),
+            Some(cache.path().to_path_buf()),
+        );
+        while !s.handle.as_ref().is_some_and(|h| h.is_finished()) {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(s.finish().is_err());
+        Ok(())
+    }
+}
@@ -10,29 +10,31 @@ use std::collections::HashSet;
 use std::io::Write;
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::matching_repr::bitset::Bitset;
-use crate::matching_repr::{IdBase, MaskedMatching};
+use crate::matching_repr::IdBase;
+use crate::solution_store::SolutionStore;
 use crate::Lut;
 
 /// Parsing struct for [`TreeConfig`], can be converted to this via the [`TreeConfigParse::finalize`] function
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub(crate) struct TreeConfigParse {
     /// an id which can be used as a component in the filename
     id: String,
     /// something which can be shown as part of the title in the image
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     title: String,
     /// these individuals from set_b will be ignored when drawing this tree
-    #[serde(default, rename = "ignoreB")]
+    #[serde(default, rename = "ignoreB", skip_serializing_if = "Vec::is_empty")]
     ignore_b: Vec<String>,
     /// the layers associated with these individuals from set_a will be moved up right below the
     /// layers where the PM has already been found. The order specified here will be the order in
     /// the output.
     /// Especially useful when using ignore_b as tree_ordering is determined on the unfiltered
     /// matchings
-    #[serde(default, rename = "moveUpA")]
+    #[serde(default, rename = "moveUpA", skip_serializing_if = "Vec::is_empty")]
     move_up_a: Vec<String>,
 }
 
@@ -110,7 +112,7 @@ impl TreeConfig {
     pub(crate) fn dot_tree<W: Write>(
         &self,
         writer: &mut W,
-        data: &[MaskedMatching],
+        data: &SolutionStore,
         ordering: &[(IdBase, usize)],
         title: &str,
         map_a: &[String],
@@ -126,7 +128,8 @@ impl TreeConfig {
 
         let mut builder = DotBuilder::new(writer, map_a, map_b);
 
-        for p in data {
+        for p in data.iter() {
+            let p = p?;
             let mut parent = "root".to_owned();
             for &(i, _) in ordering.iter() {
                 let mut mask = *p
@@ -236,11 +239,14 @@ fn write_footer<W: Write>(writer: &mut W) -> Result<()> {
 /// The layers are ordered so the amount of outgoing edges of a (complete) layer is minimized.
 ///
 /// Returns a sorted list of layers to be drawn: [(id in set_a, num outgoing edges)]
-pub(crate) fn tree_ordering(data: &[MaskedMatching], map_a: &[String]) -> Vec<(IdBase, usize)> {
+pub(crate) fn tree_ordering(
+    data: &SolutionStore,
+    map_a: &[String],
+) -> Result<Vec<(IdBase, usize)>> {
     // tab maps people from set_a -> possible matches (set -> no duplicates)
     let mut tab = vec![HashSet::new(); map_a.len()];
-    for p in data {
-        for (i, js) in p.iter().enumerate() {
+    for p in data.iter() {
+        for (i, js) in p?.iter().enumerate() {
             tab[i].insert(js.as_word());
         }
     }
@@ -259,7 +265,7 @@ pub(crate) fn tree_ordering(data: &[MaskedMatching], map_a: &[String]) -> Vec<(I
         .collect();
 
     ordering.sort_unstable_by_key(|(_, x)| *x);
-    ordering
+    Ok(ordering)
 }
 
 /// adjust the ordering so the ids are moved up.
@@ -411,14 +417,15 @@ mod tests {
     }
 
     #[test]
-    fn tree_ordering_returns_expected_pairs() {
+    fn tree_ordering_returns_expected_pairs() -> Result<()> {
         let (data, map_a, _) = fixture_data();
 
-        let ordering = tree_ordering(&data, &map_a);
+        let ordering = tree_ordering(&data.into(), &map_a)?;
 
         // node-id 0 has one instance
         // node-id 1 has two instances
         assert_eq!(ordering, vec![(0, 1), (1, 2)]);
+        Ok(())
     }
 
     #[test]
@@ -487,11 +494,11 @@ mod tests {
 
     #[test]
     fn dot_tree_produces_complete_dot_output() -> Result<()> {
-        let data = vec![
+        let data = SolutionStore::from(vec![
             MaskedMatching::from_matching_ref(&[vec![3], vec![0, 1], vec![2]]),
             MaskedMatching::from_matching_ref(&[vec![3], vec![2], vec![0, 1]]),
             MaskedMatching::from_matching_ref(&[vec![3], vec![4], vec![0, 1]]),
-        ];
+        ]);
 
         let map_a = vec!["A", "B", "C"]
             .into_iter()
@@ -502,7 +509,7 @@ mod tests {
             .map(|x| x.to_string())
             .collect::<Vec<_>>();
 
-        let ordering = tree_ordering(&data, &map_a);
+        let ordering = tree_ordering(&data, &map_a)?;
 
         println!("{:?}", ordering);
 
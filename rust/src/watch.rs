@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: 2026 Lukas Heindl
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! This module allows to watch a game-config for changes, so the simulation can be re-run
+//! automatically while editing it.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// how long to wait for further events before handling a change. Editors often write a file in
+/// multiple steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// whether `ev` changes the content of the file at `path`
+fn affects(ev: &Event, path: &Path) -> bool {
+    matches!(ev.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && ev.paths.iter().any(|p| p == path)
+}
+
+/// Call `on_change` once and then again every time the file at `path` changes. This never
+/// returns unless watching fails.
+///
+/// The directory containing the file is watched instead of the file itself, as lots of editors
+/// replace the file on save (which would end a watch on the file).
+pub fn watch_file(path: &Path, mut on_change: impl FnMut()) -> Result<()> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("{} not found", path.display()))?;
+    let dir = path.parent().context("parent dir of the file not found")?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    on_change();
+    loop {
+        if !affects(&rx.recv()??, &path) {
+            continue;
+        }
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+        on_change();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+    use std::path::PathBuf;
+
+    #[test]
+    fn affects_simple() {
+        let path = PathBuf::from("/tmp/x.yaml");
+        let ev = |kind, p: &str| Event::new(kind).add_path(PathBuf::from(p));
+
+        assert!(affects(
+            &ev(EventKind::Modify(ModifyKind::Any), "/tmp/x.yaml"),
+            &path
+        ));
+        assert!(affects(
+            &ev(EventKind::Create(CreateKind::File), "/tmp/x.yaml"),
+            &path
+        ));
+        assert!(!affects(
+            &ev(EventKind::Modify(ModifyKind::Any), "/tmp/x.md"),
+            &path
+        ));
+        assert!(!affects(
+            &ev(EventKind::Access(AccessKind::Any), "/tmp/x.yaml"),
+            &path
+        ));
+    }
+}